@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS sync_checkpoints
+(
+    contract_address     TEXT   NOT NULL,
+    stream               TEXT   NOT NULL,
+    last_processed_block BIGINT NOT NULL,
+    updated_at           TEXT   NOT NULL,
+    PRIMARY KEY (contract_address, stream)
+);
@@ -0,0 +1,31 @@
+DROP TABLE IF EXISTS indexed_blocks;
+
+ALTER TABLE authenticity_settings
+    DROP COLUMN IF EXISTS log_index,
+    DROP COLUMN IF EXISTS block_hash,
+    DROP COLUMN IF EXISTS block_number;
+
+ALTER TABLE ownership_claims
+    DROP COLUMN IF EXISTS log_index,
+    DROP COLUMN IF EXISTS block_hash,
+    DROP COLUMN IF EXISTS block_number;
+
+ALTER TABLE items
+    DROP COLUMN IF EXISTS log_index,
+    DROP COLUMN IF EXISTS block_hash,
+    DROP COLUMN IF EXISTS block_number;
+
+ALTER TABLE manufacturers
+    DROP COLUMN IF EXISTS log_index,
+    DROP COLUMN IF EXISTS block_hash,
+    DROP COLUMN IF EXISTS block_number;
+
+ALTER TABLE users_info
+    DROP COLUMN IF EXISTS log_index,
+    DROP COLUMN IF EXISTS block_hash,
+    DROP COLUMN IF EXISTS block_number;
+
+ALTER TABLE contracts
+    DROP COLUMN IF EXISTS log_index,
+    DROP COLUMN IF EXISTS block_hash,
+    DROP COLUMN IF EXISTS block_number;
@@ -0,0 +1,38 @@
+ALTER TABLE contracts
+    ADD COLUMN IF NOT EXISTS block_number BIGINT,
+    ADD COLUMN IF NOT EXISTS block_hash   TEXT,
+    ADD COLUMN IF NOT EXISTS log_index    BIGINT;
+
+ALTER TABLE users_info
+    ADD COLUMN IF NOT EXISTS block_number BIGINT,
+    ADD COLUMN IF NOT EXISTS block_hash   TEXT,
+    ADD COLUMN IF NOT EXISTS log_index    BIGINT;
+
+ALTER TABLE manufacturers
+    ADD COLUMN IF NOT EXISTS block_number BIGINT,
+    ADD COLUMN IF NOT EXISTS block_hash   TEXT,
+    ADD COLUMN IF NOT EXISTS log_index    BIGINT;
+
+ALTER TABLE items
+    ADD COLUMN IF NOT EXISTS block_number BIGINT,
+    ADD COLUMN IF NOT EXISTS block_hash   TEXT,
+    ADD COLUMN IF NOT EXISTS log_index    BIGINT;
+
+ALTER TABLE ownership_claims
+    ADD COLUMN IF NOT EXISTS block_number BIGINT,
+    ADD COLUMN IF NOT EXISTS block_hash   TEXT,
+    ADD COLUMN IF NOT EXISTS log_index    BIGINT;
+
+ALTER TABLE authenticity_settings
+    ADD COLUMN IF NOT EXISTS block_number BIGINT,
+    ADD COLUMN IF NOT EXISTS block_hash   TEXT,
+    ADD COLUMN IF NOT EXISTS log_index    BIGINT;
+
+CREATE TABLE IF NOT EXISTS indexed_blocks
+(
+    contract_address TEXT   NOT NULL,
+    stream           TEXT   NOT NULL,
+    block_number     BIGINT NOT NULL,
+    block_hash       TEXT   NOT NULL,
+    PRIMARY KEY (contract_address, stream, block_number)
+);
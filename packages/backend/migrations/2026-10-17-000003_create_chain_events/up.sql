@@ -0,0 +1,16 @@
+CREATE TABLE IF NOT EXISTS chain_events
+(
+    id               BIGSERIAL PRIMARY KEY,
+    contract_address TEXT   NOT NULL,
+    event_name       TEXT   NOT NULL,
+    block_number     BIGINT NOT NULL,
+    block_hash       TEXT   NOT NULL,
+    log_index        BIGINT NOT NULL,
+    tnx_hash         TEXT   NOT NULL,
+    payload          JSONB  NOT NULL,
+    created_at       TEXT   NOT NULL,
+    UNIQUE (tnx_hash, log_index)
+);
+
+CREATE INDEX IF NOT EXISTS chain_events_contract_block_idx
+    ON chain_events (contract_address, block_number, log_index);
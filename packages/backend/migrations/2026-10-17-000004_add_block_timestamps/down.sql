@@ -0,0 +1 @@
+ALTER TABLE chain_events DROP COLUMN IF EXISTS block_timestamp;
@@ -0,0 +1,2 @@
+-- Block time of each stored event, so replays do not need to fetch headers again
+ALTER TABLE chain_events ADD COLUMN IF NOT EXISTS block_timestamp TEXT;
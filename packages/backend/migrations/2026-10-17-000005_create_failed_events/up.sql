@@ -0,0 +1,22 @@
+-- Logs whose projection failed; the listener skips them and a background job retries them
+CREATE TABLE IF NOT EXISTS failed_events
+(
+    id               BIGSERIAL PRIMARY KEY,
+    contract_address TEXT    NOT NULL,
+    stream           TEXT    NOT NULL,
+    event_name       TEXT    NOT NULL,
+    block_number     BIGINT  NOT NULL,
+    log_index        BIGINT  NOT NULL,
+    tnx_hash         TEXT    NOT NULL,
+    raw_log          JSONB   NOT NULL,
+    error            TEXT    NOT NULL,
+    attempts         INTEGER NOT NULL,
+    status           TEXT    NOT NULL,
+    next_retry_at    TEXT    NOT NULL,
+    created_at       TEXT    NOT NULL,
+    updated_at       TEXT    NOT NULL,
+    UNIQUE (tnx_hash, log_index)
+);
+
+CREATE INDEX IF NOT EXISTS failed_events_status_retry_idx
+    ON failed_events (status, next_retry_at);
@@ -0,0 +1 @@
+DROP TABLE IF EXISTS eip712_domains;
@@ -0,0 +1,21 @@
+-- Every EIP-712 domain the authenticity contract has signed under. Exactly one domain per
+-- contract is active; older ones are kept so certificates signed under them still verify.
+CREATE TABLE IF NOT EXISTS eip712_domains
+(
+    id                 BIGSERIAL PRIMARY KEY,
+    contract_address   TEXT    NOT NULL,
+    name               TEXT    NOT NULL,
+    version            TEXT    NOT NULL,
+    chain_id           TEXT    NOT NULL,
+    verifying_contract TEXT    NOT NULL,
+    is_active          BOOLEAN NOT NULL,
+    block_number       BIGINT  NOT NULL,
+    activated_block    BIGINT  NOT NULL,
+    tnx_hash           TEXT    NOT NULL,
+    created_at         TEXT    NOT NULL,
+    activated_at       TEXT    NOT NULL,
+    UNIQUE (contract_address, name, version, chain_id, verifying_contract)
+);
+
+CREATE INDEX IF NOT EXISTS eip712_domains_contract_active_idx
+    ON eip712_domains (contract_address, is_active);
@@ -0,0 +1,23 @@
+DROP INDEX IF EXISTS ownership_claims_deployment_idx;
+
+ALTER TABLE items DROP CONSTRAINT IF EXISTS items_deployment_item_id_key;
+ALTER TABLE items ADD CONSTRAINT items_item_id_key UNIQUE (item_id);
+
+ALTER TABLE manufacturers DROP CONSTRAINT IF EXISTS manufacturers_deployment_address_key;
+ALTER TABLE manufacturers ADD PRIMARY KEY (manufacturer_address);
+
+ALTER TABLE users_info DROP CONSTRAINT IF EXISTS users_info_deployment_address_key;
+ALTER TABLE users_info ADD PRIMARY KEY (user_address);
+
+ALTER TABLE contracts DROP CONSTRAINT IF EXISTS contracts_deployment_address_key;
+ALTER TABLE contracts ADD PRIMARY KEY (contract_address);
+
+ALTER TABLE eip712_domains DROP COLUMN IF EXISTS deployment_id;
+ALTER TABLE authenticity_settings DROP COLUMN IF EXISTS deployment_id;
+ALTER TABLE ownership_claims DROP COLUMN IF EXISTS deployment_id;
+ALTER TABLE items DROP COLUMN IF EXISTS deployment_id;
+ALTER TABLE manufacturers DROP COLUMN IF EXISTS deployment_id;
+ALTER TABLE users_info DROP COLUMN IF EXISTS deployment_id;
+ALTER TABLE contracts DROP COLUMN IF EXISTS deployment_id;
+
+DROP TABLE IF EXISTS deployments;
@@ -0,0 +1,59 @@
+-- Contract pairs the indexer follows. The active pair is the one the API sends transactions
+-- to; inactive pairs keep being indexed so their history stays queryable.
+CREATE TABLE IF NOT EXISTS deployments
+(
+    id                            SERIAL PRIMARY KEY,
+    authenticity_address          TEXT    NOT NULL,
+    ownership_address             TEXT    NOT NULL,
+    authenticity_deployment_block BIGINT,
+    ownership_deployment_block    BIGINT,
+    is_active                     BOOLEAN NOT NULL,
+    created_at                    TEXT    NOT NULL,
+    UNIQUE (authenticity_address, ownership_address)
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS deployments_single_active_idx
+    ON deployments (is_active) WHERE is_active;
+
+-- Projections are tagged with the deployment they were indexed from. Rows indexed before
+-- this migration are tagged with the configured deployment on startup.
+ALTER TABLE contracts
+    ADD COLUMN IF NOT EXISTS deployment_id INTEGER REFERENCES deployments (id);
+
+ALTER TABLE users_info
+    ADD COLUMN IF NOT EXISTS deployment_id INTEGER REFERENCES deployments (id);
+
+ALTER TABLE manufacturers
+    ADD COLUMN IF NOT EXISTS deployment_id INTEGER REFERENCES deployments (id);
+
+ALTER TABLE items
+    ADD COLUMN IF NOT EXISTS deployment_id INTEGER REFERENCES deployments (id);
+
+ALTER TABLE ownership_claims
+    ADD COLUMN IF NOT EXISTS deployment_id INTEGER REFERENCES deployments (id);
+
+ALTER TABLE authenticity_settings
+    ADD COLUMN IF NOT EXISTS deployment_id INTEGER REFERENCES deployments (id);
+
+ALTER TABLE eip712_domains
+    ADD COLUMN IF NOT EXISTS deployment_id INTEGER REFERENCES deployments (id);
+
+-- The same item or address may appear on several deployments, so keys are per deployment
+ALTER TABLE contracts DROP CONSTRAINT IF EXISTS contracts_pkey;
+ALTER TABLE contracts
+    ADD CONSTRAINT contracts_deployment_address_key UNIQUE (deployment_id, contract_address);
+
+ALTER TABLE users_info DROP CONSTRAINT IF EXISTS users_info_pkey;
+ALTER TABLE users_info
+    ADD CONSTRAINT users_info_deployment_address_key UNIQUE (deployment_id, user_address);
+
+ALTER TABLE manufacturers DROP CONSTRAINT IF EXISTS manufacturers_pkey;
+ALTER TABLE manufacturers
+    ADD CONSTRAINT manufacturers_deployment_address_key UNIQUE (deployment_id, manufacturer_address);
+
+ALTER TABLE items DROP CONSTRAINT IF EXISTS items_item_id_key;
+ALTER TABLE items
+    ADD CONSTRAINT items_deployment_item_id_key UNIQUE (deployment_id, item_id);
+
+CREATE INDEX IF NOT EXISTS ownership_claims_deployment_idx
+    ON ownership_claims (deployment_id, item_id);
@@ -0,0 +1,45 @@
+DROP INDEX IF EXISTS chain_events_contract_block_idx;
+CREATE INDEX IF NOT EXISTS chain_events_contract_block_idx
+    ON chain_events (contract_address, block_number, log_index);
+
+ALTER TABLE failed_events DROP CONSTRAINT IF EXISTS failed_events_chain_log_key;
+ALTER TABLE failed_events
+    ADD CONSTRAINT failed_events_tnx_hash_log_index_key UNIQUE (tnx_hash, log_index);
+
+ALTER TABLE chain_events DROP CONSTRAINT IF EXISTS chain_events_chain_log_key;
+ALTER TABLE chain_events
+    ADD CONSTRAINT chain_events_tnx_hash_log_index_key UNIQUE (tnx_hash, log_index);
+
+ALTER TABLE indexed_blocks DROP CONSTRAINT IF EXISTS indexed_blocks_chain_block_key;
+ALTER TABLE indexed_blocks
+    ADD CONSTRAINT indexed_blocks_pkey PRIMARY KEY (contract_address, stream, block_number);
+
+ALTER TABLE sync_checkpoints DROP CONSTRAINT IF EXISTS sync_checkpoints_chain_stream_key;
+ALTER TABLE sync_checkpoints
+    ADD CONSTRAINT sync_checkpoints_pkey PRIMARY KEY (contract_address, stream);
+
+ALTER TABLE certificates DROP CONSTRAINT IF EXISTS certificates_chain_unique_id_key;
+ALTER TABLE certificates
+    ADD CONSTRAINT certificates_pkey PRIMARY KEY (unique_id);
+
+DROP INDEX IF EXISTS deployments_single_active_idx;
+CREATE UNIQUE INDEX IF NOT EXISTS deployments_single_active_idx
+    ON deployments (is_active) WHERE is_active;
+
+ALTER TABLE deployments DROP CONSTRAINT IF EXISTS deployments_chain_addresses_key;
+ALTER TABLE deployments
+    ADD CONSTRAINT deployments_authenticity_address_ownership_address_key
+        UNIQUE (authenticity_address, ownership_address);
+
+ALTER TABLE indexed_blocks DROP COLUMN IF EXISTS chain_id;
+ALTER TABLE sync_checkpoints DROP COLUMN IF EXISTS chain_id;
+ALTER TABLE failed_events DROP COLUMN IF EXISTS chain_id;
+ALTER TABLE chain_events DROP COLUMN IF EXISTS chain_id;
+ALTER TABLE certificates DROP COLUMN IF EXISTS chain_id;
+ALTER TABLE authenticity_settings DROP COLUMN IF EXISTS chain_id;
+ALTER TABLE ownership_claims DROP COLUMN IF EXISTS chain_id;
+ALTER TABLE items DROP COLUMN IF EXISTS chain_id;
+ALTER TABLE manufacturers DROP COLUMN IF EXISTS chain_id;
+ALTER TABLE users_info DROP COLUMN IF EXISTS chain_id;
+ALTER TABLE contracts DROP COLUMN IF EXISTS chain_id;
+ALTER TABLE deployments DROP COLUMN IF EXISTS chain_id;
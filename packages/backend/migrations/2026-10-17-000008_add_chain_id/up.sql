@@ -0,0 +1,74 @@
+-- Every indexed row records the chain it came from. Rows indexed before this migration
+-- were all read from the single configured chain and are tagged with it on startup.
+ALTER TABLE deployments
+    ADD COLUMN IF NOT EXISTS chain_id BIGINT;
+
+ALTER TABLE contracts
+    ADD COLUMN IF NOT EXISTS chain_id BIGINT;
+
+ALTER TABLE users_info
+    ADD COLUMN IF NOT EXISTS chain_id BIGINT;
+
+ALTER TABLE manufacturers
+    ADD COLUMN IF NOT EXISTS chain_id BIGINT;
+
+ALTER TABLE items
+    ADD COLUMN IF NOT EXISTS chain_id BIGINT;
+
+ALTER TABLE ownership_claims
+    ADD COLUMN IF NOT EXISTS chain_id BIGINT;
+
+ALTER TABLE authenticity_settings
+    ADD COLUMN IF NOT EXISTS chain_id BIGINT;
+
+ALTER TABLE certificates
+    ADD COLUMN IF NOT EXISTS chain_id BIGINT;
+
+ALTER TABLE chain_events
+    ADD COLUMN IF NOT EXISTS chain_id BIGINT;
+
+ALTER TABLE failed_events
+    ADD COLUMN IF NOT EXISTS chain_id BIGINT;
+
+ALTER TABLE sync_checkpoints
+    ADD COLUMN IF NOT EXISTS chain_id BIGINT;
+
+ALTER TABLE indexed_blocks
+    ADD COLUMN IF NOT EXISTS chain_id BIGINT;
+
+-- A contract pair is identified per chain, and each chain has its own active pair
+ALTER TABLE deployments
+    DROP CONSTRAINT IF EXISTS deployments_authenticity_address_ownership_address_key;
+ALTER TABLE deployments
+    ADD CONSTRAINT deployments_chain_addresses_key
+        UNIQUE (chain_id, authenticity_address, ownership_address);
+
+DROP INDEX IF EXISTS deployments_single_active_idx;
+CREATE UNIQUE INDEX IF NOT EXISTS deployments_single_active_idx
+    ON deployments (chain_id) WHERE is_active;
+
+-- The same address, transaction hash or certificate id may appear on several chains
+ALTER TABLE certificates DROP CONSTRAINT IF EXISTS certificates_pkey;
+ALTER TABLE certificates
+    ADD CONSTRAINT certificates_chain_unique_id_key UNIQUE (chain_id, unique_id);
+
+ALTER TABLE sync_checkpoints DROP CONSTRAINT IF EXISTS sync_checkpoints_pkey;
+ALTER TABLE sync_checkpoints
+    ADD CONSTRAINT sync_checkpoints_chain_stream_key UNIQUE (chain_id, contract_address, stream);
+
+ALTER TABLE indexed_blocks DROP CONSTRAINT IF EXISTS indexed_blocks_pkey;
+ALTER TABLE indexed_blocks
+    ADD CONSTRAINT indexed_blocks_chain_block_key
+        UNIQUE (chain_id, contract_address, stream, block_number);
+
+ALTER TABLE chain_events DROP CONSTRAINT IF EXISTS chain_events_tnx_hash_log_index_key;
+ALTER TABLE chain_events
+    ADD CONSTRAINT chain_events_chain_log_key UNIQUE (chain_id, tnx_hash, log_index);
+
+ALTER TABLE failed_events DROP CONSTRAINT IF EXISTS failed_events_tnx_hash_log_index_key;
+ALTER TABLE failed_events
+    ADD CONSTRAINT failed_events_chain_log_key UNIQUE (chain_id, tnx_hash, log_index);
+
+DROP INDEX IF EXISTS chain_events_contract_block_idx;
+CREATE INDEX IF NOT EXISTS chain_events_contract_block_idx
+    ON chain_events (chain_id, contract_address, block_number, log_index);
@@ -0,0 +1,16 @@
+-- Audit trail of on-chain vs database reconciliation runs
+CREATE TABLE IF NOT EXISTS reconciliation_runs
+(
+    id            BIGSERIAL PRIMARY KEY,
+    chain_id      BIGINT  NOT NULL,
+    repair        BOOLEAN NOT NULL,
+    items_checked BIGINT  NOT NULL,
+    mismatches    BIGINT  NOT NULL,
+    repaired      BIGINT  NOT NULL,
+    report        JSONB   NOT NULL,
+    started_at    TEXT    NOT NULL,
+    finished_at   TEXT    NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS reconciliation_runs_chain_idx
+    ON reconciliation_runs (chain_id, id);
@@ -0,0 +1,22 @@
+-- Single-use nonces handed out for Sign-In With Ethereum messages
+CREATE TABLE IF NOT EXISTS siwe_nonces
+(
+    nonce      TEXT PRIMARY KEY,
+    created_at TEXT NOT NULL,
+    expires_at TEXT NOT NULL,
+    used_at    TEXT
+);
+
+-- Sessions issued after a verified SIWE login. Only a hash of the bearer token is stored.
+CREATE TABLE IF NOT EXISTS sessions
+(
+    id         BIGSERIAL PRIMARY KEY,
+    token_hash TEXT   NOT NULL UNIQUE,
+    address    TEXT   NOT NULL,
+    chain_id   BIGINT NOT NULL,
+    created_at TEXT   NOT NULL,
+    expires_at TEXT   NOT NULL,
+    revoked_at TEXT
+);
+
+CREATE INDEX IF NOT EXISTS sessions_address_idx ON sessions (address);
@@ -0,0 +1,4 @@
+ALTER TABLE ownership_claims DROP COLUMN IF EXISTS intent_id;
+ALTER TABLE ownership_codes DROP COLUMN IF EXISTS intent_id;
+
+DROP TABLE IF EXISTS ownership_intents;
@@ -0,0 +1,24 @@
+-- EIP-712 signatures authorising ownership code generation, revocation and claims. Kept after
+-- the code or claim they authorised is gone, as the record of who asked for what.
+CREATE TABLE IF NOT EXISTS ownership_intents
+(
+    id             BIGSERIAL PRIMARY KEY,
+    chain_id       BIGINT NOT NULL,
+    action         TEXT   NOT NULL,
+    signer         TEXT   NOT NULL,
+    item_id        TEXT   NOT NULL,
+    ownership_code TEXT   NOT NULL,
+    temp_owner     TEXT   NOT NULL,
+    nonce          TEXT   NOT NULL,
+    deadline       BIGINT NOT NULL,
+    digest         TEXT   NOT NULL,
+    signature      TEXT   NOT NULL,
+    tnx_hash       TEXT,
+    created_at     TEXT   NOT NULL,
+    CONSTRAINT ownership_intents_signer_nonce_key UNIQUE (chain_id, signer, nonce)
+);
+
+CREATE INDEX IF NOT EXISTS ownership_intents_tnx_hash_idx ON ownership_intents (tnx_hash);
+
+ALTER TABLE ownership_codes ADD COLUMN IF NOT EXISTS intent_id BIGINT;
+ALTER TABLE ownership_claims ADD COLUMN IF NOT EXISTS intent_id BIGINT;
@@ -0,0 +1,16 @@
+-- Keys manufacturers use from their own systems. Only a hash of the key is stored; the
+-- prefix identifies a key in listings.
+CREATE TABLE IF NOT EXISTS api_keys
+(
+    id                   BIGSERIAL PRIMARY KEY,
+    manufacturer_address TEXT   NOT NULL,
+    name                 TEXT   NOT NULL,
+    key_prefix           TEXT   NOT NULL,
+    key_hash             TEXT   NOT NULL UNIQUE,
+    scopes               TEXT[] NOT NULL,
+    created_at           TEXT   NOT NULL,
+    last_used_at         TEXT,
+    revoked_at           TEXT
+);
+
+CREATE INDEX IF NOT EXISTS api_keys_manufacturer_idx ON api_keys (manufacturer_address);
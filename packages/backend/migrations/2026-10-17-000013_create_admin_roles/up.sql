@@ -0,0 +1,28 @@
+-- Staff wallets and the role each holds: `admin` manages roles and reads the audit log,
+-- `operator` sends privileged contract transactions and replays failed events, `support`
+-- has read-only access to the admin API.
+CREATE TABLE IF NOT EXISTS admin_roles
+(
+    address    TEXT PRIMARY KEY,
+    role       TEXT NOT NULL CHECK (role IN ('admin', 'operator', 'support')),
+    granted_by TEXT NOT NULL,
+    granted_at TEXT NOT NULL
+);
+
+-- One row per privileged action, whether it succeeded or not
+CREATE TABLE IF NOT EXISTS admin_audit_log
+(
+    id         BIGSERIAL PRIMARY KEY,
+    actor      TEXT   NOT NULL,
+    role       TEXT   NOT NULL,
+    action     TEXT   NOT NULL,
+    chain_id   BIGINT,
+    target     TEXT,
+    details    JSONB  NOT NULL,
+    succeeded  BOOLEAN NOT NULL,
+    error      TEXT,
+    created_at TEXT   NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS admin_audit_log_actor_idx ON admin_audit_log (actor);
+CREATE INDEX IF NOT EXISTS admin_audit_log_action_idx ON admin_audit_log (action);
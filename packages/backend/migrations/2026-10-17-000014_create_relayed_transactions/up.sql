@@ -0,0 +1,28 @@
+-- Contract calls the server wallet sends on behalf of API requests. A row is queued by the
+-- handler and then submitted, fee-bumped and tracked by the relayer worker. The signed
+-- transaction is stored before it is broadcast, so it can be sent again after a restart.
+CREATE TABLE IF NOT EXISTS relayed_transactions
+(
+    id              BIGSERIAL PRIMARY KEY,
+    chain_id        BIGINT  NOT NULL,
+    kind            TEXT    NOT NULL,
+    from_address    TEXT    NOT NULL,
+    to_address      TEXT    NOT NULL,
+    calldata        TEXT    NOT NULL,
+    status          TEXT    NOT NULL,
+    nonce           BIGINT,
+    gas_limit       TEXT,
+    gas_price       TEXT,
+    tnx_hash        TEXT,
+    tnx_hashes      TEXT[]  NOT NULL DEFAULT '{}',
+    raw_transaction TEXT,
+    attempts        INTEGER NOT NULL DEFAULT 0,
+    error           TEXT,
+    intent_id       BIGINT,
+    created_at      TEXT    NOT NULL,
+    submitted_at    TEXT,
+    updated_at      TEXT    NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS relayed_transactions_status_idx
+    ON relayed_transactions (chain_id, status, id);
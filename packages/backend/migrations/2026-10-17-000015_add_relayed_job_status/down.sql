@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS relayed_transactions_job_id_idx;
+
+ALTER TABLE relayed_transactions DROP COLUMN IF EXISTS revert_reason;
+ALTER TABLE relayed_transactions DROP COLUMN IF EXISTS block_number;
+ALTER TABLE relayed_transactions DROP COLUMN IF EXISTS job_id;
@@ -0,0 +1,12 @@
+-- Relayed transactions double as the jobs asynchronous write endpoints hand out. The job id
+-- is random so one client cannot look up another's jobs by counting.
+ALTER TABLE relayed_transactions ADD COLUMN IF NOT EXISTS job_id TEXT;
+ALTER TABLE relayed_transactions ADD COLUMN IF NOT EXISTS block_number BIGINT;
+ALTER TABLE relayed_transactions ADD COLUMN IF NOT EXISTS revert_reason TEXT;
+
+UPDATE relayed_transactions
+SET job_id = 'job_' || md5(random()::TEXT || id::TEXT)
+WHERE job_id IS NULL;
+ALTER TABLE relayed_transactions ALTER COLUMN job_id SET NOT NULL;
+
+CREATE UNIQUE INDEX IF NOT EXISTS relayed_transactions_job_id_idx ON relayed_transactions (job_id);
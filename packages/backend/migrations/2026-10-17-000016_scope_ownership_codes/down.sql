@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS ownership_codes_deployment_item_idx;
+
+ALTER TABLE ownership_codes DROP COLUMN IF EXISTS deployment_id;
+ALTER TABLE ownership_codes DROP COLUMN IF EXISTS chain_id;
@@ -0,0 +1,11 @@
+-- Pending codes belong to the chain and deployment of the item they were generated for, so
+-- a transfer on one deployment only clears its own codes. Codes generated before this
+-- migration are tagged with the default chain's deployment on startup.
+ALTER TABLE ownership_codes
+    ADD COLUMN IF NOT EXISTS chain_id BIGINT;
+
+ALTER TABLE ownership_codes
+    ADD COLUMN IF NOT EXISTS deployment_id INTEGER REFERENCES deployments (id);
+
+CREATE INDEX IF NOT EXISTS ownership_codes_deployment_item_idx
+    ON ownership_codes (chain_id, deployment_id, item_id);
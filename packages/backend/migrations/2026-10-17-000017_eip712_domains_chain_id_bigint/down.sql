@@ -0,0 +1,6 @@
+DROP INDEX IF EXISTS eip712_domains_contract_active_idx;
+CREATE INDEX IF NOT EXISTS eip712_domains_contract_active_idx
+    ON eip712_domains (contract_address, is_active);
+
+ALTER TABLE eip712_domains
+    ALTER COLUMN chain_id TYPE TEXT USING chain_id::TEXT;
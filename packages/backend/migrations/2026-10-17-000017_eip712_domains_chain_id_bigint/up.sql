@@ -0,0 +1,7 @@
+-- chain_id is BIGINT on every other table
+ALTER TABLE eip712_domains
+    ALTER COLUMN chain_id TYPE BIGINT USING chain_id::BIGINT;
+
+DROP INDEX IF EXISTS eip712_domains_contract_active_idx;
+CREATE INDEX IF NOT EXISTS eip712_domains_contract_active_idx
+    ON eip712_domains (chain_id, contract_address, is_active);
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS failed_events_ordering_key_idx;
+
+ALTER TABLE failed_events DROP COLUMN IF EXISTS ordering_key;
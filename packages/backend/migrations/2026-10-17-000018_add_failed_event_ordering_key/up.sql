@@ -0,0 +1,7 @@
+-- Events about the same entity (e.g. an item) are projected in chain order. While one of
+-- them is dead-lettered, later events with the same key are parked behind it.
+ALTER TABLE failed_events
+    ADD COLUMN IF NOT EXISTS ordering_key TEXT;
+
+CREATE INDEX IF NOT EXISTS failed_events_ordering_key_idx
+    ON failed_events (chain_id, contract_address, ordering_key, block_number, log_index);
@@ -0,0 +1,126 @@
+use crate::auth::admin::{
+    grant_role, list_audit_log, list_roles, record_admin_action, revoke_role, AdminActor,
+    AdminRole,
+};
+use crate::config::app_state::AppState;
+use crate::deployments::{all_indexers, list_deployments};
+use crate::indexer::dead_letter::{find_failed_event, list_failed_events, replay_failed_event};
+use crate::indexer::reconcile::list_reconciliation_runs;
+use clap::Subcommand;
+use eyre::Result;
+use serde::Serialize;
+use serde_json::json;
+use std::sync::Arc;
+
+// Operator commands that mirror the admin endpoints without going through HTTP. Results are
+// printed to stdout as JSON.
+#[derive(Subcommand)]
+pub enum AdminCommand {
+    #[command(about = "List events that failed to project")]
+    FailedEvents {
+        #[arg(long)]
+        chain_id: Option<i64>,
+        #[arg(long)]
+        status: Option<String>,
+        #[arg(long, default_value_t = 100)]
+        limit: i64,
+    },
+    #[command(about = "Project a failed event again")]
+    ReplayFailedEvent { id: i64 },
+    #[command(about = "List the contract deployments being indexed")]
+    Deployments,
+    #[command(about = "List stored reconciliation reports, newest first")]
+    Reconciliations {
+        #[arg(long)]
+        chain_id: Option<i64>,
+        #[arg(long, default_value_t = 20)]
+        limit: i64,
+    },
+    #[command(about = "Grant a wallet the admin, operator or support role")]
+    GrantRole { address: String, role: String },
+    #[command(about = "Revoke a wallet's admin role")]
+    RevokeRole { address: String },
+    #[command(about = "List wallets holding an admin role")]
+    Roles,
+    #[command(about = "List privileged actions, newest first")]
+    AuditLog {
+        #[arg(long)]
+        actor: Option<String>,
+        #[arg(long)]
+        action: Option<String>,
+        #[arg(long, default_value_t = 100)]
+        limit: i64,
+    },
+}
+
+pub async fn run_admin(state: &Arc<AppState>, command: AdminCommand) -> Result<()> {
+    let conn = &mut state.db_pool.get().map_err(|e| {
+        eprintln!("Failed to get DB connection: {:?}", e);
+        eyre::eyre!("Failed to get DB connection: {}", e)
+    })?;
+
+    match command {
+        AdminCommand::FailedEvents { chain_id, status, limit } => {
+            print_json(&list_failed_events(conn, chain_id, status.as_deref(), limit)?)
+        }
+        AdminCommand::ReplayFailedEvent { id } => {
+            let failed =
+                find_failed_event(conn, id)?.ok_or_else(|| eyre::eyre!("Failed event not found"))?;
+            let indexers = all_indexers(state)?;
+            let outcome = replay_failed_event(state, &indexers, conn, &failed).await;
+            record_admin_action(
+                state,
+                &AdminActor::cli(),
+                "replay_failed_event",
+                failed.chain_id.map(|chain_id| chain_id as u64),
+                Some(&id.to_string()),
+                json!({ "resolved": outcome.as_ref().ok() }),
+                &outcome,
+            );
+            print_json(&json!({ "id": id, "resolved": outcome? }))
+        }
+        AdminCommand::Deployments => print_json(&list_deployments(conn)?),
+        AdminCommand::Reconciliations { chain_id, limit } => {
+            print_json(&list_reconciliation_runs(conn, chain_id, limit)?)
+        }
+        AdminCommand::GrantRole { address, role } => {
+            let actor = AdminActor::cli();
+            let outcome = AdminRole::parse(&role)
+                .and_then(|role| grant_role(conn, &address, role, &actor.actor));
+            record_admin_action(
+                state,
+                &actor,
+                "grant_role",
+                None,
+                Some(&address),
+                json!({ "role": role }),
+                &outcome,
+            );
+            print_json(&outcome?)
+        }
+        AdminCommand::RevokeRole { address } => {
+            let outcome = revoke_role(conn, &address);
+            record_admin_action(
+                state,
+                &AdminActor::cli(),
+                "revoke_role",
+                None,
+                Some(&address),
+                json!({ "role": outcome.as_ref().ok().map(|grant| &grant.role) }),
+                &outcome,
+            );
+            print_json(&outcome?)
+        }
+        AdminCommand::Roles => print_json(&list_roles(conn)?),
+        AdminCommand::AuditLog { actor, action, limit } => {
+            print_json(&list_audit_log(conn, actor.as_deref(), action.as_deref(), limit)?)
+        }
+    }
+}
+
+fn print_json<T: Serialize>(value: &T) -> Result<()> {
+    let output = serde_json::to_string_pretty(value)
+        .map_err(|e| eyre::eyre!("Failed to serialize output: {}", e))?;
+    println!("{}", output);
+    Ok(())
+}
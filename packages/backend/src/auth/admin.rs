@@ -0,0 +1,305 @@
+use crate::auth::session::{find_session, hash_token};
+use crate::config::app_state::AppState;
+use crate::contract_models::{AdminAuditEntry, AdminRoleGrant, NewAdminAuditEntry};
+use crate::schema::{admin_audit_log, admin_roles};
+use crate::utility::timestamp_after;
+use axum::extract::{Request, State};
+use axum::http::header::AUTHORIZATION;
+use axum::http::StatusCode;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use diesel::prelude::*;
+use diesel::upsert::excluded;
+use diesel::{PgConnection, RunQueryDsl};
+use ethers::core::utils::to_checksum;
+use ethers::types::Address;
+use eyre::Result;
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+
+// Header carrying the bootstrap key configured through ADMIN_API_KEY
+pub const ADMIN_KEY_HEADER: &str = "x-admin-key";
+
+// Actor recorded for requests authenticated with ADMIN_API_KEY instead of a wallet
+const ADMIN_KEY_ACTOR: &str = "admin-key";
+
+// Each role can do everything the roles below it can
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AdminRole {
+    Support,
+    Operator,
+    Admin,
+}
+
+impl AdminRole {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AdminRole::Support => "support",
+            AdminRole::Operator => "operator",
+            AdminRole::Admin => "admin",
+        }
+    }
+
+    pub fn parse(role: &str) -> Result<Self> {
+        match role {
+            "support" => Ok(AdminRole::Support),
+            "operator" => Ok(AdminRole::Operator),
+            "admin" => Ok(AdminRole::Admin),
+            role => Err(eyre::eyre!("Unknown role: {}", role)),
+        }
+    }
+}
+
+// Who is calling an admin route and with which role. Added to the request extensions by
+// `require_role`.
+#[derive(Clone)]
+pub struct AdminActor {
+    pub actor: String,
+    pub role: AdminRole,
+}
+
+impl AdminActor {
+    // Used by the admin CLI, which runs with database access and needs no credentials
+    pub fn cli() -> Self {
+        AdminActor {
+            actor: "cli".to_string(),
+            role: AdminRole::Admin,
+        }
+    }
+}
+
+// Roles are keyed by the EIP-55 address, the form sessions are issued for
+pub fn grant_role(
+    conn: &mut PgConnection,
+    address: &str,
+    role: AdminRole,
+    granted_by: &str,
+) -> Result<AdminRoleGrant> {
+    let address: Address = address
+        .parse()
+        .map_err(|_| eyre::eyre!("Invalid address"))?;
+
+    diesel::insert_into(admin_roles::table)
+        .values(AdminRoleGrant {
+            address: to_checksum(&address, None),
+            role: role.as_str().to_string(),
+            granted_by: granted_by.to_string(),
+            granted_at: timestamp_after(Duration::ZERO),
+        })
+        .on_conflict(admin_roles::address)
+        .do_update()
+        .set((
+            admin_roles::role.eq(excluded(admin_roles::role)),
+            admin_roles::granted_by.eq(excluded(admin_roles::granted_by)),
+            admin_roles::granted_at.eq(excluded(admin_roles::granted_at)),
+        ))
+        .returning(AdminRoleGrant::as_returning())
+        .get_result(conn)
+        .map_err(|e| {
+            eprintln!("Failed to grant {} role: {:?}", role.as_str(), e);
+            eyre::eyre!("Failed to grant role: {}", e)
+        })
+}
+
+pub fn revoke_role(conn: &mut PgConnection, address: &str) -> Result<AdminRoleGrant> {
+    let address: Address = address
+        .parse()
+        .map_err(|_| eyre::eyre!("Invalid address"))?;
+
+    diesel::delete(admin_roles::table.find(to_checksum(&address, None)))
+        .returning(AdminRoleGrant::as_returning())
+        .get_result(conn)
+        .optional()
+        .map_err(|e| {
+            eprintln!("Failed to revoke role of {:?}: {:?}", address, e);
+            eyre::eyre!("Failed to revoke role: {}", e)
+        })?
+        .ok_or_else(|| eyre::eyre!("Admin role not found"))
+}
+
+pub fn list_roles(conn: &mut PgConnection) -> Result<Vec<AdminRoleGrant>> {
+    admin_roles::table
+        .order(admin_roles::address.asc())
+        .select(AdminRoleGrant::as_select())
+        .load(conn)
+        .map_err(|e| eyre::eyre!("Failed to load admin roles: {}", e))
+}
+
+fn role_for_address(conn: &mut PgConnection, address: &str) -> Result<Option<AdminRole>> {
+    admin_roles::table
+        .find(address)
+        .select(admin_roles::role)
+        .first::<String>(conn)
+        .optional()
+        .map_err(|e| eyre::eyre!("Failed to load admin role: {}", e))?
+        .map(|role| AdminRole::parse(&role))
+        .transpose()
+}
+
+// Records a privileged action and its outcome. A failed write is logged rather than
+// returned, so it never masks the result of an action that already happened.
+pub fn record_admin_action<T>(
+    state: &AppState,
+    actor: &AdminActor,
+    action: &str,
+    chain_id: Option<u64>,
+    target: Option<&str>,
+    details: serde_json::Value,
+    outcome: &Result<T>,
+) {
+    let entry = NewAdminAuditEntry {
+        actor: actor.actor.clone(),
+        role: actor.role.as_str().to_string(),
+        action: action.to_string(),
+        chain_id: chain_id.map(|chain_id| chain_id as i64),
+        target: target.map(str::to_string),
+        details,
+        succeeded: outcome.is_ok(),
+        error: outcome.as_ref().err().map(|e| e.to_string()),
+        created_at: timestamp_after(Duration::ZERO),
+    };
+
+    let written = state
+        .db_pool
+        .get()
+        .map_err(|e| eyre::eyre!("Failed to get DB connection: {}", e))
+        .and_then(|mut conn| {
+            diesel::insert_into(admin_audit_log::table)
+                .values(&entry)
+                .execute(&mut conn)
+                .map_err(|e| eyre::eyre!("Failed to insert audit entry: {}", e))
+        });
+    if let Err(e) = written {
+        eprintln!(
+            "Failed to record {} by {} in the audit log: {:?}",
+            action, actor.actor, e
+        );
+    }
+}
+
+pub fn list_audit_log(
+    conn: &mut PgConnection,
+    actor: Option<&str>,
+    action: Option<&str>,
+    limit: i64,
+) -> Result<Vec<AdminAuditEntry>> {
+    let mut query = admin_audit_log::table
+        .order(admin_audit_log::id.desc())
+        .limit(limit)
+        .select(AdminAuditEntry::as_select())
+        .into_boxed();
+    if let Some(actor) = actor {
+        query = query.filter(admin_audit_log::actor.eq(actor.to_string()));
+    }
+    if let Some(action) = action {
+        query = query.filter(admin_audit_log::action.eq(action.to_string()));
+    }
+    query
+        .load(conn)
+        .map_err(|e| eyre::eyre!("Failed to load audit log: {}", e))
+}
+
+// Compares digests instead of the keys, so the time taken says nothing about how much of the
+// key was right, and folds every byte so the comparison itself does not stop early
+fn admin_key_matches(expected: &str, provided: &str) -> bool {
+    let expected = hash_token(expected);
+    let provided = hash_token(provided);
+    expected
+        .bytes()
+        .zip(provided.bytes())
+        .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+        == 0
+}
+
+// Middleware for the admin router: accepts ADMIN_API_KEY as the `admin` role, or a SIWE
+// session whose address holds at least `role`, and hands the caller to the handler as
+// `Extension<AdminActor>`
+pub async fn require_role(
+    State(state): State<Arc<AppState>>,
+    role: AdminRole,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let error = |status: StatusCode, message: String| {
+        (status, Json(json!({"error": message}))).into_response()
+    };
+
+    let admin_key = request
+        .headers()
+        .get(ADMIN_KEY_HEADER)
+        .and_then(|value| value.to_str().ok());
+    let token = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    let actor = match (admin_key, token) {
+        (Some(key), _) => {
+            if !state
+                .admin_api_key
+                .as_deref()
+                .is_some_and(|expected| admin_key_matches(expected, key))
+            {
+                return error(StatusCode::UNAUTHORIZED, "Invalid admin key".to_string());
+            }
+            AdminActor {
+                actor: ADMIN_KEY_ACTOR.to_string(),
+                role: AdminRole::Admin,
+            }
+        }
+        (None, Some(token)) => {
+            let resolved = state
+                .db_pool
+                .get()
+                .map_err(|e| eyre::eyre!("Failed to get DB connection: {}", e))
+                .and_then(|mut conn| {
+                    let Some(session) = find_session(&mut conn, token)? else {
+                        return Ok(None);
+                    };
+                    let granted = role_for_address(&mut conn, &session.address)?;
+                    Ok(Some((session.address, granted)))
+                });
+            match resolved {
+                Ok(Some((address, Some(granted)))) => AdminActor {
+                    actor: address,
+                    role: granted,
+                },
+                Ok(Some((_, None))) => {
+                    return error(StatusCode::FORBIDDEN, "Caller has no admin role".to_string());
+                }
+                Ok(None) => {
+                    return error(
+                        StatusCode::UNAUTHORIZED,
+                        "Invalid or expired session token".to_string(),
+                    );
+                }
+                Err(e) => {
+                    eprintln!("Error resolving admin role: {:?}", e);
+                    return error(
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        format!("Internal server error: {}", e),
+                    );
+                }
+            }
+        }
+        (None, None) => {
+            return error(
+                StatusCode::UNAUTHORIZED,
+                "Missing session token or admin key".to_string(),
+            );
+        }
+    };
+
+    if actor.role < role {
+        return error(
+            StatusCode::FORBIDDEN,
+            format!("Requires the {} role", role.as_str()),
+        );
+    }
+
+    request.extensions_mut().insert(actor);
+    next.run(request).await
+}
@@ -0,0 +1,299 @@
+use crate::auth::session::hash_token;
+use crate::config::app_state::AppState;
+use crate::contract_models::{ApiKey, ChainQuery, NewApiKey};
+use crate::deployments::active_deployment_id;
+use crate::schema::{api_keys, manufacturers};
+use crate::utility::timestamp_after;
+use axum::extract::{Query, Request, State};
+use axum::http::StatusCode;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use diesel::prelude::*;
+use diesel::{PgConnection, RunQueryDsl};
+use eyre::Result;
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+
+// Header manufacturer systems send their key in
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+pub const SCOPE_CERTIFICATES_WRITE: &str = "certificates:write";
+pub const SCOPE_ITEMS_READ: &str = "items:read";
+pub const SCOPE_ANALYTICS_READ: &str = "analytics:read";
+pub const ALL_SCOPES: [&str; 3] = [
+    SCOPE_CERTIFICATES_WRITE,
+    SCOPE_ITEMS_READ,
+    SCOPE_ANALYTICS_READ,
+];
+
+// Keys look like `true_<64 hex chars>`; the first 13 characters are kept as the prefix
+const KEY_PREFIX_LEN: usize = 13;
+
+// The manufacturer a request's API key belongs to. Added to the request extensions by
+// `require_scope`.
+#[derive(Clone)]
+pub struct ApiKeyAuth {
+    pub key_id: i64,
+    pub manufacturer_address: String,
+    pub manufacturer_name: String,
+}
+
+// Returns the stored key and the plaintext key, which is only ever shown here
+pub fn create_api_key(
+    conn: &mut PgConnection,
+    manufacturer_address: &str,
+    name: &str,
+    scopes: &[String],
+) -> Result<(ApiKey, String)> {
+    if name.is_empty() {
+        return Err(eyre::eyre!("API key name cannot be empty"));
+    }
+    if scopes.is_empty() {
+        return Err(eyre::eyre!("At least one scope is required"));
+    }
+    if let Some(scope) = scopes.iter().find(|scope| !ALL_SCOPES.contains(&scope.as_str())) {
+        return Err(eyre::eyre!("Unknown scope: {}", scope));
+    }
+
+    let key = format!("true_{}", hex::encode(rand::random::<[u8; 32]>()));
+    let api_key = diesel::insert_into(api_keys::table)
+        .values(NewApiKey {
+            manufacturer_address: manufacturer_address.to_string(),
+            name: name.to_string(),
+            key_prefix: key[..KEY_PREFIX_LEN].to_string(),
+            key_hash: hash_token(&key),
+            scopes: scopes.iter().cloned().map(Some).collect(),
+            created_at: timestamp_after(Duration::ZERO),
+        })
+        .returning(ApiKey::as_returning())
+        .get_result(conn)
+        .map_err(|e| {
+            eprintln!("Failed to create API key for {}: {:?}", manufacturer_address, e);
+            eyre::eyre!("Failed to create API key: {}", e)
+        })?;
+
+    Ok((api_key, key))
+}
+
+pub fn list_api_keys(conn: &mut PgConnection, manufacturer_address: &str) -> Result<Vec<ApiKey>> {
+    api_keys::table
+        .filter(api_keys::manufacturer_address.eq(manufacturer_address))
+        .order(api_keys::id.desc())
+        .select(ApiKey::as_select())
+        .load(conn)
+        .map_err(|e| eyre::eyre!("Failed to load API keys: {}", e))
+}
+
+// Only the owning manufacturer can revoke a key
+pub fn revoke_api_key(conn: &mut PgConnection, manufacturer_address: &str, id: i64) -> Result<ApiKey> {
+    diesel::update(
+        api_keys::table
+            .filter(api_keys::id.eq(id))
+            .filter(api_keys::manufacturer_address.eq(manufacturer_address)),
+    )
+    .set(api_keys::revoked_at.eq(timestamp_after(Duration::ZERO)))
+    .returning(ApiKey::as_returning())
+    .get_result(conn)
+    .optional()
+    .map_err(|e| {
+        eprintln!("Failed to revoke API key {}: {:?}", id, e);
+        eyre::eyre!("Failed to revoke API key: {}", e)
+    })?
+    .ok_or_else(|| eyre::eyre!("API key not found"))
+}
+
+// A live key and the scopes it grants, with the manufacturer it belongs to. The manufacturer
+// must be registered on the given deployment; a registration on another deployment or chain
+// does not count.
+fn resolve_api_key(
+    conn: &mut PgConnection,
+    key: &str,
+    deployment_id: i32,
+) -> Result<Option<(ApiKeyAuth, Vec<String>)>> {
+    let found = api_keys::table
+        .inner_join(
+            manufacturers::table.on(manufacturers::manufacturer_address
+                .eq(api_keys::manufacturer_address)
+                .and(manufacturers::deployment_id.eq(deployment_id))),
+        )
+        .filter(api_keys::key_hash.eq(hash_token(key)))
+        .filter(api_keys::revoked_at.is_null())
+        .filter(manufacturers::is_registered.eq(true))
+        .select((
+            api_keys::id,
+            manufacturers::manufacturer_address,
+            manufacturers::manufacturer_name,
+            api_keys::scopes,
+        ))
+        .first::<(i64, String, String, Vec<Option<String>>)>(conn)
+        .optional()
+        .map_err(|e| eyre::eyre!("Failed to load API key: {}", e))?;
+
+    let Some((key_id, manufacturer_address, manufacturer_name, scopes)) = found else {
+        return Ok(None);
+    };
+
+    diesel::update(api_keys::table.find(key_id))
+        .set(api_keys::last_used_at.eq(timestamp_after(Duration::ZERO)))
+        .execute(conn)
+        .map_err(|e| eyre::eyre!("Failed to update API key usage: {}", e))?;
+
+    Ok(Some((
+        ApiKeyAuth {
+            key_id,
+            manufacturer_address,
+            manufacturer_name,
+        },
+        scopes.into_iter().flatten().collect(),
+    )))
+}
+
+// Middleware for routes manufacturer systems call: rejects requests without a live key
+// granting `scope` whose manufacturer is registered on the active deployment of the
+// request's chain (the `chain_id` query parameter, or the default chain), and hands the
+// key's manufacturer to the handler as `Extension<ApiKeyAuth>`
+pub async fn require_scope(
+    State(state): State<Arc<AppState>>,
+    scope: &'static str,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let error = |status: StatusCode, message: String| {
+        (status, Json(json!({"error": message}))).into_response()
+    };
+
+    let Some(key) = request
+        .headers()
+        .get(API_KEY_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+    else {
+        return error(StatusCode::UNAUTHORIZED, "Missing API key".to_string());
+    };
+
+    let chain_id = match Query::<ChainQuery>::try_from_uri(request.uri()) {
+        Ok(Query(query)) => query.chain_id,
+        Err(e) => return error(StatusCode::BAD_REQUEST, e.body_text()),
+    };
+    let chain = match state.chain(chain_id) {
+        Ok(chain) => chain,
+        Err(e) => return error(StatusCode::BAD_REQUEST, e.to_string()),
+    };
+
+    let resolved = state
+        .db_pool
+        .get()
+        .map_err(|e| eyre::eyre!("Failed to get DB connection: {}", e))
+        .and_then(|mut conn| {
+            let deployment_id = active_deployment_id(&mut conn, chain)?;
+            resolve_api_key(&mut conn, &key, deployment_id)
+        });
+    let (auth, scopes) = match resolved {
+        Ok(Some(resolved)) => resolved,
+        Ok(None) => return error(StatusCode::UNAUTHORIZED, "Invalid or revoked API key".to_string()),
+        Err(e) => {
+            eprintln!("Error resolving API key: {:?}", e);
+            return error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Internal server error: {}", e),
+            );
+        }
+    };
+
+    if !scopes.iter().any(|granted| granted == scope) {
+        return error(
+            StatusCode::FORBIDDEN,
+            format!("API key is missing the {} scope", scope),
+        );
+    }
+
+    request.extensions_mut().insert(auth);
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::deployments;
+    use crate::test_db::test_connection;
+
+    const MANUFACTURER: &str = "0x1111111111111111111111111111111111111111";
+
+    fn insert_deployment(conn: &mut PgConnection, chain_id: i64, ownership_address: &str) -> i32 {
+        diesel::insert_into(deployments::table)
+            .values((
+                deployments::authenticity_address.eq("0x2222222222222222222222222222222222222222"),
+                deployments::ownership_address.eq(ownership_address),
+                deployments::is_active.eq(false),
+                deployments::created_at.eq("2026-01-01T00:00:00Z"),
+                deployments::chain_id.eq(chain_id),
+            ))
+            .returning(deployments::id)
+            .get_result(conn)
+            .unwrap()
+    }
+
+    fn insert_manufacturer(
+        conn: &mut PgConnection,
+        deployment_id: i32,
+        chain_id: i64,
+        name: &str,
+        is_registered: bool,
+    ) {
+        diesel::insert_into(manufacturers::table)
+            .values((
+                manufacturers::manufacturer_address.eq(MANUFACTURER),
+                manufacturers::manufacturer_name.eq(name),
+                manufacturers::is_registered.eq(is_registered),
+                manufacturers::registered_at.eq("2026-01-01T00:00:00Z"),
+                manufacturers::tnx_hash.eq("0x00"),
+                manufacturers::deployment_id.eq(deployment_id),
+                manufacturers::chain_id.eq(chain_id),
+            ))
+            .execute(conn)
+            .unwrap();
+    }
+
+    #[test]
+    fn key_resolves_against_the_requested_deployment_only() {
+        let Some(mut conn) = test_connection() else {
+            return;
+        };
+        let conn = &mut conn;
+        let current = insert_deployment(conn, 31_337, "0x3333333333333333333333333333333333333333");
+        let retired = insert_deployment(conn, 31_337, "0x4444444444444444444444444444444444444444");
+        let other_chain = insert_deployment(conn, 84_532, "0x3333333333333333333333333333333333333333");
+        insert_manufacturer(conn, retired, 31_337, "Acme (old)", true);
+        insert_manufacturer(conn, current, 31_337, "Acme", false);
+        insert_manufacturer(conn, other_chain, 84_532, "Acme Base", true);
+        let scopes = vec![SCOPE_ITEMS_READ.to_string()];
+        let (_, key) = create_api_key(conn, MANUFACTURER, "erp", &scopes).unwrap();
+
+        // Registered elsewhere does not make the key valid where the manufacturer is not
+        assert!(resolve_api_key(conn, &key, current).unwrap().is_none());
+
+        let (auth, granted) = resolve_api_key(conn, &key, retired).unwrap().unwrap();
+        assert_eq!(auth.manufacturer_name, "Acme (old)");
+        assert_eq!(granted, scopes);
+        let (auth, _) = resolve_api_key(conn, &key, other_chain).unwrap().unwrap();
+        assert_eq!(auth.manufacturer_name, "Acme Base");
+    }
+
+    #[test]
+    fn revoked_keys_do_not_resolve() {
+        let Some(mut conn) = test_connection() else {
+            return;
+        };
+        let conn = &mut conn;
+        let deployment = insert_deployment(conn, 31_337, "0x3333333333333333333333333333333333333333");
+        insert_manufacturer(conn, deployment, 31_337, "Acme", true);
+        let scopes = vec![SCOPE_ITEMS_READ.to_string()];
+        let (api_key, key) = create_api_key(conn, MANUFACTURER, "erp", &scopes).unwrap();
+        assert!(resolve_api_key(conn, &key, deployment).unwrap().is_some());
+
+        revoke_api_key(conn, MANUFACTURER, api_key.id).unwrap();
+        assert!(resolve_api_key(conn, &key, deployment).unwrap().is_none());
+    }
+}
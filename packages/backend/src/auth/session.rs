@@ -0,0 +1,167 @@
+use crate::config::app_state::AppState;
+use crate::contract_models::{NewSession, Session, SiweNonce};
+use crate::schema::{sessions, siwe_nonces};
+use crate::utility::timestamp_after;
+use axum::extract::FromRequestParts;
+use axum::http::header::AUTHORIZATION;
+use axum::http::request::Parts;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use diesel::prelude::*;
+use diesel::{PgConnection, RunQueryDsl};
+use eyre::Result;
+use serde_json::json;
+use sha3::{Digest, Keccak256};
+use std::sync::Arc;
+use std::time::Duration;
+
+// How long a nonce can be used to sign in after it was handed out
+const NONCE_TTL: Duration = Duration::from_secs(600);
+
+pub fn hash_token(token: &str) -> String {
+    format!("0x{}", hex::encode(Keccak256::digest(token.as_bytes())))
+}
+
+pub fn issue_nonce(conn: &mut PgConnection) -> Result<SiweNonce> {
+    let nonce = SiweNonce {
+        nonce: hex::encode(rand::random::<[u8; 16]>()),
+        created_at: timestamp_after(Duration::ZERO),
+        expires_at: timestamp_after(NONCE_TTL),
+        used_at: None,
+    };
+
+    diesel::insert_into(siwe_nonces::table)
+        .values(&nonce)
+        .execute(conn)
+        .map_err(|e| {
+            eprintln!("Failed to store SIWE nonce: {:?}", e);
+            eyre::eyre!("Failed to store SIWE nonce: {}", e)
+        })?;
+
+    Ok(nonce)
+}
+
+// Marks the nonce as used; each one signs in once
+pub fn consume_nonce(conn: &mut PgConnection, nonce: &str) -> Result<()> {
+    let now = timestamp_after(Duration::ZERO);
+    let consumed = diesel::update(
+        siwe_nonces::table
+            .filter(siwe_nonces::nonce.eq(nonce))
+            .filter(siwe_nonces::used_at.is_null())
+            .filter(siwe_nonces::expires_at.gt(&now)),
+    )
+    .set(siwe_nonces::used_at.eq(&now))
+    .execute(conn)
+    .map_err(|e| {
+        eprintln!("Failed to consume SIWE nonce: {:?}", e);
+        eyre::eyre!("Failed to consume SIWE nonce: {}", e)
+    })?;
+
+    if consumed == 0 {
+        return Err(eyre::eyre!("Invalid, expired or already used nonce"));
+    }
+    Ok(())
+}
+
+// Returns the bearer token and its expiry. Only the token's hash is stored.
+pub fn create_session(
+    conn: &mut PgConnection,
+    address: &str,
+    chain_id: u64,
+    ttl: Duration,
+) -> Result<(String, String)> {
+    let token = hex::encode(rand::random::<[u8; 32]>());
+    let expires_at = timestamp_after(ttl);
+
+    diesel::insert_into(sessions::table)
+        .values(NewSession {
+            token_hash: hash_token(&token),
+            address: address.to_string(),
+            chain_id: chain_id as i64,
+            created_at: timestamp_after(Duration::ZERO),
+            expires_at: expires_at.clone(),
+        })
+        .execute(conn)
+        .map_err(|e| {
+            eprintln!("Failed to create session for {}: {:?}", address, e);
+            eyre::eyre!("Failed to create session: {}", e)
+        })?;
+
+    Ok((token, expires_at))
+}
+
+pub fn revoke_session(conn: &mut PgConnection, session_id: i64) -> Result<()> {
+    diesel::update(sessions::table.find(session_id))
+        .set(sessions::revoked_at.eq(timestamp_after(Duration::ZERO)))
+        .execute(conn)
+        .map_err(|e| {
+            eprintln!("Failed to revoke session {}: {:?}", session_id, e);
+            eyre::eyre!("Failed to revoke session: {}", e)
+        })?;
+    Ok(())
+}
+
+pub fn find_session(conn: &mut PgConnection, token: &str) -> Result<Option<Session>> {
+    sessions::table
+        .filter(sessions::token_hash.eq(hash_token(token)))
+        .filter(sessions::revoked_at.is_null())
+        .filter(sessions::expires_at.gt(timestamp_after(Duration::ZERO)))
+        .select(Session::as_select())
+        .first(conn)
+        .optional()
+        .map_err(|e| eyre::eyre!("Failed to load session: {}", e))
+}
+
+// The address behind a valid `Authorization: Bearer <token>` session, in EIP-55 form. Write
+// endpoints act for this address instead of one supplied by the client.
+pub struct AuthenticatedUser {
+    pub session_id: i64,
+    pub address: String,
+    pub chain_id: u64,
+}
+
+impl FromRequestParts<Arc<AppState>> for AuthenticatedUser {
+    type Rejection = Response;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Self::Rejection> {
+        let unauthorized = |message: &str| {
+            (StatusCode::UNAUTHORIZED, Json(json!({"error": message}))).into_response()
+        };
+
+        let token = parts
+            .headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or_else(|| unauthorized("Missing session token"))?;
+
+        let conn = &mut state.db_pool.get().map_err(|e| {
+            eprintln!("Failed to get DB connection: {:?}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": format!("Internal server error: {}", e)})),
+            )
+                .into_response()
+        })?;
+        let session = find_session(conn, token)
+            .map_err(|e| {
+                eprintln!("Error loading session: {:?}", e);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({"error": format!("Internal server error: {}", e)})),
+                )
+                    .into_response()
+            })?
+            .ok_or_else(|| unauthorized("Invalid or expired session token"))?;
+
+        Ok(AuthenticatedUser {
+            session_id: session.id,
+            address: session.address,
+            chain_id: session.chain_id as u64,
+        })
+    }
+}
@@ -0,0 +1,261 @@
+use chrono::{DateTime, Utc};
+use ethers::core::utils::to_checksum;
+use ethers::types::{Address, Signature};
+use eyre::Result;
+
+const HEADER_SUFFIX: &str = " wants you to sign in with your Ethereum account:";
+
+// The parts of an EIP-4361 message the backend checks. The URI must be present; it, the
+// request ID and resources are not used otherwise.
+pub struct SiweMessage {
+    pub domain: String,
+    pub address: Address,
+    pub version: String,
+    pub chain_id: u64,
+    pub nonce: String,
+    pub issued_at: DateTime<Utc>,
+    pub expiration_time: Option<DateTime<Utc>>,
+    pub not_before: Option<DateTime<Utc>>,
+}
+
+impl SiweMessage {
+    pub fn parse(message: &str) -> Result<Self> {
+        let mut lines = message.lines();
+        let domain = lines
+            .next()
+            .and_then(|line| line.strip_suffix(HEADER_SUFFIX))
+            .filter(|domain| !domain.is_empty())
+            .ok_or_else(|| invalid("missing header"))?
+            .to_string();
+
+        let address_line = lines.next().ok_or_else(|| invalid("missing address"))?;
+        let address: Address = address_line
+            .parse()
+            .map_err(|_| invalid("invalid address"))?;
+        // EIP-4361 requires the EIP-55 checksummed form
+        if to_checksum(&address, None) != address_line {
+            return Err(invalid("address is not checksummed"));
+        }
+
+        let mut uri = None;
+        let mut version = None;
+        let mut chain_id = None;
+        let mut nonce = None;
+        let mut issued_at = None;
+        let mut expiration_time = None;
+        let mut not_before = None;
+        for line in lines {
+            if let Some(value) = line.strip_prefix("URI: ") {
+                uri = Some(value);
+            } else if let Some(value) = line.strip_prefix("Version: ") {
+                version = Some(value.to_string());
+            } else if let Some(value) = line.strip_prefix("Chain ID: ") {
+                chain_id = Some(value.parse::<u64>().map_err(|_| invalid("invalid chain id"))?);
+            } else if let Some(value) = line.strip_prefix("Nonce: ") {
+                nonce = Some(value.to_string());
+            } else if let Some(value) = line.strip_prefix("Issued At: ") {
+                issued_at = Some(parse_time(value)?);
+            } else if let Some(value) = line.strip_prefix("Expiration Time: ") {
+                expiration_time = Some(parse_time(value)?);
+            } else if let Some(value) = line.strip_prefix("Not Before: ") {
+                not_before = Some(parse_time(value)?);
+            }
+        }
+
+        if uri.is_none_or(str::is_empty) {
+            return Err(invalid("missing URI"));
+        }
+        let message = Self {
+            domain,
+            address,
+            version: version.ok_or_else(|| invalid("missing version"))?,
+            chain_id: chain_id.ok_or_else(|| invalid("missing chain id"))?,
+            nonce: nonce.ok_or_else(|| invalid("missing nonce"))?,
+            issued_at: issued_at.ok_or_else(|| invalid("missing issued at"))?,
+            expiration_time,
+            not_before,
+        };
+        if message.version != "1" {
+            return Err(invalid("unsupported version"));
+        }
+        if message.nonce.len() < 8 || !message.nonce.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(invalid("invalid nonce"));
+        }
+
+        Ok(message)
+    }
+
+    // Checks the message is meant for this backend and valid right now
+    pub fn validate(&self, domain: &str, now: DateTime<Utc>) -> Result<()> {
+        if self.domain != domain {
+            return Err(eyre::eyre!("SIWE domain mismatch: expected {}", domain));
+        }
+        if self.expiration_time.is_some_and(|expiration| expiration <= now) {
+            return Err(eyre::eyre!("SIWE message expired"));
+        }
+        if self.not_before.is_some_and(|not_before| not_before > now) || self.issued_at > now {
+            return Err(eyre::eyre!("SIWE message not yet valid"));
+        }
+        Ok(())
+    }
+
+    // The message is signed with personal_sign (EIP-191)
+    pub fn verify_signature(&self, message: &str, signature: &str) -> Result<()> {
+        let signature: Signature = signature
+            .parse()
+            .map_err(|_| eyre::eyre!("Invalid signature format"))?;
+        let signer = signature
+            .recover(message)
+            .map_err(|_| eyre::eyre!("Invalid signature"))?;
+        if signer != self.address {
+            return Err(eyre::eyre!("Invalid signature: signer does not match address"));
+        }
+        Ok(())
+    }
+}
+
+fn parse_time(value: &str) -> Result<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|time| time.with_timezone(&Utc))
+        .map_err(|_| invalid("invalid timestamp"))
+}
+
+fn invalid(reason: &str) -> eyre::Report {
+    eyre::eyre!("Invalid SIWE message: {}", reason)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use ethers::signers::{LocalWallet, Signer};
+    use ethers::utils::hash_message;
+
+    const DOMAIN: &str = "app.example.com";
+    // Well-known development keys; never funded outside local chains
+    const PRIVATE_KEY: &str = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    const OTHER_PRIVATE_KEY: &str =
+        "59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d";
+
+    fn wallet() -> LocalWallet {
+        PRIVATE_KEY.parse().unwrap()
+    }
+
+    fn time(value: &str) -> DateTime<Utc> {
+        parse_time(value).unwrap()
+    }
+
+    fn message(address: Address, extra: &str) -> String {
+        format!(
+            "{}{}\n{}\n\nSign in to the app.\n\nURI: https://{}\nVersion: 1\nChain ID: 1\nNonce: abcd1234\nIssued At: 2026-01-01T00:00:00Z{}",
+            DOMAIN,
+            HEADER_SUFFIX,
+            to_checksum(&address, None),
+            DOMAIN,
+            extra
+        )
+    }
+
+    fn sign(wallet: &LocalWallet, message: &str) -> String {
+        wallet.sign_hash(hash_message(message)).unwrap().to_string()
+    }
+
+    #[test]
+    fn parses_a_complete_message() {
+        let address = wallet().address();
+        let parsed = SiweMessage::parse(&message(
+            address,
+            "\nExpiration Time: 2026-01-01T01:00:00Z\nNot Before: 2026-01-01T00:00:00Z",
+        ))
+        .unwrap();
+
+        assert_eq!(parsed.domain, DOMAIN);
+        assert_eq!(parsed.address, address);
+        assert_eq!(parsed.version, "1");
+        assert_eq!(parsed.chain_id, 1);
+        assert_eq!(parsed.nonce, "abcd1234");
+        assert_eq!(parsed.issued_at, time("2026-01-01T00:00:00Z"));
+        assert_eq!(parsed.expiration_time, Some(time("2026-01-01T01:00:00Z")));
+        assert_eq!(parsed.not_before, Some(time("2026-01-01T00:00:00Z")));
+    }
+
+    #[test]
+    fn rejects_malformed_messages() {
+        let valid = message(wallet().address(), "");
+        let lowercase_address = format!("{:?}", wallet().address());
+        for broken in [
+            valid.replacen(HEADER_SUFFIX, " wants you to sign in:", 1),
+            valid.replacen(&to_checksum(&wallet().address(), None), &lowercase_address, 1),
+            valid.replacen("URI: https://app.example.com\n", "", 1),
+            valid.replacen("Version: 1", "Version: 2", 1),
+            valid.replacen("Chain ID: 1", "Chain ID: mainnet", 1),
+            valid.replacen("Issued At: 2026-01-01T00:00:00Z", "Issued At: yesterday", 1),
+        ] {
+            assert!(SiweMessage::parse(&broken).is_err(), "{}", broken);
+        }
+    }
+
+    #[test]
+    fn rejects_short_or_non_alphanumeric_nonces() {
+        let valid = message(wallet().address(), "");
+        for nonce in ["abc123", "abcd-1234", ""] {
+            let broken = valid.replacen("abcd1234", nonce, 1);
+            assert!(SiweMessage::parse(&broken).is_err(), "{}", nonce);
+        }
+        let missing = valid.replacen("Nonce: abcd1234\n", "", 1);
+        assert!(SiweMessage::parse(&missing).is_err());
+    }
+
+    #[test]
+    fn validate_checks_the_domain() {
+        let parsed = SiweMessage::parse(&message(wallet().address(), "")).unwrap();
+        let now = time("2026-01-01T00:30:00Z");
+
+        assert!(parsed.validate(DOMAIN, now).is_ok());
+        assert!(parsed.validate("evil.example.com", now).is_err());
+    }
+
+    #[test]
+    fn validate_checks_the_time_window() {
+        let parsed = SiweMessage::parse(&message(
+            wallet().address(),
+            "\nExpiration Time: 2026-01-01T01:00:00Z\nNot Before: 2026-01-01T00:10:00Z",
+        ))
+        .unwrap();
+        let expiration = time("2026-01-01T01:00:00Z");
+
+        assert!(parsed.validate(DOMAIN, time("2026-01-01T00:30:00Z")).is_ok());
+        assert!(parsed.validate(DOMAIN, expiration).is_err());
+        assert!(parsed.validate(DOMAIN, expiration + Duration::minutes(1)).is_err());
+        assert!(parsed.validate(DOMAIN, time("2026-01-01T00:05:00Z")).is_err());
+        // Issued in the future
+        assert!(parsed.validate(DOMAIN, time("2025-12-31T23:59:00Z")).is_err());
+    }
+
+    #[test]
+    fn verify_signature_accepts_the_address_owner() {
+        let wallet = wallet();
+        let text = message(wallet.address(), "");
+        let parsed = SiweMessage::parse(&text).unwrap();
+
+        assert!(parsed.verify_signature(&text, &sign(&wallet, &text)).is_ok());
+        assert!(
+            parsed
+                .verify_signature(&text, &format!("0x{}", sign(&wallet, &text)))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn verify_signature_rejects_other_signers_and_messages() {
+        let wallet = wallet();
+        let other: LocalWallet = OTHER_PRIVATE_KEY.parse().unwrap();
+        let text = message(wallet.address(), "");
+        let parsed = SiweMessage::parse(&text).unwrap();
+
+        assert!(parsed.verify_signature(&text, &sign(&other, &text)).is_err());
+        let tampered = text.replacen("abcd1234", "abcd5678", 1);
+        assert!(parsed.verify_signature(&text, &sign(&wallet, &tampered)).is_err());
+        assert!(parsed.verify_signature(&text, "not a signature").is_err());
+    }
+}
@@ -1,158 +1,151 @@
 use crate::authenticity::authenticity_abi::{
-    TrueAuthenticity,
-    TrueAuthenticityEvents,
     AuthenticityCreatedFilter,
-    ManufacturerRegisteredFilter
+    ManufacturerRegisteredFilter,
 };
 // use crate::authenticity::authenticity_abi::{, , /*ItemCreatedFilter*/};
+use crate::authenticity::eip712_domain::{rollback_domains, Eip712DomainChangedHandler};
 use crate::config::app_state::AppState;
+use crate::deployments::{deployment_id_for_contract, DeploymentContracts};
 use crate::contract_models::{NewContract, NewManufacturer};
+use crate::indexer::checkpoint::AUTHENTICITY_STREAM;
+use crate::indexer::contract_indexer::ContractIndexer;
+use crate::indexer::event_meta::EventMeta;
+use crate::indexer::handler::EventHandler;
+use crate::indexer::log_source::LogSource;
 use crate::schema::{contracts, manufacturers};
-use chrono::Utc;
+use async_trait::async_trait;
 use diesel::prelude::*;
 use diesel::{PgConnection, RunQueryDsl};
-use ecdsa::SigningKey;
-use ethers::core::k256::Secp256k1;
 use ethers::core::utils::to_checksum;
 use ethers::prelude::*;
 use eyre::Result;
 use std::sync::Arc;
 
-pub async fn listen_for_authenticity_events(state: &Arc<AppState>) -> Result<()> {
-    let contract = state.authenticity_contract.clone();
-    let client = contract.client();
+pub async fn listen_for_authenticity_events(
+    state: &Arc<AppState>,
+    deployment: &DeploymentContracts,
+) -> Result<()> {
+    let client = deployment.authenticity_contract.client();
+    authenticity_indexer(state, deployment)
+        .run(state, client.as_ref())
+        .await
+}
 
+pub fn authenticity_indexer(state: &AppState, deployment: &DeploymentContracts) -> ContractIndexer {
+    let deployment_id = deployment.id;
+    ContractIndexer::new(
+        AUTHENTICITY_STREAM,
+        deployment.chain_id,
+        deployment.authenticity_contract.address(),
+        deployment.authenticity_deployment_block,
+        LogSource::for_chain(state, deployment.chain_id),
+        rollback_authenticity_projections,
+    )
+    .with_handler(ManufacturerRegisteredHandler { deployment_id })
+    .with_handler(AuthenticityCreatedHandler { deployment_id })
+    .with_handler(Eip712DomainChangedHandler {
+        contract: deployment.authenticity_contract.clone(),
+        deployment_block: deployment.authenticity_deployment_block,
+        deployment_id,
+    })
+}
 
-    // Fetch historical events from the last 1,000 blocks in chunks
-    let latest_block = client.get_block_number().await.map_err(|e| {
-        eprintln!("Failed to get latest block: {:?}", e.to_string());
-        eyre::eyre!("Failed to get latest block: {}", e)
-    })?;
-    let from_block = latest_block.saturating_sub(U64::from(20));
-    let chunk_size = 4;
+struct ManufacturerRegisteredHandler {
+    deployment_id: i32,
+}
 
-    // Process historical events in chunks
-    let mut current_block = from_block;
-    while current_block < latest_block {
-        let to_block = (current_block + chunk_size).min(latest_block);
-        eprintln!(
-            "Querying Authenticity historical events from block {} to {} (range: {})",
-            current_block,
-            to_block,
-            to_block - current_block + 1
-        );
+#[async_trait]
+impl EventHandler for ManufacturerRegisteredHandler {
+    type Event = ManufacturerRegisteredFilter;
+    type Context = ();
 
-        // Event filters for the chunk
-        let manufacturer_registered_filter = contract
-            .event::<ManufacturerRegisteredFilter>()
-            .from_block(current_block)
-            .to_block(to_block);
-
-        let authenticity_created_filter = contract
-            .event::<AuthenticityCreatedFilter>()
-            .from_block(current_block)
-            .to_block(to_block);
-
-        // Fetch historical events with metadata
-        let manufacturer_registered_logs = manufacturer_registered_filter
-            .query_with_meta()
-            .await
-            .map_err(|e| {
-                eprintln!(
-                    "Failed to query ManufacturerRegistered events for blocks {} to {}: {:?}",
-                    current_block, to_block, e.to_string()
-                );
-                eyre::eyre!("Failed to query ManufacturerRegistered events: {}", e)
-            })?;
-
-        let authenticity_created_logs = authenticity_created_filter
-            .query_with_meta()
-            .await
-            .map_err(|e| {
-                eprintln!(
-                    "Failed to query AuthenticityCreated events for blocks {} to {}: {:?}",
-                    current_block, to_block, e.to_string()
-                );
-                eyre::eyre!("Failed to query AuthenticityCreated events: {}", e)
-            })?;
-
-        // Process historical events
-        let conn = &mut state.db_pool.get().map_err(|e| {
-            eprintln!("Failed to get DB connection: {:?}", e);
-            eyre::eyre!("Failed to get DB connection: {}", e)
-        })?;
+    async fn prepare(&self, _event: &Self::Event, _meta: &EventMeta) -> Result<()> {
+        Ok(())
+    }
 
-        for (event, meta) in manufacturer_registered_logs {
-            let txn_hash = Some(format!("0x{}", hex::encode(meta.transaction_hash)));
-            process_manufacturer_registered_event(&event, conn, txn_hash, &contract).await?;
-        }
+    fn project(
+        &self,
+        conn: &mut PgConnection,
+        event: &Self::Event,
+        _context: (),
+        meta: &EventMeta,
+    ) -> Result<()> {
+        process_manufacturer_registered_event(event, self.deployment_id, conn, meta)
+    }
+}
 
-        for (event, meta) in authenticity_created_logs {
-            let txn_hash = Some(format!("0x{}", hex::encode(meta.transaction_hash)));
-            process_authenticity_created_event(&event, conn, txn_hash)?;
-        }
+struct AuthenticityCreatedHandler {
+    deployment_id: i32,
+}
 
-        current_block = to_block + 1;
+#[async_trait]
+impl EventHandler for AuthenticityCreatedHandler {
+    type Event = AuthenticityCreatedFilter;
+    type Context = ();
+
+    async fn prepare(&self, _event: &Self::Event, _meta: &EventMeta) -> Result<()> {
+        Ok(())
     }
 
-    // Stream future events
-    eprintln!("Starting Authenticity event stream from block {}", latest_block + 1);
-    let events = contract.events().from_block(latest_block + 1);
+    fn project(
+        &self,
+        conn: &mut PgConnection,
+        event: &Self::Event,
+        _context: (),
+        meta: &EventMeta,
+    ) -> Result<()> {
+        process_authenticity_created_event(event, self.deployment_id, conn, meta)
+    }
+}
 
-    let mut stream = events.stream_with_meta().await.map_err(|e| {
-        eprintln!("Failed to create event stream: {:?}", e.to_string());
-        eyre::eyre!("Failed to create event stream: {}", e)
+// Removes rows indexed from orphaned blocks so the canonical ones can be replayed
+fn rollback_authenticity_projections(
+    conn: &mut PgConnection,
+    chain_id: u64,
+    contract_address: &str,
+    ancestor: U64,
+) -> Result<()> {
+    let ancestor = ancestor.as_u64() as i64;
+    let deployment_id = deployment_id_for_contract(conn, chain_id, contract_address)?;
+
+    let removed_manufacturers = diesel::delete(
+        manufacturers::table
+            .filter(manufacturers::deployment_id.eq(deployment_id))
+            .filter(manufacturers::block_number.gt(ancestor)),
+    )
+    .execute(conn)
+    .map_err(|e| {
+        eprintln!("Failed to roll back manufacturers: {:?}", e);
+        eyre::eyre!("Failed to roll back manufacturers: {}", e)
     })?;
 
+    let removed_contracts = diesel::delete(
+        contracts::table
+            .filter(contracts::deployment_id.eq(deployment_id))
+            .filter(contracts::contract_address.eq(contract_address))
+            .filter(contracts::block_number.gt(ancestor)),
+    )
+    .execute(conn)
+    .map_err(|e| {
+        eprintln!("Failed to roll back contracts: {:?}", e);
+        eyre::eyre!("Failed to roll back contracts: {}", e)
+    })?;
 
-    loop {
-        match stream.next().await {
-
-            Some(Ok((TrueAuthenticityEvents::ManufacturerRegisteredFilter(event), meta))) => {
-                let txn_hash = Some(format!("0x{}", hex::encode(meta.transaction_hash)));
-                let conn = &mut state.db_pool.get().map_err(|e| {
-                    eprintln!("Failed to get DB connection: {:?}", e);
-                    eyre::eyre!("Failed to get DB connection: {}", e)
-                })?;
-                process_manufacturer_registered_event(&event, conn, txn_hash, &contract).await?;
-            }
-
-            Some(Ok((TrueAuthenticityEvents::AuthenticityCreatedFilter(event), meta))) => {
-                let txn_hash = Some(format!("0x{}", hex::encode(meta.transaction_hash)));
-                let conn = &mut state.db_pool.get().map_err(|e| {
-                    eprintln!("Failed to get DB connection: {:?}", e);
-                    eyre::eyre!("Failed to get DB connection: {}", e)
-                })?;
-                process_authenticity_created_event(&event, conn, txn_hash)?;
-            }
-
-            Some(Ok((TrueAuthenticityEvents::Eip712DomainChangedFilter(_event), meta))) => {
-                eprintln!(
-                    "EIP712DomainChanged event received (tx: 0x{})",
-                    hex::encode(meta.transaction_hash)
-                );
-                continue;
-            }
-            Some(Err(e)) => {
-                eprintln!("Event stream error: {:?}", e.to_string());
-                tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
-                continue;
-            }
-            None => {
-                eprintln!("Event stream ended unexpectedly");
-                tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
-                continue;
-            }
-        }
-    }
-}
+    let removed_domains = rollback_domains(conn, chain_id, contract_address, ancestor)?;
+
+    eprintln!(
+        "Rolled back {} manufacturer(s), {} contract(s) and {} EIP-712 domain(s) after block {}",
+        removed_manufacturers, removed_contracts, removed_domains, ancestor
+    );
 
+    Ok(())
+}
 
-async fn process_manufacturer_registered_event(
+fn process_manufacturer_registered_event(
     event: &ManufacturerRegisteredFilter,
+    deployment_id: i32,
     conn: &mut PgConnection,
-    txn_hash: Option<String>,
-    contract: &TrueAuthenticity<SignerMiddleware<Provider<Http>, Wallet<SigningKey<Secp256k1>>>>,
+    meta: &EventMeta,
 ) -> Result<()> {
     let manufacturer_address = to_checksum(&event.manufacturer_address, None);
     let manufacturer_name = event.username.clone();
@@ -160,6 +153,7 @@ async fn process_manufacturer_registered_event(
 
     // Check if manufacturer exists
     let exists: bool = manufacturers::table
+        .filter(manufacturers::deployment_id.eq(deployment_id))
         .filter(manufacturers::manufacturer_address.eq(&manufacturer_address))
         .select(diesel::dsl::count_star())
         .first::<i64>(conn)
@@ -174,8 +168,8 @@ async fn process_manufacturer_registered_event(
 
     if exists {
         eprintln!(
-            "Skipping duplicate manufacturer registration for {} (tx: {:?})",
-            manufacturer_address, txn_hash
+            "Skipping duplicate manufacturer registration for {} (tx: {})",
+            manufacturer_address, meta.tnx_hash
         );
         return Ok(());
     }
@@ -186,10 +180,13 @@ async fn process_manufacturer_registered_event(
             manufacturer_address,
             manufacturer_name,
             is_registered: true,
-            registered_at:  Utc::now().to_rfc3339(),
-            tnx_hash: txn_hash.ok_or_else(|| {
-                eyre::eyre!("Transaction hash is required for manufacturer registration")
-            })?,
+            registered_at: meta.block_timestamp.clone(),
+            tnx_hash: meta.tnx_hash.clone(),
+            block_number: meta.block_number,
+            block_hash: meta.block_hash.clone(),
+            log_index: meta.log_index,
+            deployment_id,
+            chain_id: meta.chain_id,
         })
         .execute(conn)
         .map_err(|e| {
@@ -203,14 +200,16 @@ async fn process_manufacturer_registered_event(
 
 fn process_authenticity_created_event(
     event: &AuthenticityCreatedFilter,
+    deployment_id: i32,
     conn: &mut PgConnection,
-    txn_hash: Option<String>,
+    meta: &EventMeta,
 ) -> Result<()> {
     let contract_address = to_checksum(&event.contract_address, None);
     let owner = to_checksum(&event.owner, None);
 
     // Check if contract exists
     let exists: bool = contracts::table
+        .filter(contracts::deployment_id.eq(deployment_id))
         .filter(contracts::contract_address.eq(&contract_address))
         .select(diesel::dsl::count_star())
         .first::<i64>(conn)
@@ -225,8 +224,8 @@ fn process_authenticity_created_event(
 
     if exists {
         eprintln!(
-            "Skipping duplicate contract created for {} (tx: {:?})",
-            contract_address, txn_hash
+            "Skipping duplicate contract created for {} (tx: {})",
+            contract_address, meta.tnx_hash
         );
         return Ok(());
     }
@@ -236,10 +235,13 @@ fn process_authenticity_created_event(
         .values(NewContract {
             contract_address,
             owner,
-            tnx_hash: txn_hash.ok_or_else(|| {
-                eyre::eyre!("Transaction hash is required for manufacturer registration")
-            })?,
-            created_at:  Utc::now().to_rfc3339(),
+            tnx_hash: meta.tnx_hash.clone(),
+            created_at: meta.block_timestamp.clone(),
+            block_number: meta.block_number,
+            block_hash: meta.block_hash.clone(),
+            log_index: meta.log_index,
+            deployment_id,
+            chain_id: meta.chain_id,
         })
         .returning(crate::contract_models::Contract::as_returning())
         .get_result(conn)
@@ -0,0 +1,279 @@
+use crate::authenticity::authenticity_abi::{Eip712DomainChangedFilter, TrueAuthenticity};
+use crate::contract_models::{Eip712DomainRecord, NewEip712Domain};
+use crate::indexer::event_meta::EventMeta;
+use crate::indexer::handler::EventHandler;
+use crate::config::chains::ChainConfig;
+use crate::models::certificate_model::chain_domain;
+use crate::schema::eip712_domains;
+use async_trait::async_trait;
+use diesel::prelude::*;
+use diesel::{PgConnection, RunQueryDsl};
+use ecdsa::SigningKey;
+use ethers::core::k256::Secp256k1;
+use ethers::core::utils::to_checksum;
+use ethers::prelude::*;
+use ethers::types::transaction::eip712::EIP712Domain;
+use eyre::Result;
+
+// Domain statuses reported when verifying a certificate
+pub const DOMAIN_ACTIVE: &str = "active";
+pub const DOMAIN_LEGACY: &str = "legacy_domain";
+
+pub struct Eip712DomainChangedHandler {
+    pub contract: TrueAuthenticity<SignerMiddleware<Provider<Http>, Wallet<SigningKey<Secp256k1>>>>,
+    pub deployment_block: Option<U64>,
+    pub deployment_id: i32,
+}
+
+pub struct DomainChange {
+    previous: Option<EIP712Domain>,
+    current: EIP712Domain,
+}
+
+#[async_trait]
+impl EventHandler for Eip712DomainChangedHandler {
+    type Event = Eip712DomainChangedFilter;
+    type Context = DomainChange;
+
+    // The event carries no data, so the domain is read from the contract (EIP-5267) at the
+    // block of the change. The domain in force just before it is read as well, so
+    // certificates signed before the first recorded change keep verifying.
+    async fn prepare(&self, _event: &Self::Event, meta: &EventMeta) -> Result<DomainChange> {
+        let block_number = meta.block_number as u64;
+        let current = fetch_domain(&self.contract, block_number).await?;
+        let previous = match self.deployment_block {
+            Some(deployed) if U64::from(block_number) <= deployed => None,
+            Some(_) => Some(fetch_domain(&self.contract, block_number - 1).await?),
+            // Without a deployment block the contract may not exist at the previous block
+            None if block_number > 0 => fetch_domain(&self.contract, block_number - 1).await.ok(),
+            None => None,
+        };
+
+        Ok(DomainChange { previous, current })
+    }
+
+    fn project(
+        &self,
+        conn: &mut PgConnection,
+        _event: &Self::Event,
+        change: DomainChange,
+        meta: &EventMeta,
+    ) -> Result<()> {
+        let contract_address = to_checksum(&self.contract.address(), None);
+        record_domain_change(conn, &contract_address, self.deployment_id, change, meta)
+    }
+}
+
+async fn fetch_domain(
+    contract: &TrueAuthenticity<SignerMiddleware<Provider<Http>, Wallet<SigningKey<Secp256k1>>>>,
+    block_number: u64,
+) -> Result<EIP712Domain> {
+    let (_fields, name, version, chain_id, verifying_contract, _salt, _extensions) = contract
+        .eip_712_domain()
+        .block(U64::from(block_number))
+        .call()
+        .await
+        .map_err(|e| {
+            eprintln!(
+                "Failed to call eip712Domain at block {}: {:?}",
+                block_number,
+                e.to_string()
+            );
+            eyre::eyre!("Failed to call eip712Domain: {}", e)
+        })?;
+
+    Ok(EIP712Domain {
+        name: Some(name),
+        version: Some(version),
+        chain_id: Some(chain_id),
+        verifying_contract: Some(verifying_contract),
+        salt: None,
+    })
+}
+
+fn new_domain_row(
+    contract_address: &str,
+    deployment_id: i32,
+    domain: &EIP712Domain,
+    is_active: bool,
+    meta: &EventMeta,
+) -> NewEip712Domain {
+    NewEip712Domain {
+        contract_address: contract_address.to_string(),
+        name: domain.name.clone().unwrap_or_default(),
+        version: domain.version.clone().unwrap_or_default(),
+        chain_id: domain.chain_id.unwrap_or_default().low_u64() as i64,
+        verifying_contract: to_checksum(&domain.verifying_contract.unwrap_or_default(), None),
+        is_active,
+        block_number: meta.block_number,
+        activated_block: meta.block_number,
+        tnx_hash: meta.tnx_hash.clone(),
+        created_at: meta.block_timestamp.clone(),
+        activated_at: meta.block_timestamp.clone(),
+        deployment_id,
+    }
+}
+
+// Records the new domain as the active one. A domain that was seen before is reactivated
+// rather than duplicated.
+fn record_domain_change(
+    conn: &mut PgConnection,
+    contract_address: &str,
+    deployment_id: i32,
+    change: DomainChange,
+    meta: &EventMeta,
+) -> Result<()> {
+    if let Some(previous) = &change.previous {
+        diesel::insert_into(eip712_domains::table)
+            .values(new_domain_row(contract_address, deployment_id, previous, false, meta))
+            .on_conflict_do_nothing()
+            .execute(conn)
+            .map_err(|e| {
+                eprintln!("Failed to record previous EIP-712 domain: {:?}", e);
+                eyre::eyre!("Failed to record previous EIP-712 domain: {}", e)
+            })?;
+    }
+
+    diesel::update(
+        eip712_domains::table
+            .filter(eip712_domains::chain_id.eq(meta.chain_id))
+            .filter(eip712_domains::contract_address.eq(contract_address)),
+    )
+    .set(eip712_domains::is_active.eq(false))
+    .execute(conn)
+    .map_err(|e| eyre::eyre!("Failed to deactivate EIP-712 domains: {}", e))?;
+
+    diesel::insert_into(eip712_domains::table)
+        .values(new_domain_row(contract_address, deployment_id, &change.current, true, meta))
+        .on_conflict((
+            eip712_domains::contract_address,
+            eip712_domains::name,
+            eip712_domains::version,
+            eip712_domains::chain_id,
+            eip712_domains::verifying_contract,
+        ))
+        .do_update()
+        .set((
+            eip712_domains::is_active.eq(true),
+            eip712_domains::activated_block.eq(meta.block_number),
+            eip712_domains::activated_at.eq(&meta.block_timestamp),
+        ))
+        .execute(conn)
+        .map_err(|e| {
+            eprintln!("Failed to record EIP-712 domain: {:?}", e);
+            eyre::eyre!("Failed to record EIP-712 domain: {}", e)
+        })?;
+
+    eprintln!(
+        "EIP-712 domain changed to {} v{} (tx: {})",
+        change.current.name.unwrap_or_default(),
+        change.current.version.unwrap_or_default(),
+        meta.tnx_hash
+    );
+
+    Ok(())
+}
+
+// Drops domains first seen in orphaned blocks and reactivates the latest remaining one. A
+// domain reactivated in an orphaned block falls back to the block it was first seen in.
+pub fn rollback_domains(
+    conn: &mut PgConnection,
+    chain_id: u64,
+    contract_address: &str,
+    ancestor: i64,
+) -> Result<usize> {
+    let chain_id = chain_id as i64;
+    let removed = diesel::delete(
+        eip712_domains::table
+            .filter(eip712_domains::chain_id.eq(chain_id))
+            .filter(eip712_domains::contract_address.eq(contract_address))
+            .filter(eip712_domains::block_number.gt(ancestor)),
+    )
+    .execute(conn)
+    .map_err(|e| eyre::eyre!("Failed to roll back EIP-712 domains: {}", e))?;
+
+    diesel::update(
+        eip712_domains::table
+            .filter(eip712_domains::chain_id.eq(chain_id))
+            .filter(eip712_domains::contract_address.eq(contract_address))
+            .filter(eip712_domains::activated_block.gt(ancestor)),
+    )
+    .set((
+        eip712_domains::activated_block.eq(eip712_domains::block_number),
+        eip712_domains::activated_at.eq(eip712_domains::created_at),
+    ))
+    .execute(conn)
+    .map_err(|e| eyre::eyre!("Failed to roll back EIP-712 domain activations: {}", e))?;
+
+    let latest: Option<i64> = eip712_domains::table
+        .filter(eip712_domains::chain_id.eq(chain_id))
+        .filter(eip712_domains::contract_address.eq(contract_address))
+        .order((
+            eip712_domains::activated_block.desc(),
+            eip712_domains::id.desc(),
+        ))
+        .select(eip712_domains::id)
+        .first(conn)
+        .optional()
+        .map_err(|e| eyre::eyre!("Failed to load latest EIP-712 domain: {}", e))?;
+
+    diesel::update(
+        eip712_domains::table
+            .filter(eip712_domains::chain_id.eq(chain_id))
+            .filter(eip712_domains::contract_address.eq(contract_address)),
+    )
+    .set(eip712_domains::is_active.eq(eip712_domains::id.eq(latest.unwrap_or_default())))
+    .execute(conn)
+    .map_err(|e| eyre::eyre!("Failed to reactivate EIP-712 domain: {}", e))?;
+
+    Ok(removed)
+}
+
+// Known domains for a contract on a chain, the active one first and the rest newest first
+pub fn load_domains(
+    conn: &mut PgConnection,
+    chain_id: u64,
+    contract_address: &str,
+) -> Result<Vec<Eip712DomainRecord>> {
+    eip712_domains::table
+        .filter(eip712_domains::chain_id.eq(chain_id as i64))
+        .filter(eip712_domains::contract_address.eq(contract_address))
+        .order((
+            eip712_domains::is_active.desc(),
+            eip712_domains::activated_block.desc(),
+        ))
+        .select(Eip712DomainRecord::as_select())
+        .load(conn)
+        .map_err(|e| eyre::eyre!("Failed to load EIP-712 domains: {}", e))
+}
+
+// Domain new certificates are signed under on a chain. Until the indexer has seen a domain
+// change the configured name and version are used with the chain's authenticity contract.
+pub fn active_domain(conn: &mut PgConnection, chain: &ChainConfig) -> Result<EIP712Domain> {
+    let verifying_contract = chain.authenticity_contract.address();
+    let contract_address = to_checksum(&verifying_contract, None);
+    match load_domains(conn, chain.chain_id, &contract_address)?.first() {
+        Some(record) if record.is_active => record.to_domain(),
+        _ => Ok(chain_domain(chain.chain_id, verifying_contract)),
+    }
+}
+
+impl Eip712DomainRecord {
+    pub fn to_domain(&self) -> Result<EIP712Domain> {
+        let verifying_contract: Address = self.verifying_contract.parse().map_err(|e| {
+            eyre::eyre!(
+                "Invalid verifying contract {}: {}",
+                self.verifying_contract,
+                e
+            )
+        })?;
+
+        Ok(EIP712Domain {
+            name: Some(self.name.clone()),
+            version: Some(self.version.clone()),
+            chain_id: Some(U256::from(self.chain_id as u64)),
+            verifying_contract: Some(verifying_contract),
+            salt: None,
+        })
+    }
+}
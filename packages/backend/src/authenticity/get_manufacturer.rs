@@ -24,6 +24,16 @@ use utoipa::{OpenApi, ToSchema};
             "username" = Option<String>, 
             Query, description = "Manufacturer's username", 
             example = "SAMSUNG"
+        ),
+        (
+            "deployment_id" = Option<i32>,
+            Query, description = "Only return manufacturers registered on this deployment",
+            example = 1
+        ),
+        (
+            "chain_id" = Option<i64>,
+            Query, description = "Only return manufacturers registered on this chain; every chain when omitted",
+            example = 84532
         )
     ),
     responses(
@@ -92,6 +102,13 @@ async fn get_manufacturer_internal(
         query_builder = query_builder.or_filter(manufacturers::manufacturer_name.eq(username));
     }
 
+    if let Some(deployment_id) = query.deployment_id {
+        query_builder = query_builder.filter(manufacturers::deployment_id.eq(deployment_id));
+    }
+    if let Some(chain_id) = query.chain_id {
+        query_builder = query_builder.filter(manufacturers::chain_id.eq(chain_id));
+    }
+
     let fetched_manufacturer = query_builder
         .first::<Manufacturer>(&mut conn)
         .optional()
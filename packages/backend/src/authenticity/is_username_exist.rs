@@ -17,6 +17,10 @@ use utoipa::ToSchema;
 pub struct IsExistsQuery {
     #[schema(example = "john_doe")]
     username: Option<String>,
+    #[schema(example = 1)]
+    deployment_id: Option<i32>,
+    #[schema(example = 84532)]
+    chain_id: Option<i64>,
 }
 
 #[derive(Serialize, ToSchema)]
@@ -28,7 +32,9 @@ pub struct IsExistsResponse {
     get,
     path = "/api/manufacturer/exists",
     params(
-        ("username" = Option<String>, Query, description = "Manufacturer's username", example = "john_doe")
+        ("username" = Option<String>, Query, description = "Manufacturer's username", example = "john_doe"),
+        ("deployment_id" = Option<i32>, Query, description = "Only return rows indexed from this deployment", example = 1),
+        ("chain_id" = Option<i64>, Query, description = "Only return rows indexed from this chain; every chain when omitted", example = 84532)
     ),
     responses(
         (status = 200, description = "Check if manufacturer exists", body = IsExistsResponse, example = json!({
@@ -74,8 +80,17 @@ async fn check_manufacturer_exists_internal(
         eyre::eyre!("Failed to get DB connection: {}", e)
     })?;
 
-    let exists: bool = manufacturers::table
+    let mut exists_query = manufacturers::table
         .filter(manufacturers::manufacturer_name.eq(username))
+        .into_boxed();
+    if let Some(deployment_id) = query.deployment_id {
+        exists_query = exists_query.filter(manufacturers::deployment_id.eq(deployment_id));
+    }
+    if let Some(chain_id) = query.chain_id {
+        exists_query = exists_query.filter(manufacturers::chain_id.eq(chain_id));
+    }
+
+    let exists: bool = exists_query
         .select(diesel::dsl::count_star())
         .first::<i64>(&mut conn)
         .map(|count| count > 0)
@@ -1,4 +1,5 @@
 pub mod authenticity_event_listener;
+pub mod eip712_domain;
 pub mod get_manufacturer;
 pub mod is_username_exist;
 pub mod authenticity_abi;
@@ -1,8 +1,10 @@
 // use crate::authenticity::get_certificate::CertificateResponse;
+use crate::auth::api_key::ApiKeyAuth;
 use crate::config::app_state::AppState;
+use crate::contract_models::ChainQuery;
 use crate::schema::{certificates, manufacturers};
 use axum::Json;
-use axum::extract::{Query, State};
+use axum::extract::{Extension, Query, State};
 use axum::http::StatusCode;
 use diesel::prelude::*;
 use diesel::{PgConnection, RunQueryDsl};
@@ -20,12 +22,19 @@ pub struct Certificates {
     pub owner: String,
     pub metadata_hash: String,
     pub metadata: Vec<Option<String>>,
-    pub signature: String
+    pub signature: String,
+    // Set from the `chain_id` the certificate is saved for
+    #[serde(default)]
+    #[schema(example = 84532)]
+    pub chain_id: Option<i64>,
 }
 // Struct for GET query
 #[derive(Deserialize, Serialize, ToSchema)]
 pub struct CertificateDTO {
     pub unique_id: String,
+    #[serde(default)]
+    #[schema(example = 84532)]
+    pub chain_id: Option<i64>,
 }
 
 // Error response schema for Swagger
@@ -39,6 +48,9 @@ pub struct ErrorResponse {
     post,
     path = "/api/certificate/create",
     request_body = Certificates,
+    params(
+        ("chain_id" = Option<u64>, Query, description = "Chain the certificate is issued on; the default chain when omitted", example = 84532)
+    ),
     responses(
         (status = 200, description = "Certificate and signature saved successfully", body = Certificates, example = json!({
             "name": "iPhone 15 Pro",
@@ -49,16 +61,31 @@ pub struct ErrorResponse {
             "metadata_hash": "0xabcdef1234567890abcdef1234567890abcdef1234567890abcdef1234567890",
             "metadata": ["Black", "128GB", "Pro Model"]
         })),
-        (status = 400, description = "Invalid input (e.g., empty unique_id or invalid owner)", body = ErrorResponse, example = json!({"error": "Unique ID cannot be empty"})),
-        (status = 404, description = "Manufacturer not found", body = ErrorResponse, example = json!({"error": "Manufacturer not found"})),
+        (status = 400, description = "Invalid input (e.g., empty unique_id or unsupported chain)", body = ErrorResponse, example = json!({"error": "Unique ID cannot be empty"})),
+        (status = 401, description = "Missing, invalid or revoked API key", body = ErrorResponse, example = json!({"error": "Missing API key"})),
+        (status = 403, description = "API key lacks the certificates:write scope, or the owner is not the key's manufacturer", body = ErrorResponse, example = json!({"error": "Owner address does not match the API key's manufacturer"})),
+        (status = 404, description = "Manufacturer not found on the chain", body = ErrorResponse, example = json!({"error": "Manufacturer not found"})),
         (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({"error": "Failed to save certificate: Database error"}))
     ),
+    security(("api_key" = [])),
     tag = "Certificates"
 )]
 pub async fn save_certificate(
     State(state): State<Arc<AppState>>,
-    Json(payload): Json<Certificates>,
+    Extension(api_key): Extension<ApiKeyAuth>,
+    Query(query): Query<ChainQuery>,
+    Json(mut payload): Json<Certificates>,
 ) -> axum::response::Result<Json<CertificateDTO>> {
+    let chain_id = state
+        .chain(query.chain_id)
+        .map_err(|e| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": e.to_string() })),
+            )
+        })?
+        .chain_id as i64;
+    payload.chain_id = Some(chain_id);
 
     let conn = &mut state.db_pool.get().map_err(|e| {
         (
@@ -76,9 +103,19 @@ pub async fn save_certificate(
             .into());
     }
 
-    // Verify manufacturer exists
+    // Certificates can only be issued for the manufacturer the API key belongs to
+    if !api_key.manufacturer_address.eq_ignore_ascii_case(&payload.owner) {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({ "error": "Owner address does not match the API key's manufacturer" })),
+        )
+            .into());
+    }
+
+    // Verify manufacturer exists on the chain
     let manufacturer_address = manufacturers::table
-        .filter(manufacturers::manufacturer_address.eq(&payload.owner))
+        .filter(manufacturers::chain_id.eq(chain_id))
+        .filter(manufacturers::manufacturer_address.eq(&api_key.manufacturer_address))
         .select(manufacturers::manufacturer_address)
         .first::<String>(conn)
         .optional()
@@ -128,6 +165,7 @@ pub async fn save_certificate(
 
     let response = CertificateDTO {
         unique_id: payload.unique_id,
+        chain_id: payload.chain_id,
     };
 
     Ok(Json(response))
@@ -138,7 +176,8 @@ pub async fn save_certificate(
     get,
     path = "/api/certificate/{item_id}",
     params(
-        ("unique_id" = String, Query, description = "Unique ID of the certificate", example = "123")
+        ("unique_id" = String, Query, description = "Unique ID of the certificate", example = "123"),
+        ("chain_id" = Option<i64>, Query, description = "Chain the certificate was issued on; any chain when omitted", example = 84532)
     ),
     responses(
         (status = 200, description = "Certificate and signature retrieved successfully", body = (Certificates, String), example = json!([
@@ -178,9 +217,15 @@ pub async fn get_certificate(
             .into());
     }
 
-    let cert = certificates::table
+    let mut cert_query = certificates::table
         .filter(certificates::unique_id.eq(query.unique_id))
         .select(Certificates::as_select())
+        .into_boxed();
+    if let Some(chain_id) = query.chain_id {
+        cert_query = cert_query.filter(certificates::chain_id.eq(chain_id));
+    }
+
+    let cert = cert_query
         .first::<Certificates>(conn)
         .optional()
         .map_err(|e| {
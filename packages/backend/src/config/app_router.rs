@@ -6,10 +6,7 @@ use crate::ownership::get_my_items::{ get_owner_items};
 use crate::ownership::get_user_info::get_user;
 use crate::ownership::is_name_exist::user_exists;
 use crate::services::create_eip712::create_certificate;
-use crate::services::other_tests::{
-    generate_signature, get_owner, manufacturer_registers,
-    verify_signature,
-};
+use crate::services::other_tests::{generate_signature, get_owner, verify_signature};
 use crate::services::qr_code::generate_qr_code;
 use crate::services::verify_authenticity::verify_authenticity;
 use axum::routing::{get, post};
@@ -30,6 +27,29 @@ use crate::ownership::revoke_ownership_code::revoke_ownership_code;
 use crate::ownership::transfer_ownership_code::transfer_ownership_code;
 use crate::services::claim_ownership::claim_ownership;
 use crate::services::create_item::create_item;
+use crate::services::failed_events::{get_failed_events, replay_failed_event_handler};
+use crate::services::deployments::get_deployments;
+use crate::services::chains::get_chains;
+use crate::services::auth::{get_nonce, logout, verify_siwe};
+use crate::services::ownership_intent::prepare_ownership_intent;
+use crate::services::api_keys::{
+    create_manufacturer_api_key, get_manufacturer_api_keys, revoke_manufacturer_api_key,
+};
+use crate::services::manufacturer_api::{get_manufacturer_analytics, get_manufacturer_items};
+use crate::auth::api_key::{
+    require_scope, SCOPE_ANALYTICS_READ, SCOPE_CERTIFICATES_WRITE, SCOPE_ITEMS_READ,
+};
+use crate::auth::admin::{require_role, AdminRole};
+use crate::rate_limit::{rate_limit, RouteClass};
+use crate::services::admin::{
+    get_admin_roles, get_audit_log, grant_admin_role, register_manufacturer, revoke_admin_role,
+};
+use axum::extract::{Request, State};
+use axum::middleware::{self, Next};
+use crate::services::reconciliations::get_reconciliation_runs;
+use crate::services::health::health;
+use crate::services::jobs::get_job;
+use crate::services::indexer_status::{indexer_metrics, indexer_status};
 use crate::services::register_user::user_register;
 use crate::services::set_autheticity::set_authenticity;
 use crate::sync::sync;
@@ -41,11 +61,78 @@ pub fn paths(state: Arc<AppState>, path: RouterPath) -> Router {
         .allow_methods(Any)
         .allow_headers(Any);
 
+    // Routes manufacturer systems call with an API key, one scope per group
+    let certificates_write = Router::new()
+        .route(&path.save_certificate, get(save_certificate))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            |state: State<Arc<AppState>>, request: Request, next: Next| {
+                require_scope(state, SCOPE_CERTIFICATES_WRITE, request, next)
+            },
+        ));
+    let items_read = Router::new()
+        .route(&path.manufacturer_items, get(get_manufacturer_items))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            |state: State<Arc<AppState>>, request: Request, next: Next| {
+                require_scope(state, SCOPE_ITEMS_READ, request, next)
+            },
+        ));
+    let analytics_read = Router::new()
+        .route(&path.manufacturer_analytics, get(get_manufacturer_analytics))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            |state: State<Arc<AppState>>, request: Request, next: Next| {
+                require_scope(state, SCOPE_ANALYTICS_READ, request, next)
+            },
+        ));
+
+    // Routes that make the server wallet pay gas
+    let gas_spending = Router::new()
+        .route(&path.user_register, post(user_register))
+        .route(&path.claim_ownership, post(claim_ownership))
+        .route(&path.create_item, post(create_item))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            |state: State<Arc<AppState>>, request: Request, next: Next| {
+                rate_limit(state, RouteClass::Gas, request, next)
+            },
+        ));
+
+    // Admin routes, grouped by the least role that may call them
+    let admin_support = Router::new()
+        .route(&path.failed_events, get(get_failed_events))
+        .route(&path.reconciliations, get(get_reconciliation_runs))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            |state: State<Arc<AppState>>, request: Request, next: Next| {
+                require_role(state, AdminRole::Support, request, next)
+            },
+        ));
+    let admin_operator = Router::new()
+        .route(&path.replay_failed_event, post(replay_failed_event_handler))
+        .route(&path.register_manufacturer, post(register_manufacturer))
+        .route(&path.set_authenticity, post(set_authenticity))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            |state: State<Arc<AppState>>, request: Request, next: Next| {
+                require_role(state, AdminRole::Operator, request, next)
+            },
+        ));
+    let admin_admin = Router::new()
+        .route(&path.admin_roles, get(get_admin_roles).post(grant_admin_role))
+        .route(&path.revoke_admin_role, post(revoke_admin_role))
+        .route(&path.audit_log, get(get_audit_log))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            |state: State<Arc<AppState>>, request: Request, next: Next| {
+                require_role(state, AdminRole::Admin, request, next)
+            },
+        ));
+
     let app = Router::new()
         .route(&path.generate_signature, post(generate_signature))
         .route(&path.verify_authenticity, post(verify_authenticity))
-        .route(&path.sign_up, post(manufacturer_registers))
-        .route(&path.user_register, post(user_register))
         .route(&path.get_owner, get(get_owner))
         .route(&path.verify_signature, post(verify_signature))
         .route(&path.create_certificate, post(create_certificate))
@@ -60,12 +147,37 @@ pub fn paths(state: Arc<AppState>, path: RouterPath) -> Router {
         .route(&path.get_certificate, get(get_certificate))
         .route(&path.batch_items, post(batch_items))
         .route(&path.revoke_code, post(revoke_ownership_code))
-        .route(&path.set_authenticity, post(set_authenticity))
-        .route(&path.claim_ownership, post(claim_ownership))
-        .route(&path.create_item, post(create_item))
-        .route(&path.save_certificate, get(save_certificate))
         .route(&path.sync, post(sync))
         .route(&path.manufacturer_name_exists, get(manufacturer_name_exists))
+        .route(&path.health, get(health))
+        .route(&path.indexer_status, get(indexer_status))
+        .route(&path.indexer_metrics, get(indexer_metrics))
+        .route(&path.deployments, get(get_deployments))
+        .route(&path.chains, get(get_chains))
+        .route(&path.auth_nonce, get(get_nonce))
+        .route(&path.auth_verify, post(verify_siwe))
+        .route(&path.auth_logout, post(logout))
+        .route(&path.ownership_intent, post(prepare_ownership_intent))
+        .route(
+            &path.api_keys,
+            get(get_manufacturer_api_keys).post(create_manufacturer_api_key),
+        )
+        .route(&path.revoke_api_key, post(revoke_manufacturer_api_key))
+        .route(&path.job, get(get_job))
+        .merge(gas_spending)
+        .merge(certificates_write)
+        .merge(items_read)
+        .merge(analytics_read)
+        .merge(admin_support)
+        .merge(admin_operator)
+        .merge(admin_admin)
+        // Every route above also draws from the read budget
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            |state: State<Arc<AppState>>, request: Request, next: Next| {
+                rate_limit(state, RouteClass::Read, request, next)
+            },
+        ))
         .merge(SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", ApiDoc::openapi()))
         .with_state(state)
         .layer(cors); // Optional: Enable CORS
@@ -73,12 +185,20 @@ pub fn paths(state: Arc<AppState>, path: RouterPath) -> Router {
     app
 }
 
+// Health and indexer status only, for processes that run the indexer without the API
+pub fn ops_paths(state: Arc<AppState>, path: RouterPath) -> Router {
+    Router::new()
+        .route(&path.health, get(health))
+        .route(&path.indexer_status, get(indexer_status))
+        .route(&path.indexer_metrics, get(indexer_metrics))
+        .with_state(state)
+}
+
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct RouterPath {
     pub  generate_signature: String,
     pub verify_authenticity: String,
-    pub sign_up: String,
     pub get_owner: String,
     pub verify_signature: String,
     pub create_certificate: String,
@@ -101,6 +221,27 @@ pub struct RouterPath {
     pub get_certificate: String,
     pub save_certificate: String,
     pub check_before_claim: String,
+    pub failed_events: String,
+    pub replay_failed_event: String,
+    pub health: String,
+    pub indexer_status: String,
+    pub indexer_metrics: String,
+    pub deployments: String,
+    pub chains: String,
+    pub reconciliations: String,
+    pub auth_nonce: String,
+    pub auth_verify: String,
+    pub auth_logout: String,
+    pub ownership_intent: String,
+    pub api_keys: String,
+    pub revoke_api_key: String,
+    pub manufacturer_items: String,
+    pub manufacturer_analytics: String,
+    pub register_manufacturer: String,
+    pub admin_roles: String,
+    pub revoke_admin_role: String,
+    pub audit_log: String,
+    pub job: String,
 
 
 }
@@ -110,7 +251,6 @@ impl RouterPath {
         Self {
             generate_signature: "/generate_signature".to_string(),
             verify_authenticity: "/verify_authenticity".to_string(),
-            sign_up: "/manufacturer_registers".to_string(),
             get_owner: "/get_owner/{address}".to_string(),
             verify_signature: "/verify_signature".to_string(),
             create_certificate: "/create_certificate".to_string(),
@@ -124,7 +264,7 @@ impl RouterPath {
             transfer_code: "/api/get_transfer_code".to_string(),
             revoke_code: "/api/revoke_ownership_code".to_string(),
             user_register: "/api/user/register".to_string(),
-            set_authenticity:  "/api/set_authenticity".to_string(),
+            set_authenticity: "/admin/authenticity".to_string(),
             claim_ownership: "/api/ownership/claim".to_string(),
             create_item:  "/api/item/create".to_string(),
             get_item: "/api/item/{item_id}".to_string(),
@@ -133,6 +273,27 @@ impl RouterPath {
             get_certificate: "/api/certificate/{item_id}".to_string(),
             save_certificate: "/api/certificate/create".to_string(),
             check_before_claim: "/api/ownership/check_temp_owner".to_string(),
+            failed_events: "/admin/failed_events".to_string(),
+            replay_failed_event: "/admin/failed_events/{id}/replay".to_string(),
+            health: "/api/health".to_string(),
+            indexer_status: "/api/indexer/status".to_string(),
+            indexer_metrics: "/api/indexer/metrics".to_string(),
+            deployments: "/api/deployments".to_string(),
+            chains: "/api/chains".to_string(),
+            reconciliations: "/admin/reconciliations".to_string(),
+            auth_nonce: "/api/auth/nonce".to_string(),
+            auth_verify: "/api/auth/verify".to_string(),
+            auth_logout: "/api/auth/logout".to_string(),
+            ownership_intent: "/api/ownership/intent".to_string(),
+            api_keys: "/api/manufacturer/api_keys".to_string(),
+            revoke_api_key: "/api/manufacturer/api_keys/{id}/revoke".to_string(),
+            manufacturer_items: "/api/manufacturer/items".to_string(),
+            manufacturer_analytics: "/api/manufacturer/analytics".to_string(),
+            register_manufacturer: "/admin/manufacturers".to_string(),
+            admin_roles: "/admin/roles".to_string(),
+            revoke_admin_role: "/admin/roles/{address}/revoke".to_string(),
+            audit_log: "/admin/audit_log".to_string(),
+            job: "/api/jobs/{id}".to_string(),
         }
     }
 }
@@ -1,23 +1,38 @@
-use crate::authenticity::authenticity_abi::TrueAuthenticity;
-use crate::ownership::ownership_abi::TrueOwnership;
+use crate::config::chains::{load_chains, ChainConfig};
+use crate::config::leader::Leadership;
+use crate::config::supervisor::Supervisor;
+use crate::indexer::metrics::IndexerMetrics;
+use crate::indexer::reorg::ConfirmationPolicy;
+use crate::rate_limit::RateLimiter;
+use crate::relayer::worker::RelayerConfig;
 use diesel::pg::PgConnection;
 use diesel::r2d2::{ConnectionManager, Pool};
-use ecdsa::SigningKey;
-use ethabi::ethereum_types::Address;
-use ethers::core::k256::Secp256k1;
-use ethers::middleware::{Middleware, SignerMiddleware};
-use ethers::prelude::{Http, LocalWallet, Provider};
-use ethers::signers::{Signer, Wallet};
+use ethers::prelude::LocalWallet;
+use ethers::signers::Signer;
 use eyre::Report;
 use std::env;
-use std::sync::Arc;
 use std::time::Duration;
 
 #[derive(Clone)]
 pub struct AppState {
     pub db_pool: Pool<ConnectionManager<PgConnection>>,
-    pub authenticity_contract: TrueAuthenticity<SignerMiddleware<Provider<Http>, Wallet<SigningKey<Secp256k1>>>>,
-    pub ownership_contract: TrueOwnership<SignerMiddleware<Provider<Http>, Wallet<SigningKey<Secp256k1>>>>,
+    // Configured chains, the default chain first
+    pub chains: Vec<ChainConfig>,
+    pub confirmation_policy: ConfirmationPolicy,
+    pub max_log_range: u64,
+    pub admin_api_key: Option<String>,
+    // Reconciliation against the contracts only runs in the background when an interval is set
+    pub reconcile_interval: Option<Duration>,
+    pub reconcile_repair: bool,
+    // Domain SIWE messages must be issued for, and how long the resulting sessions last
+    pub siwe_domain: String,
+    pub session_ttl: Duration,
+    pub rate_limiter: RateLimiter,
+    pub relayer: RelayerConfig,
+    pub supervisor: Supervisor,
+    pub leadership: Leadership,
+    pub relayer_leadership: Leadership,
+    pub indexer_metrics: IndexerMetrics,
 }
 
 impl AppState {
@@ -31,34 +46,72 @@ impl AppState {
             .map_err(|e| eyre::eyre!("Failed to create pool: {}", e))?;
 
         //contract connection
-        let rpc_url = env::var("BASE_URL")?;
         let private_key = env::var("PRIVATE_KEY")?;
-        let authenticity_address: Address = env::var("AUTHENTICITY_ADDRESS")?
-            .parse()
-            .map_err(|_| anyhow::anyhow!("Invalid contract address"))
-            .unwrap();
-        let ownership_address: Address = env::var("OWNERSHIP_ADDRESS")?
-            .parse()
-            .map_err(|_| anyhow::anyhow!("Invalid contract address"))
-            .unwrap();
-
-        let provider = Provider::<Http>::try_from(&rpc_url)?.interval(Duration::from_millis(1000));
-        let chain_id = provider.get_chainid().await?.as_u64();
+        let confirmation_policy = ConfirmationPolicy::from_env()?;
+        // Upper bound for a single eth_getLogs range; the indexer shrinks below it on provider errors
+        let max_log_range = env::var("INDEXER_MAX_LOG_RANGE")
+            .unwrap_or_else(|_| "2000".to_string())
+            .parse::<u64>()
+            .map_err(|e| eyre::eyre!("Invalid INDEXER_MAX_LOG_RANGE: {}", e))?;
+        // Admin endpoints stay disabled unless a key is configured
+        let admin_api_key = env::var("ADMIN_API_KEY").ok().filter(|key| !key.is_empty());
+        let reconcile_interval = match env::var("RECONCILE_INTERVAL_SECS") {
+            Ok(secs) => Some(Duration::from_secs(
+                secs.parse::<u64>()
+                    .map_err(|e| eyre::eyre!("Invalid RECONCILE_INTERVAL_SECS: {}", e))?,
+            )),
+            Err(_) => None,
+        };
+        let reconcile_repair = env::var("RECONCILE_REPAIR").is_ok_and(|value| value == "true");
+        let siwe_domain = env::var("SIWE_DOMAIN").unwrap_or_else(|_| "localhost:8080".to_string());
+        let session_ttl = Duration::from_secs(
+            env::var("SESSION_TTL_SECS")
+                .unwrap_or_else(|_| "86400".to_string())
+                .parse::<u64>()
+                .map_err(|e| eyre::eyre!("Invalid SESSION_TTL_SECS: {}", e))?,
+        );
+        let rate_limiter = RateLimiter::from_env()?;
+        let relayer = RelayerConfig::from_env()?;
 
-        let wallet = private_key.parse::<LocalWallet>()?.with_chain_id(chain_id);
+        let wallet = private_key.parse::<LocalWallet>()?;
         println!("Wallet address: 0x{:x}", wallet.address());
 
-        let eth_client = Arc::new(SignerMiddleware::new(provider, wallet.clone()));
-
-        let authenticity_contract = TrueAuthenticity::new(authenticity_address, eth_client.clone());
-        let ownership_contract = TrueOwnership::new(ownership_address, eth_client.clone());
+        let chains = load_chains(&private_key).await?;
 
         let state = AppState {
             db_pool: pool,
-            authenticity_contract,
-            ownership_contract,
+            chains,
+            confirmation_policy,
+            max_log_range,
+            admin_api_key,
+            reconcile_interval,
+            reconcile_repair,
+            siwe_domain,
+            session_ttl,
+            rate_limiter,
+            relayer,
+            supervisor: Supervisor::default(),
+            leadership: Leadership::indexer(),
+            relayer_leadership: Leadership::relayer(),
+            indexer_metrics: IndexerMetrics::default(),
         };
         
         Ok(state)
     }
+
+    pub fn default_chain(&self) -> &ChainConfig {
+        &self.chains[0]
+    }
+
+    // Chain a request is sent to; the default chain when none is given
+    pub fn chain(&self, chain_id: Option<u64>) -> eyre::Result<&ChainConfig> {
+        match chain_id {
+            None => Ok(self.default_chain()),
+            Some(chain_id) => self
+                .chains
+                .iter()
+                .find(|chain| chain.chain_id == chain_id)
+                .ok_or_else(|| eyre::eyre!("Unsupported chain id {}", chain_id)),
+        }
+    }
 }
@@ -0,0 +1,116 @@
+use crate::authenticity::authenticity_abi::TrueAuthenticity;
+use crate::indexer::log_source::LogFile;
+use crate::indexer::transport::StreamTransport;
+use crate::ownership::ownership_abi::TrueOwnership;
+use ecdsa::SigningKey;
+use ethabi::ethereum_types::Address;
+use ethers::core::k256::Secp256k1;
+use ethers::middleware::{Middleware, SignerMiddleware};
+use ethers::prelude::{Http, LocalWallet, Provider, U64};
+use ethers::signers::{Signer, Wallet};
+use eyre::Result;
+use std::collections::HashSet;
+use std::env;
+use std::sync::Arc;
+use std::time::Duration;
+
+// Provider, contracts and indexing settings for one EVM chain. Every chain signs with the
+// same wallet.
+#[derive(Clone)]
+pub struct ChainConfig {
+    pub chain_id: u64,
+    pub name: String,
+    pub authenticity_contract: TrueAuthenticity<SignerMiddleware<Provider<Http>, Wallet<SigningKey<Secp256k1>>>>,
+    pub ownership_contract: TrueOwnership<SignerMiddleware<Provider<Http>, Wallet<SigningKey<Secp256k1>>>>,
+    pub authenticity_deployment_block: Option<U64>,
+    pub ownership_deployment_block: Option<U64>,
+    pub stream_transport: Option<StreamTransport>,
+    // Recorded logs the listeners replay instead of reading from the node
+    pub log_file: Option<Arc<LogFile>>,
+}
+
+// The chain behind BASE_URL and the unprefixed contract variables comes first and is the
+// default. Further chains are named in CHAINS (e.g. `CHAINS=arbitrum_sepolia`) and read the
+// same variables with the upper-cased name as prefix, e.g. ARBITRUM_SEPOLIA_RPC_URL and
+// ARBITRUM_SEPOLIA_AUTHENTICITY_ADDRESS.
+pub async fn load_chains(private_key: &str) -> Result<Vec<ChainConfig>> {
+    let default_name = env::var("CHAIN_NAME").unwrap_or_else(|_| "default".to_string());
+    let rpc_url = env::var("BASE_URL").map_err(|_| eyre::eyre!("BASE_URL must be set"))?;
+    let mut chains = vec![connect_chain(default_name, &rpc_url, "", private_key).await?];
+
+    let names = env::var("CHAINS").unwrap_or_default();
+    for name in names.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+        let prefix = format!("{}_", name.to_uppercase());
+        let rpc_url = env::var(format!("{}RPC_URL", prefix))
+            .map_err(|_| eyre::eyre!("{}RPC_URL must be set", prefix))?;
+        chains.push(connect_chain(name.to_lowercase(), &rpc_url, &prefix, private_key).await?);
+    }
+
+    let mut seen = HashSet::new();
+    for chain in &chains {
+        if !seen.insert(chain.chain_id) {
+            return Err(eyre::eyre!(
+                "Chain {} is configured more than once",
+                chain.chain_id
+            ));
+        }
+    }
+
+    Ok(chains)
+}
+
+async fn connect_chain(
+    name: String,
+    rpc_url: &str,
+    prefix: &str,
+    private_key: &str,
+) -> Result<ChainConfig> {
+    let authenticity_address = address(&format!("{}AUTHENTICITY_ADDRESS", prefix))?;
+    let ownership_address = address(&format!("{}OWNERSHIP_ADDRESS", prefix))?;
+    let authenticity_deployment_block =
+        deployment_block(&format!("{}AUTHENTICITY_DEPLOYMENT_BLOCK", prefix))?;
+    let ownership_deployment_block =
+        deployment_block(&format!("{}OWNERSHIP_DEPLOYMENT_BLOCK", prefix))?;
+    let stream_transport = StreamTransport::from_env(&format!("{}INDEXER_STREAM_URL", prefix));
+    let log_file = match env::var(format!("{}INDEXER_LOG_FILE", prefix)) {
+        Ok(path) if !path.is_empty() => Some(Arc::new(LogFile::load(&path)?)),
+        _ => None,
+    };
+
+    let provider = Provider::<Http>::try_from(rpc_url)?.interval(Duration::from_millis(1000));
+    let chain_id = provider.get_chainid().await?.as_u64();
+    let wallet = private_key.parse::<LocalWallet>()?.with_chain_id(chain_id);
+    let eth_client = Arc::new(SignerMiddleware::new(provider, wallet));
+    eprintln!("Connected to chain {} ({})", name, chain_id);
+
+    Ok(ChainConfig {
+        chain_id,
+        name,
+        authenticity_contract: TrueAuthenticity::new(authenticity_address, eth_client.clone()),
+        ownership_contract: TrueOwnership::new(ownership_address, eth_client),
+        authenticity_deployment_block,
+        ownership_deployment_block,
+        stream_transport,
+        log_file,
+    })
+}
+
+fn address(key: &str) -> Result<Address> {
+    env::var(key)
+        .map_err(|_| eyre::eyre!("{} must be set", key))?
+        .parse()
+        .map_err(|_| eyre::eyre!("Invalid contract address in {}", key))
+}
+
+// Block the contract was deployed at, used as the starting point when no checkpoint exists yet
+fn deployment_block(key: &str) -> Result<Option<U64>> {
+    match env::var(key) {
+        Ok(value) => {
+            let block = value
+                .parse::<u64>()
+                .map_err(|e| eyre::eyre!("Invalid {}: {}", key, e))?;
+            Ok(Some(U64::from(block)))
+        }
+        Err(_) => Ok(None),
+    }
+}
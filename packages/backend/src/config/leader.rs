@@ -0,0 +1,140 @@
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Bool};
+use std::env;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+
+// Advisory lock keys ("TRUEIDX", "TRUERLY") shared by every replica pointed at the same
+// database
+const INDEXER_LOCK_KEY: i64 = 0x0054_5255_4549_4458;
+const RELAYER_LOCK_KEY: i64 = 0x0054_5255_4552_4c59;
+
+// How often a follower tries to take the lock, and how often the leader checks the
+// connection holding it
+const ELECTION_INTERVAL: Duration = Duration::from_secs(5);
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+
+define_sql_function!(fn pg_try_advisory_lock(key: BigInt) -> Bool);
+
+// Whether this instance currently holds one of the advisory locks. The indexer leader runs
+// the tasks that write indexed data; the relayer leader sends queued transactions, so one
+// process hands out each wallet's nonces.
+#[derive(Clone)]
+pub struct Leadership {
+    role: &'static str,
+    lock_key: i64,
+    is_leader: Arc<watch::Sender<bool>>,
+}
+
+impl Leadership {
+    pub fn indexer() -> Self {
+        Self::new("indexer", INDEXER_LOCK_KEY)
+    }
+
+    pub fn relayer() -> Self {
+        Self::new("relayer", RELAYER_LOCK_KEY)
+    }
+
+    fn new(role: &'static str, lock_key: i64) -> Self {
+        Self {
+            role,
+            lock_key,
+            is_leader: Arc::new(watch::Sender::new(false)),
+        }
+    }
+
+    pub fn is_leader(&self) -> bool {
+        *self.is_leader.borrow()
+    }
+
+    // Waits for leadership, then runs the task until it finishes or leadership is lost. A
+    // lost lock is returned as an error so the supervisor restarts the task, which then waits
+    // to be elected again.
+    pub async fn run_while_leader<F>(&self, task: F) -> eyre::Result<()>
+    where
+        F: Future<Output = eyre::Result<()>>,
+    {
+        let mut is_leader = self.is_leader.subscribe();
+        is_leader
+            .wait_for(|is_leader| *is_leader)
+            .await
+            .map_err(|e| eyre::eyre!("Leader election stopped: {}", e))?;
+
+        tokio::select! {
+            result = task => result,
+            _ = is_leader.wait_for(|is_leader| !*is_leader) => {
+                Err(eyre::eyre!("Lost {} leadership", self.role))
+            }
+        }
+    }
+}
+
+// Steps down when the election task stops for any reason, including a panic
+struct LeaderGuard<'a>(&'a Leadership);
+
+impl Drop for LeaderGuard<'_> {
+    fn drop(&mut self) {
+        if self.0.is_leader.send_replace(false) {
+            eprintln!("Stepped down as {} leader", self.0.role);
+        }
+    }
+}
+
+fn try_lock(conn: &mut PgConnection, lock_key: i64) -> eyre::Result<bool> {
+    diesel::select(pg_try_advisory_lock(lock_key))
+        .get_result::<bool>(conn)
+        .map_err(|e| {
+            eprintln!("Failed to try advisory lock {:#x}: {:?}", lock_key, e);
+            eyre::eyre!("Failed to try advisory lock {:#x}: {}", lock_key, e)
+        })
+}
+
+fn connect_for_lock() -> eyre::Result<PgConnection> {
+    let db_url = env::var("DATABASE_URL").map_err(|_| eyre::eyre!("DATABASE_URL must be set"))?;
+    PgConnection::establish(&db_url).map_err(|e| {
+        eprintln!("Failed to connect for leader election: {:?}", e);
+        eyre::eyre!("Failed to connect for leader election: {}", e)
+    })
+}
+
+// Takes the indexer lock for a one-off command that rewrites indexed data, failing instead
+// of waiting when an indexer holds it. The lock lasts until the returned connection is
+// dropped.
+pub fn hold_indexer_lock() -> eyre::Result<PgConnection> {
+    let mut conn = connect_for_lock()?;
+    if !try_lock(&mut conn, INDEXER_LOCK_KEY)? {
+        return Err(eyre::eyre!(
+            "The indexer lock is held by another process; stop the indexer before running this command"
+        ));
+    }
+    Ok(conn)
+}
+
+// Competes for a session-level Postgres advisory lock on a connection of its own, outside
+// the pool, so the lock is released as soon as that connection or the process dies. The
+// leader keeps pinging the connection and steps down when it fails; another replica then
+// takes over within ELECTION_INTERVAL.
+pub async fn elect_leader(leadership: &Leadership) -> eyre::Result<()> {
+    let conn = &mut connect_for_lock()?;
+    let _guard = LeaderGuard(leadership);
+
+    while !try_lock(conn, leadership.lock_key)? {
+        tokio::time::sleep(ELECTION_INTERVAL).await;
+    }
+
+    eprintln!("Acquired {} leadership", leadership.role);
+    leadership.is_leader.send_replace(true);
+
+    loop {
+        tokio::time::sleep(HEARTBEAT_INTERVAL).await;
+        diesel::select(diesel::dsl::sql::<Bool>("TRUE"))
+            .get_result::<bool>(conn)
+            .map_err(|e| {
+                eprintln!("Lost the connection holding the {} lock: {:?}", leadership.role, e);
+                eyre::eyre!("Lost the connection holding the {} lock: {}", leadership.role, e)
+            })?;
+    }
+}
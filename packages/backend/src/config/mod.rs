@@ -1,4 +1,7 @@
 pub mod swagger_config;
 pub(crate) mod app_router;
 pub(crate) mod app_state;
+pub(crate) mod chains;
+pub mod leader;
 pub mod server;
+pub mod supervisor;
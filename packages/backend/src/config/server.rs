@@ -1,10 +1,17 @@
 use crate::authenticity::authenticity_event_listener::listen_for_authenticity_events;
 use crate::config::app_router::RouterPath;
-use crate::config::app_router::paths;
+use crate::config::app_router::{ops_paths, paths};
 use crate::config::app_state::AppState;
+use crate::config::leader::elect_leader;
+use crate::deployments::{deployment_contracts, register_configured_deployments};
+use crate::indexer::dead_letter::retry_failed_events;
+use crate::indexer::reconcile::reconcile_periodically;
 use crate::ownership::ownership_event::listen_for_ownership_events;
+use crate::relayer::worker::run_relayer;
 use anyhow::{Result, anyhow};
 use axum::Router;
+use diesel::pg::PgConnection;
+use diesel::r2d2::{ConnectionManager, PooledConnection};
 use diesel_migrations::{EmbeddedMigrations, MigrationHarness, embed_migrations};
 use dotenv::dotenv;
 use std::net::SocketAddr;
@@ -12,24 +19,75 @@ use std::sync::Arc;
 use tokio::net::TcpListener;
 use tokio::time::{Duration, sleep};
 
-const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
+pub(crate) const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
 
+// Runs migrations, the indexer, the relayer and the HTTP API in one process
 pub async fn server() -> Result<()> {
     eprintln!("PROJECT STARTING...");
+    let arc_state = init_state().await?;
+    let mut conn = db_connection(&arc_state).await?;
+    run_migrations(&mut conn)?;
+    drop(conn);
+
+    spawn_indexer(&arc_state).await?;
+    spawn_relayer(&arc_state);
+    let addr = default_addr();
+    eprintln!("Swagger UI available at {:?}/swagger-ui/index.html#/", addr);
+    serve_http(paths(arc_state, RouterPath::init()), addr).await
+}
+
+// Runs the HTTP API and the transaction relayer without indexing; migrations are expected
+// to have been applied with `migrate`
+pub async fn serve(addr: SocketAddr) -> Result<()> {
+    let arc_state = init_state().await?;
+    let mut conn = db_connection(&arc_state).await?;
+    ensure_migrated(&mut conn)?;
+    drop(conn);
+
+    spawn_relayer(&arc_state);
+    eprintln!("Swagger UI available at {:?}/swagger-ui/index.html#/", addr);
+    serve_http(paths(arc_state, RouterPath::init()), addr).await
+}
+
+// Runs the indexer only, with health and indexer status served on `addr`
+pub async fn index(addr: SocketAddr) -> Result<()> {
+    let arc_state = init_state().await?;
+    let mut conn = db_connection(&arc_state).await?;
+    ensure_migrated(&mut conn)?;
+    drop(conn);
+
+    spawn_indexer(&arc_state).await?;
+    serve_http(ops_paths(arc_state, RouterPath::init()), addr).await
+}
+
+// Applies pending migrations and exits
+pub async fn migrate() -> Result<()> {
+    let arc_state = init_state().await?;
+    let mut conn = db_connection(&arc_state).await?;
+    run_migrations(&mut conn)
+}
+
+pub fn default_addr() -> SocketAddr {
+    SocketAddr::from(([127, 0, 0, 1], 8080))
+}
+
+async fn init_state() -> Result<Arc<AppState>> {
     // Load environment variables
     dotenv().ok();
 
-    let arc_state = Arc::from(AppState::init_app_state().await.unwrap());
+    let state = AppState::init_app_state()
+        .await
+        .map_err(|e| anyhow!("Failed to initialise app state: {}", e))?;
+    Ok(Arc::new(state))
+}
 
-    // Run migrations
+async fn db_connection(
+    arc_state: &AppState,
+) -> Result<PooledConnection<ConnectionManager<PgConnection>>> {
     let mut attempts = 3;
-    let mut conn = None;
-    for _ in 0..attempts {
+    loop {
         match arc_state.db_pool.get() {
-            Ok(c) => {
-                conn = Some(c);
-                break;
-            }
+            Ok(conn) => return Ok(conn),
             Err(e) => {
                 eprintln!(
                     "Failed to get DB connection (attempt {}): {:?}",
@@ -44,97 +102,142 @@ pub async fn server() -> Result<()> {
             }
         }
     }
-    let mut conn = conn.unwrap();
-
-    conn.run_pending_migrations(MIGRATIONS)
-        .map_err(|e| {
-            eprintln!("Failed to run migrations: {:?}", e);
-            eyre::eyre!("Migration failed: {}", e)
-        })
-        .unwrap();
+}
+
+fn run_migrations(conn: &mut PgConnection) -> Result<()> {
+    conn.run_pending_migrations(MIGRATIONS).map_err(|e| {
+        eprintln!("Failed to run migrations: {:?}", e);
+        anyhow!("Migration failed: {}", e)
+    })?;
     eprintln!("Database migrations completed successfully");
+    Ok(())
+}
 
-    let state_clone1 = arc_state.clone();
-    let state_clone2 = arc_state.clone();
+// Processes started on their own do not migrate, so several of them can start at once
+fn ensure_migrated(conn: &mut PgConnection) -> Result<()> {
+    let pending = conn
+        .has_pending_migration(MIGRATIONS)
+        .map_err(|e| anyhow!("Failed to check migrations: {}", e))?;
+    if pending {
+        return Err(anyhow!(
+            "The database has pending migrations; run `backend migrate` first"
+        ));
+    }
+    Ok(())
+}
 
-    tokio::spawn(async move {
-        let mut attempts = 5;
-        loop {
-            if let Err(e) = listen_for_authenticity_events(&state_clone1).await {
-                eprintln!(
-                    "Error in authenticity listener (attempt {}): {:?}",
-                    6 - attempts,
-                    e
-                );
-                attempts -= 1;
-                if attempts == 0 {
-                    eprintln!("Max retries reached for authenticity listener");
-                    break;
+async fn spawn_indexer(arc_state: &Arc<AppState>) -> Result<()> {
+    let mut conn = db_connection(arc_state).await?;
+    // Each chain's configured contract pair becomes its active deployment; older pairs stay
+    // indexed
+    let deployment_ids = register_configured_deployments(&mut conn, arc_state)
+        .map_err(|e| anyhow!("Failed to register deployments: {}", e))?;
+    eprintln!("Active deployments: {:?}", deployment_ids);
+    let deployments = deployment_contracts(arc_state)
+        .map_err(|e| anyhow!("Failed to load deployments: {}", e))?;
+
+    // Background tasks are restarted by the supervisor whenever they fail. Every replica
+    // serves HTTP, but only the one holding the indexer lock runs the tasks that write
+    // indexed data; the others wait to take over.
+    let supervisor = &arc_state.supervisor;
+    let state = arc_state.clone();
+    supervisor.spawn("leader_election", move || {
+        let state = state.clone();
+        async move { elect_leader(&state.leadership).await }
+    });
+    for deployment in deployments {
+        let state = arc_state.clone();
+        let authenticity_deployment = deployment.clone();
+        supervisor.spawn(
+            &format!("authenticity_listener_{}_{}", deployment.chain_id, deployment.id),
+            move || {
+                let state = state.clone();
+                let deployment = authenticity_deployment.clone();
+                async move {
+                    state
+                        .leadership
+                        .run_while_leader(listen_for_authenticity_events(&state, &deployment))
+                        .await
                 }
-                tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
-            } else {
-                break;
-            }
+            },
+        );
+        let state = arc_state.clone();
+        let ownership_deployment = deployment.clone();
+        supervisor.spawn(
+            &format!("ownership_listener_{}_{}", deployment.chain_id, deployment.id),
+            move || {
+                let state = state.clone();
+                let deployment = ownership_deployment.clone();
+                async move {
+                    state
+                        .leadership
+                        .run_while_leader(listen_for_ownership_events(&state, &deployment))
+                        .await
+                }
+            },
+        );
+    }
+    // Retry events the listeners had to skip
+    let state = arc_state.clone();
+    supervisor.spawn("failed_event_retry", move || {
+        let state = state.clone();
+        async move {
+            state
+                .leadership
+                .run_while_leader(retry_failed_events(&state))
+                .await
         }
     });
-
-    tokio::spawn(async move {
-        let mut attempts = 5;
-        loop {
-            if let Err(e) = listen_for_ownership_events(&state_clone2).await {
-                eprintln!(
-                    "Error in ownership listener (attempt {}): {:?}",
-                    6 - attempts,
-                    e
-                );
-                attempts -= 1;
-                if attempts == 0 {
-                    eprintln!("Max retries reached for ownership listener");
-                    break;
-                }
-                tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
-            } else {
-                break;
+    // Compare the projections against the contracts, if enabled
+    if let Some(interval) = arc_state.reconcile_interval {
+        let state = arc_state.clone();
+        let repair = arc_state.reconcile_repair;
+        supervisor.spawn("reconciliation", move || {
+            let state = state.clone();
+            async move {
+                state
+                    .leadership
+                    .run_while_leader(reconcile_periodically(&state, interval, repair))
+                    .await
             }
-        }
+        });
+    }
+
+    Ok(())
+}
+
+// Sends queued contract calls from the server wallet, one worker per chain so each wallet's
+// nonces are handed out in order. Every process that accepts writes competes for the relayer
+// lock, so queued jobs are sent as long as one API replica is up, and only by one of them.
+fn spawn_relayer(arc_state: &Arc<AppState>) {
+    let supervisor = &arc_state.supervisor;
+    let state = arc_state.clone();
+    supervisor.spawn("relayer_election", move || {
+        let state = state.clone();
+        async move { elect_leader(&state.relayer_leadership).await }
     });
+    for chain in &arc_state.chains {
+        let state = arc_state.clone();
+        let chain_id = chain.chain_id;
+        supervisor.spawn(&format!("relayer_{}", chain_id), move || {
+            let state = state.clone();
+            async move {
+                state
+                    .relayer_leadership
+                    .run_while_leader(run_relayer(&state, chain_id))
+                    .await
+            }
+        });
+    }
+}
 
-    // let mut conn = arc_state
-    //     .db_pool
-    //     .get()
-    //     .map_err(|e| eyre::eyre!("Failed to get DB connection: {}", e)).unwrap();
-    // conn.run_pending_migrations(MIGRATIONS)
-    //     .map_err(|e| eyre::eyre!("Migration failed: {}", e)).unwrap();
-    //
-    //
-    // let state_clone1 = arc_state.clone();
-    // let state_clone2 = arc_state.clone();
-    //
-    // tokio::spawn(async move {
-    //     if let Err(e) = listen_for_authenticity_events(&state_clone1).await {
-    //         eprintln!("Error in authenticity listener, retrying in 5s: {:?}", e);
-    //         tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
-    //     }
-    // });
-    //
-    // tokio::spawn(async move {
-    //     if let Err(e) = listen_for_ownership_events(&state_clone2).await {
-    //         eprintln!("Error in event listener for ownership: {:?}", e);
-    //         tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
-    //     }
-    // });
-
-    // Define routes
-    let app: Router = paths(arc_state, RouterPath::init());
-
-    // Start the server
-    let addr = SocketAddr::from(([127, 0, 0, 1], 8080));
+async fn serve_http(app: Router, addr: SocketAddr) -> Result<()> {
     let listener = TcpListener::bind(addr).await?;
 
     eprintln!("Server running on {:?}", addr);
-    eprintln!("Swagger UI available at {:?}/swagger-ui/index.html#/", addr);
 
-    axum::serve(listener, app).await?;
+    // Connection info gives the rate limiter the client IP
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await?;
 
     Ok(()) // another way to say return nothing
 }
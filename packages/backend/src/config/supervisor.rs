@@ -0,0 +1,141 @@
+use chrono::Utc;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use utoipa::ToSchema;
+
+const BASE_RESTART_DELAY: Duration = Duration::from_secs(1);
+const MAX_RESTART_DELAY: Duration = Duration::from_secs(300);
+
+// A task that stays up this long is considered recovered
+const STABLE_RUN: Duration = Duration::from_secs(60);
+
+// Consecutive failures after which a task is reported as failed to health checks. It keeps
+// being restarted regardless.
+const FAILED_THRESHOLD: u32 = 5;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskState {
+    Running,
+    BackingOff,
+    Failed,
+}
+
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct TaskStatus {
+    pub name: String,
+    pub state: TaskState,
+    pub last_error: Option<String>,
+    pub restart_count: u64,
+    pub consecutive_failures: u32,
+    pub started_at: String,
+    pub next_restart_at: Option<String>,
+}
+
+// Keeps long-running background tasks alive. A task that returns an error or panics is
+// restarted after a jittered exponential backoff, without an attempt limit.
+#[derive(Clone, Default)]
+pub struct Supervisor {
+    tasks: Arc<RwLock<BTreeMap<String, TaskStatus>>>,
+}
+
+impl Supervisor {
+    pub fn spawn<F, Fut>(&self, name: &str, task: F)
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = eyre::Result<()>> + Send + 'static,
+    {
+        let supervisor = self.clone();
+        let name = name.to_string();
+
+        tokio::spawn(async move {
+            let mut consecutive_failures: u32 = 0;
+            loop {
+                supervisor.update(&name, |status| {
+                    // A task that keeps failing stays reported as failed until it runs stably
+                    if status.consecutive_failures < FAILED_THRESHOLD {
+                        status.state = TaskState::Running;
+                    }
+                    status.started_at = Utc::now().to_rfc3339();
+                    status.next_restart_at = None;
+                });
+
+                let mut handle = tokio::spawn(task());
+                let outcome = tokio::select! {
+                    outcome = &mut handle => outcome,
+                    _ = tokio::time::sleep(STABLE_RUN) => {
+                        // The task has recovered, so its backoff starts over
+                        consecutive_failures = 0;
+                        supervisor.update(&name, |status| {
+                            status.state = TaskState::Running;
+                            status.consecutive_failures = 0;
+                        });
+                        handle.await
+                    }
+                };
+                let error = match outcome {
+                    Ok(Ok(())) => "Task exited unexpectedly".to_string(),
+                    Ok(Err(e)) => format!("{:?}", e),
+                    Err(e) => format!("Task panicked: {}", e),
+                };
+                consecutive_failures += 1;
+
+                let delay = restart_delay(consecutive_failures);
+                eprintln!(
+                    "Task {} stopped (failure {}), restarting in {:?}: {}",
+                    name, consecutive_failures, delay, error
+                );
+                supervisor.update(&name, |status| {
+                    status.state = if consecutive_failures >= FAILED_THRESHOLD {
+                        TaskState::Failed
+                    } else {
+                        TaskState::BackingOff
+                    };
+                    status.last_error = Some(error.clone());
+                    status.restart_count += 1;
+                    status.consecutive_failures = consecutive_failures;
+                    status.next_restart_at = Some((Utc::now() + delay).to_rfc3339());
+                });
+
+                tokio::time::sleep(delay).await;
+            }
+        });
+    }
+
+    fn update(&self, name: &str, apply: impl FnOnce(&mut TaskStatus)) {
+        let mut tasks = self.tasks.write().unwrap();
+        let status = tasks.entry(name.to_string()).or_insert_with(|| TaskStatus {
+            name: name.to_string(),
+            state: TaskState::Running,
+            last_error: None,
+            restart_count: 0,
+            consecutive_failures: 0,
+            started_at: Utc::now().to_rfc3339(),
+            next_restart_at: None,
+        });
+        apply(status);
+    }
+
+    pub fn statuses(&self) -> Vec<TaskStatus> {
+        self.tasks.read().unwrap().values().cloned().collect()
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        self.tasks
+            .read()
+            .unwrap()
+            .values()
+            .all(|status| status.state != TaskState::Failed)
+    }
+}
+
+// Exponential backoff, jittered between half and the full delay so tasks that failed
+// together do not restart in lockstep
+fn restart_delay(consecutive_failures: u32) -> Duration {
+    let exponent = consecutive_failures.saturating_sub(1).min(16);
+    let ceiling = (BASE_RESTART_DELAY * 2u32.pow(exponent)).min(MAX_RESTART_DELAY);
+    ceiling.mul_f64(rand::random_range(0.5..=1.0))
+}
@@ -2,10 +2,13 @@ use crate::authenticity::get_certificate::CertificateResponse;
 use crate::authenticity::get_manufacturer::__path_get_manufacturer;
 use crate::authenticity::is_username_exist::__path_manufacturer_name_exists;
 use crate::authenticity::is_username_exist::{IsExistsQuery, IsExistsResponse};
+use crate::config::supervisor::{TaskState, TaskStatus};
+use crate::indexer::metrics::{IndexerMode, StreamStatus};
 use crate::certificate::{__path_get_certificate,__path_save_certificate, Certificates, CertificateDTO};
-use crate::contract_models::{Manufacturer, ManufacturerQuery, Item};
+use crate::contract_models::{AdminAuditEntry, AdminRoleGrant, ApiKey, ChainQuery, Deployment, DeploymentQuery, ReconciliationRun, FailedEvent, Manufacturer, ManufacturerQuery, Item};
+use crate::models::ownership_intent_model::IntentSignature;
 use crate::models::certificate_model::{
-    CertificateData, Eip712Object, RegInput, SignedCertificate,
+    CertificateData, Eip712Object, SignedCertificate,
 };
 use crate::ownership::{
     get_user_info::{__path_get_user, UserQuery, UserResponse},
@@ -20,19 +23,53 @@ use crate::ownership::{
 use crate::services::{
     create_eip712::__path_create_certificate,
     other_tests::{
-        __path_generate_signature, __path_get_owner, __path_manufacturer_registers,
-        __path_verify_signature,
+        __path_generate_signature, __path_get_owner, __path_verify_signature,
     },
     qr_code::__path_generate_qr_code,
-    verify_authenticity::__path_verify_authenticity,
+    verify_authenticity::{__path_verify_authenticity, VerificationResult},
     set_autheticity::{__path_set_authenticity, SetAuthenticityResponse, SetAuthenticityRequest},
-    claim_ownership::{__path_claim_ownership, ClaimOwnershipResponse, ClaimOwnershipRequest},
-    create_item::{__path_create_item, CreateItemResponse, CreateItemRequest},
+    claim_ownership::{__path_claim_ownership, ClaimOwnershipRequest},
+    create_item::{__path_create_item, CreateItemRequest},
+    failed_events::{
+        __path_get_failed_events, __path_replay_failed_event_handler, FailedEventsQuery,
+        FailedEventsResponse, ReplayFailedEventResponse,
+    },
+    deployments::{__path_get_deployments, DeploymentsResponse},
+    chains::{__path_get_chains, ChainInfo, ChainsResponse},
+    reconciliations::{
+        __path_get_reconciliation_runs, ReconciliationRunsQuery, ReconciliationRunsResponse,
+    },
+    auth::{
+        __path_get_nonce, __path_logout, __path_verify_siwe, NonceResponse, SessionResponse,
+        SiweVerifyRequest,
+    },
+    ownership_intent::{__path_prepare_ownership_intent, OwnershipIntentRequest},
+    api_keys::{
+        __path_create_manufacturer_api_key, __path_get_manufacturer_api_keys,
+        __path_revoke_manufacturer_api_key, ApiKeysResponse, CreateApiKeyRequest,
+        CreateApiKeyResponse,
+    },
+    manufacturer_api::{
+        __path_get_manufacturer_analytics, __path_get_manufacturer_items,
+        ManufacturerAnalyticsResponse, ManufacturerItemsQuery, ManufacturerItemsResponse,
+    },
+    admin::{
+        __path_get_admin_roles, __path_get_audit_log, __path_grant_admin_role,
+        __path_register_manufacturer, __path_revoke_admin_role, AdminRolesResponse,
+        AuditLogQuery, AuditLogResponse, GrantRoleRequest, RegisterManufacturerRequest,
+        RegisterManufacturerResponse,
+    },
+    health::{__path_health, HealthResponse},
+    jobs::{__path_get_job, JobAcceptedResponse, JobResponse},
+    indexer_status::{__path_indexer_metrics, __path_indexer_status, IndexerStatusResponse},
 };
 use crate::sync::{__path_sync, SyncPayload, SyncResponse};
 use crate::ownership::batch_items::{__path_batch_items, BatchItemsPayload, BatchItemsResponse};
-use crate::services::register_user::{__path_user_register, UserRegisterResponse, UserRegisterRequest};
-use utoipa::OpenApi;
+use crate::services::register_user::{__path_user_register, UserRegisterRequest};
+use crate::auth::admin::ADMIN_KEY_HEADER;
+use crate::auth::api_key::API_KEY_HEADER;
+use utoipa::openapi::security::{ApiKey as ApiKeyScheme, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
 
 // Swagger/OpenAPI configuration
 #[derive(OpenApi)]
@@ -40,7 +77,6 @@ use utoipa::OpenApi;
     paths(
         verify_authenticity,
         generate_signature,
-        manufacturer_registers,
         get_owner,
         verify_signature,
         create_certificate,
@@ -62,11 +98,33 @@ use utoipa::OpenApi;
         batch_items,
         get_certificate,
         save_certificate,
-        check_before_claim
+        check_before_claim,
+        get_failed_events,
+        replay_failed_event_handler,
+        health,
+        indexer_status,
+        indexer_metrics,
+        get_deployments,
+        get_chains,
+        get_reconciliation_runs,
+        get_nonce,
+        verify_siwe,
+        logout,
+        prepare_ownership_intent,
+        create_manufacturer_api_key,
+        get_manufacturer_api_keys,
+        revoke_manufacturer_api_key,
+        get_manufacturer_items,
+        get_manufacturer_analytics,
+        register_manufacturer,
+        get_admin_roles,
+        grant_admin_role,
+        revoke_admin_role,
+        get_audit_log,
+        get_job
     ),
     components(
         schemas(
-            RegInput,
             CertificateData,
             SignedCertificate,
             Eip712Object,
@@ -85,21 +143,34 @@ use utoipa::OpenApi;
             GetOwnershipCodeQuery,
             OwnershipResponse,
             OwnershipQuery,
-            UserRegisterResponse,
             UserRegisterRequest,
             SetAuthenticityRequest,
             SetAuthenticityResponse,
             ClaimOwnershipRequest,
-            ClaimOwnershipResponse,
-            CreateItemResponse,
             CreateItemRequest,
             Item,
             SyncPayload, SyncResponse, BatchItemsResponse, BatchItemsPayload,
             CertificateDTO, Certificates,
-            OwnershipCheckResponse, OwnershipCheckQuery
+            OwnershipCheckResponse, OwnershipCheckQuery,
+            FailedEvent, FailedEventsQuery, FailedEventsResponse, ReplayFailedEventResponse,
+            HealthResponse, TaskStatus, TaskState,
+            VerificationResult,
+            IndexerStatusResponse, StreamStatus, IndexerMode,
+            Deployment, DeploymentQuery, DeploymentsResponse,
+            ChainQuery, ChainInfo, ChainsResponse,
+            ReconciliationRun, ReconciliationRunsQuery, ReconciliationRunsResponse,
+            NonceResponse, SiweVerifyRequest, SessionResponse,
+            OwnershipIntentRequest, IntentSignature,
+            ApiKey, CreateApiKeyRequest, CreateApiKeyResponse, ApiKeysResponse,
+            ManufacturerItemsQuery, ManufacturerItemsResponse, ManufacturerAnalyticsResponse,
+            RegisterManufacturerRequest, RegisterManufacturerResponse,
+            AdminRoleGrant, GrantRoleRequest, AdminRolesResponse,
+            AdminAuditEntry, AuditLogQuery, AuditLogResponse,
+            JobAcceptedResponse, JobResponse
         ),
         // responses()
     ),
+    modifiers(&SecurityAddon),
     tags(
         (name = "ERI", description = "Signature Verifying APIs")
     ),
@@ -125,3 +196,25 @@ use utoipa::OpenApi;
     // )
 )]
 pub struct ApiDoc;
+
+// Bearer token returned by /api/auth/verify, manufacturer API keys and the admin key
+struct SecurityAddon;
+
+impl Modify for SecurityAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        if let Some(components) = openapi.components.as_mut() {
+            components.add_security_scheme(
+                "session_token",
+                SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+            );
+            components.add_security_scheme(
+                "api_key",
+                SecurityScheme::ApiKey(ApiKeyScheme::Header(ApiKeyValue::new(API_KEY_HEADER))),
+            );
+            components.add_security_scheme(
+                "admin_key",
+                SecurityScheme::ApiKey(ApiKeyScheme::Header(ApiKeyValue::new(ADMIN_KEY_HEADER))),
+            );
+        }
+    }
+}
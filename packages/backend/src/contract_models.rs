@@ -19,6 +19,11 @@ pub struct NewContract {
     pub owner: String,
     pub tnx_hash: String,
     pub created_at: String,
+    pub block_number: i64,
+    pub block_hash: String,
+    pub log_index: i64,
+    pub deployment_id: i32,
+    pub chain_id: i64,
 }
 
 #[derive(Queryable, Selectable, Insertable, Serialize, Deserialize)]
@@ -29,6 +34,11 @@ pub struct UserInfo {
     pub is_registered: bool,
     pub created_at: String,
     pub tnx_hash: String,
+    pub block_number: Option<i64>,
+    pub block_hash: Option<String>,
+    pub log_index: Option<i64>,
+    pub deployment_id: Option<i32>,
+    pub chain_id: Option<i64>,
 }
 #[derive(Serialize, Deserialize, ToSchema, Queryable, Selectable)]
 #[diesel(table_name = crate::schema::users_info)]
@@ -37,6 +47,8 @@ pub struct User {
     pub username: String,
     pub is_registered: bool,
     pub created_at: String,
+    pub deployment_id: Option<i32>,
+    pub chain_id: Option<i64>,
 }
 
 #[derive(Queryable, Selectable, Serialize, Deserialize, Debug, ToSchema)]
@@ -51,6 +63,10 @@ pub struct Manufacturer {
     is_registered: bool,
     #[schema(example = "2025-08-24T12:04:00Z")]
     registered_at: String,
+    #[schema(example = 1)]
+    deployment_id: Option<i32>,
+    #[schema(example = 84532)]
+    chain_id: Option<i64>,
 }
 
 
@@ -61,7 +77,12 @@ pub struct NewManufacturer {
     pub manufacturer_name: String,
     pub is_registered: bool,
     pub registered_at: String,
-    pub tnx_hash: String
+    pub tnx_hash: String,
+    pub block_number: i64,
+    pub block_hash: String,
+    pub log_index: i64,
+    pub deployment_id: i32,
+    pub chain_id: i64,
 }
 
 #[derive(Queryable, Selectable, Insertable, Serialize, Deserialize, ToSchema)]
@@ -72,6 +93,10 @@ pub struct OwnershipCode {
     pub item_owner: String,
     pub temp_owner: String,
     pub created_at: String,
+    // Signed request that generated the code
+    pub intent_id: Option<i64>,
+    pub chain_id: Option<i64>,
+    pub deployment_id: Option<i32>,
 }
 
 #[derive(Queryable, Selectable, Serialize, Deserialize, ToSchema)]
@@ -86,6 +111,8 @@ pub struct Item {
     #[schema(nullable = true, value_type = Vec<Option<String>>)]
     pub metadata: Vec<Option<String>>,
     pub created_at: String,
+    pub deployment_id: Option<i32>,
+    pub chain_id: Option<i64>,
 }
 
 
@@ -101,6 +128,11 @@ pub struct NewItem {
     pub metadata: Vec<String>,
     pub created_at: String,
     pub tnx_hash: String,
+    pub block_number: i64,
+    pub block_hash: String,
+    pub log_index: i64,
+    pub deployment_id: i32,
+    pub chain_id: i64,
 }
 
 #[derive(Queryable, Selectable, Insertable, Serialize, Deserialize)]
@@ -122,6 +154,13 @@ pub struct NewOwnershipClaim {
     pub old_owner: String,
     pub tnx_hash: String,
     pub created_at: String,
+    pub block_number: i64,
+    pub block_hash: String,
+    pub log_index: i64,
+    pub deployment_id: i32,
+    pub chain_id: i64,
+    // Signed claim whose transaction emitted the transfer, when it went through this backend
+    pub intent_id: Option<i64>,
 }
 
 #[derive(Queryable, Insertable, Serialize, Deserialize)]
@@ -156,8 +195,357 @@ pub struct NewAuthenticitySetting {
     pub authenticity_address: String,
     pub tnx_hash: String,
     pub created_at: String,
+    pub block_number: i64,
+    pub block_hash: String,
+    pub log_index: i64,
+    pub deployment_id: i32,
+    pub chain_id: i64,
+}
+
+#[derive(Queryable, Selectable, Insertable, Serialize, Deserialize)]
+#[diesel(table_name = crate::schema::sync_checkpoints)]
+pub struct SyncCheckpoint {
+    pub contract_address: String,
+    pub stream: String,
+    pub last_processed_block: i64,
+    pub updated_at: String,
+    pub chain_id: Option<i64>,
+}
+
+#[derive(Queryable, Selectable, Insertable, Serialize, Deserialize)]
+#[diesel(table_name = crate::schema::indexed_blocks)]
+pub struct IndexedBlock {
+    pub contract_address: String,
+    pub stream: String,
+    pub block_number: i64,
+    pub block_hash: String,
+    pub chain_id: Option<i64>,
+}
+
+#[derive(Queryable, Selectable, Serialize, Deserialize)]
+#[diesel(table_name = crate::schema::chain_events)]
+pub struct ChainEvent {
+    pub id: i64,
+    pub contract_address: String,
+    pub event_name: String,
+    pub block_number: i64,
+    pub block_hash: String,
+    pub log_index: i64,
+    pub tnx_hash: String,
+    pub payload: serde_json::Value,
+    pub created_at: String,
+    pub block_timestamp: Option<String>,
+    pub chain_id: Option<i64>,
+}
+
+#[derive(Insertable, Serialize, Deserialize)]
+#[diesel(table_name = crate::schema::chain_events)]
+pub struct NewChainEvent {
+    pub contract_address: String,
+    pub event_name: String,
+    pub block_number: i64,
+    pub block_hash: String,
+    pub log_index: i64,
+    pub tnx_hash: String,
+    pub payload: serde_json::Value,
+    pub created_at: String,
+    pub block_timestamp: Option<String>,
+    pub chain_id: i64,
+}
+
+
+#[derive(Queryable, Selectable, Serialize, Deserialize, ToSchema)]
+#[diesel(table_name = crate::schema::failed_events)]
+pub struct FailedEvent {
+    pub id: i64,
+    pub contract_address: String,
+    pub stream: String,
+    pub event_name: String,
+    pub block_number: i64,
+    pub log_index: i64,
+    pub tnx_hash: String,
+    #[schema(value_type = Object)]
+    pub raw_log: serde_json::Value,
+    pub error: String,
+    pub attempts: i32,
+    pub status: String,
+    pub next_retry_at: String,
+    pub created_at: String,
+    pub updated_at: String,
+    pub chain_id: Option<i64>,
+    // Entity the event is about; later events with the same key wait for this one
+    pub ordering_key: Option<String>,
+}
+
+#[derive(Insertable, Serialize, Deserialize)]
+#[diesel(table_name = crate::schema::failed_events)]
+pub struct NewFailedEvent {
+    pub contract_address: String,
+    pub stream: String,
+    pub event_name: String,
+    pub block_number: i64,
+    pub log_index: i64,
+    pub tnx_hash: String,
+    pub raw_log: serde_json::Value,
+    pub error: String,
+    pub attempts: i32,
+    pub status: String,
+    pub next_retry_at: String,
+    pub created_at: String,
+    pub updated_at: String,
+    pub chain_id: i64,
+    pub ordering_key: Option<String>,
+}
+
+#[derive(Queryable, Selectable, Serialize, Deserialize, ToSchema)]
+#[diesel(table_name = crate::schema::eip712_domains)]
+pub struct Eip712DomainRecord {
+    pub id: i64,
+    pub contract_address: String,
+    pub name: String,
+    pub version: String,
+    pub chain_id: i64,
+    pub verifying_contract: String,
+    pub is_active: bool,
+    pub block_number: i64,
+    pub activated_block: i64,
+    pub tnx_hash: String,
+    pub created_at: String,
+    pub activated_at: String,
+    pub deployment_id: Option<i32>,
+}
+
+#[derive(Insertable, Serialize, Deserialize)]
+#[diesel(table_name = crate::schema::eip712_domains)]
+pub struct NewEip712Domain {
+    pub contract_address: String,
+    pub name: String,
+    pub version: String,
+    pub chain_id: i64,
+    pub verifying_contract: String,
+    pub is_active: bool,
+    pub block_number: i64,
+    pub activated_block: i64,
+    pub tnx_hash: String,
+    pub created_at: String,
+    pub activated_at: String,
+    pub deployment_id: i32,
+}
+
+#[derive(Queryable, Selectable, Serialize, Deserialize, ToSchema)]
+#[diesel(table_name = crate::schema::deployments)]
+pub struct Deployment {
+    pub id: i32,
+    pub chain_id: Option<i64>,
+    pub authenticity_address: String,
+    pub ownership_address: String,
+    pub authenticity_deployment_block: Option<i64>,
+    pub ownership_deployment_block: Option<i64>,
+    pub is_active: bool,
+    pub created_at: String,
+}
+
+#[derive(Insertable, Serialize, Deserialize)]
+#[diesel(table_name = crate::schema::deployments)]
+pub struct NewDeployment {
+    pub chain_id: i64,
+    pub authenticity_address: String,
+    pub ownership_address: String,
+    pub authenticity_deployment_block: Option<i64>,
+    pub ownership_deployment_block: Option<i64>,
+    pub is_active: bool,
+    pub created_at: String,
+}
+
+#[derive(Queryable, Selectable, Serialize, Deserialize, ToSchema)]
+#[diesel(table_name = crate::schema::reconciliation_runs)]
+pub struct ReconciliationRun {
+    pub id: i64,
+    pub chain_id: i64,
+    pub repair: bool,
+    pub items_checked: i64,
+    pub mismatches: i64,
+    pub repaired: i64,
+    #[schema(value_type = Object)]
+    pub report: serde_json::Value,
+    pub started_at: String,
+    pub finished_at: String,
+}
+
+#[derive(Insertable, Serialize, Deserialize)]
+#[diesel(table_name = crate::schema::reconciliation_runs)]
+pub struct NewReconciliationRun {
+    pub chain_id: i64,
+    pub repair: bool,
+    pub items_checked: i64,
+    pub mismatches: i64,
+    pub repaired: i64,
+    pub report: serde_json::Value,
+    pub started_at: String,
+    pub finished_at: String,
+}
+
+#[derive(Queryable, Selectable, Insertable)]
+#[diesel(table_name = crate::schema::siwe_nonces)]
+pub struct SiweNonce {
+    pub nonce: String,
+    pub created_at: String,
+    pub expires_at: String,
+    pub used_at: Option<String>,
+}
+
+// What a request needs from a live session; the token hash and timestamps only filter
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = crate::schema::sessions)]
+pub struct Session {
+    pub id: i64,
+    pub address: String,
+    pub chain_id: i64,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::ownership_intents)]
+pub struct NewOwnershipIntent {
+    pub chain_id: i64,
+    pub action: String,
+    pub signer: String,
+    pub item_id: String,
+    pub ownership_code: String,
+    pub temp_owner: String,
+    pub nonce: String,
+    pub deadline: i64,
+    pub digest: String,
+    pub signature: String,
+    pub created_at: String,
+}
+
+// An API key as listed to its manufacturer; the hash never leaves the database
+#[derive(Queryable, Selectable, Serialize, ToSchema)]
+#[diesel(table_name = crate::schema::api_keys)]
+pub struct ApiKey {
+    #[schema(example = 1)]
+    pub id: i64,
+    #[schema(example = "0x1234567890abcdef1234567890abcdef12345678")]
+    pub manufacturer_address: String,
+    #[schema(example = "Line 3 MES")]
+    pub name: String,
+    #[schema(example = "true_3f9a1c2e")]
+    pub key_prefix: String,
+    #[schema(example = json!(["certificates:write", "items:read"]))]
+    pub scopes: Vec<Option<String>>,
+    pub created_at: String,
+    pub last_used_at: Option<String>,
+    pub revoked_at: Option<String>,
 }
 
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::api_keys)]
+pub struct NewApiKey {
+    pub manufacturer_address: String,
+    pub name: String,
+    pub key_prefix: String,
+    pub key_hash: String,
+    pub scopes: Vec<Option<String>>,
+    pub created_at: String,
+}
+
+#[derive(Queryable, Selectable, Insertable, Serialize, ToSchema)]
+#[diesel(table_name = crate::schema::admin_roles)]
+pub struct AdminRoleGrant {
+    #[schema(example = "0x1234567890AbcdEF1234567890aBcdef12345678")]
+    pub address: String,
+    #[schema(example = "operator")]
+    pub role: String,
+    pub granted_by: String,
+    pub granted_at: String,
+}
+
+#[derive(Queryable, Selectable, Serialize, ToSchema)]
+#[diesel(table_name = crate::schema::admin_audit_log)]
+pub struct AdminAuditEntry {
+    #[schema(example = 1)]
+    pub id: i64,
+    #[schema(example = "0x1234567890AbcdEF1234567890aBcdef12345678")]
+    pub actor: String,
+    #[schema(example = "operator")]
+    pub role: String,
+    #[schema(example = "set_authenticity")]
+    pub action: String,
+    #[schema(example = 84532)]
+    pub chain_id: Option<i64>,
+    pub target: Option<String>,
+    #[schema(value_type = Object)]
+    pub details: serde_json::Value,
+    pub succeeded: bool,
+    pub error: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::admin_audit_log)]
+pub struct NewAdminAuditEntry {
+    pub actor: String,
+    pub role: String,
+    pub action: String,
+    pub chain_id: Option<i64>,
+    pub target: Option<String>,
+    pub details: serde_json::Value,
+    pub succeeded: bool,
+    pub error: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Queryable, Selectable, Serialize, Clone)]
+#[diesel(table_name = crate::schema::relayed_transactions)]
+pub struct RelayedTransaction {
+    pub id: i64,
+    pub chain_id: i64,
+    pub kind: String,
+    pub from_address: String,
+    pub to_address: String,
+    pub calldata: String,
+    pub status: String,
+    pub nonce: Option<i64>,
+    pub gas_limit: Option<String>,
+    pub gas_price: Option<String>,
+    pub tnx_hash: Option<String>,
+    pub tnx_hashes: Vec<Option<String>>,
+    pub raw_transaction: Option<String>,
+    pub attempts: i32,
+    pub error: Option<String>,
+    pub intent_id: Option<i64>,
+    pub created_at: String,
+    pub submitted_at: Option<String>,
+    pub updated_at: String,
+    pub job_id: String,
+    pub block_number: Option<i64>,
+    pub revert_reason: Option<String>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::relayed_transactions)]
+pub struct NewRelayedTransaction {
+    pub chain_id: i64,
+    pub kind: String,
+    pub from_address: String,
+    pub to_address: String,
+    pub calldata: String,
+    pub status: String,
+    pub intent_id: Option<i64>,
+    pub created_at: String,
+    pub updated_at: String,
+    pub job_id: String,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::sessions)]
+pub struct NewSession {
+    pub token_hash: String,
+    pub address: String,
+    pub chain_id: i64,
+    pub created_at: String,
+    pub expires_at: String,
+}
 
 #[derive(Deserialize, ToSchema)]
 pub struct ManufacturerQuery {
@@ -165,4 +553,24 @@ pub struct ManufacturerQuery {
     pub(crate) address: Option<String>,
     #[schema(example = "john_doe")]
     pub(crate) username: Option<String>,
+    #[schema(example = 1)]
+    pub(crate) deployment_id: Option<i32>,
+    #[schema(example = 84532)]
+    pub(crate) chain_id: Option<i64>,
+}
+
+// Optional scoping of a read to one deployment or chain; reads span every chain otherwise
+#[derive(Deserialize, ToSchema)]
+pub struct DeploymentQuery {
+    #[schema(example = 1)]
+    pub deployment_id: Option<i32>,
+    #[schema(example = 84532)]
+    pub chain_id: Option<i64>,
+}
+
+// Chain a write is sent to; the default chain when omitted
+#[derive(Deserialize, ToSchema)]
+pub struct ChainQuery {
+    #[schema(example = 84532)]
+    pub chain_id: Option<u64>,
 }
@@ -0,0 +1,314 @@
+use crate::authenticity::authenticity_abi::TrueAuthenticity;
+use crate::authenticity::authenticity_event_listener::authenticity_indexer;
+use crate::config::app_state::AppState;
+use crate::config::chains::ChainConfig;
+use crate::contract_models::{Deployment, NewDeployment};
+use crate::indexer::contract_indexer::ContractIndexer;
+use crate::ownership::ownership_abi::TrueOwnership;
+use crate::ownership::ownership_event::ownership_indexer;
+use crate::schema::{
+    authenticity_settings, certificates, chain_events, contracts, deployments, eip712_domains,
+    failed_events, indexed_blocks, items, manufacturers, ownership_claims, ownership_codes,
+    sync_checkpoints, users_info,
+};
+use chrono::{SecondsFormat, Utc};
+use diesel::prelude::*;
+use diesel::{PgConnection, RunQueryDsl};
+use ecdsa::SigningKey;
+use ethers::core::k256::Secp256k1;
+use ethers::core::utils::to_checksum;
+use ethers::prelude::*;
+use eyre::Result;
+
+// A deployment with contract handles, ready to be indexed
+#[derive(Clone)]
+pub struct DeploymentContracts {
+    pub id: i32,
+    pub chain_id: u64,
+    pub authenticity_contract: TrueAuthenticity<SignerMiddleware<Provider<Http>, Wallet<SigningKey<Secp256k1>>>>,
+    pub ownership_contract: TrueOwnership<SignerMiddleware<Provider<Http>, Wallet<SigningKey<Secp256k1>>>>,
+    pub authenticity_deployment_block: Option<U64>,
+    pub ownership_deployment_block: Option<U64>,
+}
+
+// Records the contract pair configured for each chain as that chain's active deployment and
+// marks every other pair on the chain inactive. Returns the deployment ids, default chain first.
+pub fn register_configured_deployments(
+    conn: &mut PgConnection,
+    state: &AppState,
+) -> Result<Vec<i32>> {
+    let default_chain = state.default_chain();
+    conn.transaction::<_, eyre::Error, _>(|conn| {
+        // Everything indexed before chains were tracked came from the default chain
+        tag_untagged_chain(conn, default_chain.chain_id as i64)?;
+
+        let mut ids = Vec::with_capacity(state.chains.len());
+        for chain in &state.chains {
+            ids.push(register_chain_deployment(conn, chain)?);
+        }
+
+        // Rows indexed before deployments were tracked belong to the default chain's pair
+        tag_untagged_rows(conn, ids[0])?;
+        Ok(ids)
+    })
+}
+
+fn register_chain_deployment(conn: &mut PgConnection, chain: &ChainConfig) -> Result<i32> {
+    let chain_id = chain.chain_id as i64;
+    let authenticity_address = to_checksum(&chain.authenticity_contract.address(), None);
+    let ownership_address = to_checksum(&chain.ownership_contract.address(), None);
+    let authenticity_deployment_block = chain
+        .authenticity_deployment_block
+        .map(|block| block.as_u64() as i64);
+    let ownership_deployment_block = chain
+        .ownership_deployment_block
+        .map(|block| block.as_u64() as i64);
+
+    diesel::update(
+        deployments::table
+            .filter(deployments::chain_id.eq(chain_id))
+            .filter(deployments::is_active.eq(true)),
+    )
+    .set(deployments::is_active.eq(false))
+    .execute(conn)
+    .map_err(|e| eyre::eyre!("Failed to deactivate deployments: {}", e))?;
+
+    diesel::insert_into(deployments::table)
+        .values(NewDeployment {
+            chain_id,
+            authenticity_address,
+            ownership_address,
+            authenticity_deployment_block,
+            ownership_deployment_block,
+            is_active: true,
+            created_at: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+        })
+        .on_conflict((
+            deployments::chain_id,
+            deployments::authenticity_address,
+            deployments::ownership_address,
+        ))
+        .do_update()
+        .set((
+            deployments::is_active.eq(true),
+            deployments::authenticity_deployment_block.eq(authenticity_deployment_block),
+            deployments::ownership_deployment_block.eq(ownership_deployment_block),
+        ))
+        .returning(deployments::id)
+        .get_result(conn)
+        .map_err(|e| {
+            eprintln!("Failed to register deployment on chain {}: {:?}", chain_id, e);
+            eyre::eyre!("Failed to register deployment: {}", e)
+        })
+}
+
+fn tag_untagged_chain(conn: &mut PgConnection, chain_id: i64) -> Result<()> {
+    let map_err = |e: diesel::result::Error| eyre::eyre!("Failed to tag rows with chain: {}", e);
+
+    diesel::update(deployments::table.filter(deployments::chain_id.is_null()))
+        .set(deployments::chain_id.eq(chain_id))
+        .execute(conn)
+        .map_err(map_err)?;
+    diesel::update(contracts::table.filter(contracts::chain_id.is_null()))
+        .set(contracts::chain_id.eq(chain_id))
+        .execute(conn)
+        .map_err(map_err)?;
+    diesel::update(users_info::table.filter(users_info::chain_id.is_null()))
+        .set(users_info::chain_id.eq(chain_id))
+        .execute(conn)
+        .map_err(map_err)?;
+    diesel::update(manufacturers::table.filter(manufacturers::chain_id.is_null()))
+        .set(manufacturers::chain_id.eq(chain_id))
+        .execute(conn)
+        .map_err(map_err)?;
+    diesel::update(items::table.filter(items::chain_id.is_null()))
+        .set(items::chain_id.eq(chain_id))
+        .execute(conn)
+        .map_err(map_err)?;
+    diesel::update(ownership_claims::table.filter(ownership_claims::chain_id.is_null()))
+        .set(ownership_claims::chain_id.eq(chain_id))
+        .execute(conn)
+        .map_err(map_err)?;
+    diesel::update(ownership_codes::table.filter(ownership_codes::chain_id.is_null()))
+        .set(ownership_codes::chain_id.eq(chain_id))
+        .execute(conn)
+        .map_err(map_err)?;
+    diesel::update(
+        authenticity_settings::table.filter(authenticity_settings::chain_id.is_null()),
+    )
+    .set(authenticity_settings::chain_id.eq(chain_id))
+    .execute(conn)
+    .map_err(map_err)?;
+    diesel::update(certificates::table.filter(certificates::chain_id.is_null()))
+        .set(certificates::chain_id.eq(chain_id))
+        .execute(conn)
+        .map_err(map_err)?;
+    diesel::update(chain_events::table.filter(chain_events::chain_id.is_null()))
+        .set(chain_events::chain_id.eq(chain_id))
+        .execute(conn)
+        .map_err(map_err)?;
+    diesel::update(failed_events::table.filter(failed_events::chain_id.is_null()))
+        .set(failed_events::chain_id.eq(chain_id))
+        .execute(conn)
+        .map_err(map_err)?;
+    diesel::update(sync_checkpoints::table.filter(sync_checkpoints::chain_id.is_null()))
+        .set(sync_checkpoints::chain_id.eq(chain_id))
+        .execute(conn)
+        .map_err(map_err)?;
+    diesel::update(indexed_blocks::table.filter(indexed_blocks::chain_id.is_null()))
+        .set(indexed_blocks::chain_id.eq(chain_id))
+        .execute(conn)
+        .map_err(map_err)?;
+
+    Ok(())
+}
+
+fn tag_untagged_rows(conn: &mut PgConnection, deployment_id: i32) -> Result<()> {
+    let map_err = |e: diesel::result::Error| eyre::eyre!("Failed to tag rows with deployment: {}", e);
+
+    diesel::update(contracts::table.filter(contracts::deployment_id.is_null()))
+        .set(contracts::deployment_id.eq(deployment_id))
+        .execute(conn)
+        .map_err(map_err)?;
+    diesel::update(users_info::table.filter(users_info::deployment_id.is_null()))
+        .set(users_info::deployment_id.eq(deployment_id))
+        .execute(conn)
+        .map_err(map_err)?;
+    diesel::update(manufacturers::table.filter(manufacturers::deployment_id.is_null()))
+        .set(manufacturers::deployment_id.eq(deployment_id))
+        .execute(conn)
+        .map_err(map_err)?;
+    diesel::update(items::table.filter(items::deployment_id.is_null()))
+        .set(items::deployment_id.eq(deployment_id))
+        .execute(conn)
+        .map_err(map_err)?;
+    diesel::update(ownership_claims::table.filter(ownership_claims::deployment_id.is_null()))
+        .set(ownership_claims::deployment_id.eq(deployment_id))
+        .execute(conn)
+        .map_err(map_err)?;
+    diesel::update(ownership_codes::table.filter(ownership_codes::deployment_id.is_null()))
+        .set(ownership_codes::deployment_id.eq(deployment_id))
+        .execute(conn)
+        .map_err(map_err)?;
+    diesel::update(
+        authenticity_settings::table.filter(authenticity_settings::deployment_id.is_null()),
+    )
+    .set(authenticity_settings::deployment_id.eq(deployment_id))
+    .execute(conn)
+    .map_err(map_err)?;
+    diesel::update(eip712_domains::table.filter(eip712_domains::deployment_id.is_null()))
+        .set(eip712_domains::deployment_id.eq(deployment_id))
+        .execute(conn)
+        .map_err(map_err)?;
+
+    Ok(())
+}
+
+pub fn list_deployments(conn: &mut PgConnection) -> Result<Vec<Deployment>> {
+    deployments::table
+        .order(deployments::id.asc())
+        .select(Deployment::as_select())
+        .load(conn)
+        .map_err(|e| eyre::eyre!("Failed to load deployments: {}", e))
+}
+
+// Deployment an indexed contract belongs to, used to scope rollbacks
+pub fn deployment_id_for_contract(
+    conn: &mut PgConnection,
+    chain_id: u64,
+    contract_address: &str,
+) -> Result<i32> {
+    deployments::table
+        .filter(deployments::chain_id.eq(chain_id as i64))
+        .filter(
+            deployments::authenticity_address
+                .eq(contract_address)
+                .or(deployments::ownership_address.eq(contract_address)),
+        )
+        .select(deployments::id)
+        .first(conn)
+        .map_err(|e| {
+            eyre::eyre!(
+                "Failed to find deployment for contract {} on chain {}: {}",
+                contract_address,
+                chain_id,
+                e
+            )
+        })
+}
+
+// The chain's active deployment, the one the API sends transactions to
+pub fn active_deployment_id(conn: &mut PgConnection, chain: &ChainConfig) -> Result<i32> {
+    let ownership_address = to_checksum(&chain.ownership_contract.address(), None);
+    deployment_id_for_contract(conn, chain.chain_id, &ownership_address)
+}
+
+// Every listed deployment on a configured chain, with contract handles sharing that chain's
+// client. Deployments on chains that are no longer configured are skipped.
+pub fn deployment_contracts(state: &AppState) -> Result<Vec<DeploymentContracts>> {
+    let conn = &mut state.db_pool.get().map_err(|e| {
+        eprintln!("Failed to get DB connection: {:?}", e);
+        eyre::eyre!("Failed to get DB connection: {}", e)
+    })?;
+
+    list_deployments(conn)?
+        .into_iter()
+        .filter_map(|deployment| {
+            let chain_id = deployment.chain_id.unwrap_or_default() as u64;
+            match state.chain(Some(chain_id)) {
+                Ok(chain) => Some((deployment, chain)),
+                Err(_) => {
+                    eprintln!(
+                        "Skipping deployment {}: chain {} is not configured",
+                        deployment.id, chain_id
+                    );
+                    None
+                }
+            }
+        })
+        .map(|(deployment, chain)| {
+            let client = chain.ownership_contract.client();
+            let authenticity_address: Address =
+                deployment.authenticity_address.parse().map_err(|e| {
+                    eyre::eyre!(
+                        "Invalid authenticity address for deployment {}: {}",
+                        deployment.id,
+                        e
+                    )
+                })?;
+            let ownership_address: Address = deployment.ownership_address.parse().map_err(|e| {
+                eyre::eyre!(
+                    "Invalid ownership address for deployment {}: {}",
+                    deployment.id,
+                    e
+                )
+            })?;
+
+            Ok(DeploymentContracts {
+                id: deployment.id,
+                chain_id: chain.chain_id,
+                authenticity_contract: TrueAuthenticity::new(authenticity_address, client.clone()),
+                ownership_contract: TrueOwnership::new(ownership_address, client.clone()),
+                authenticity_deployment_block: deployment
+                    .authenticity_deployment_block
+                    .map(|block| U64::from(block as u64)),
+                ownership_deployment_block: deployment
+                    .ownership_deployment_block
+                    .map(|block| U64::from(block as u64)),
+            })
+        })
+        .collect()
+}
+
+// Indexers for both contracts of every listed deployment
+pub fn all_indexers(state: &AppState) -> Result<Vec<ContractIndexer>> {
+    Ok(deployment_contracts(state)?
+        .iter()
+        .flat_map(|deployment| {
+            [
+                ownership_indexer(state, deployment),
+                authenticity_indexer(state, deployment),
+            ]
+        })
+        .collect())
+}
@@ -0,0 +1,48 @@
+use chrono::{DateTime, Utc};
+use ethers::prelude::{Middleware, U64};
+use eyre::Result;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+// Block headers kept in memory; events cluster in recent blocks, so the oldest are evicted
+const CACHE_SIZE: usize = 1024;
+
+// Caches block timestamps so several events in one block cost a single header lookup
+pub struct BlockTimestamps {
+    cache: Mutex<BTreeMap<u64, String>>,
+}
+
+impl BlockTimestamps {
+    pub fn new() -> Self {
+        Self {
+            cache: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    // RFC 3339 timestamp of the block
+    pub async fn get<M: Middleware>(&self, client: &M, block_number: U64) -> Result<String> {
+        if let Some(timestamp) = self.cache.lock().unwrap().get(&block_number.as_u64()) {
+            return Ok(timestamp.clone());
+        }
+
+        let block = client
+            .get_block(block_number)
+            .await
+            .map_err(|e| {
+                eprintln!("Failed to get block {}: {:?}", block_number, e.to_string());
+                eyre::eyre!("Failed to get block {}: {}", block_number, e)
+            })?
+            .ok_or_else(|| eyre::eyre!("Block {} not found", block_number))?;
+        let timestamp = DateTime::<Utc>::from_timestamp(block.timestamp.as_u64() as i64, 0)
+            .ok_or_else(|| eyre::eyre!("Invalid timestamp for block {}", block_number))?
+            .to_rfc3339();
+
+        let mut cache = self.cache.lock().unwrap();
+        cache.insert(block_number.as_u64(), timestamp.clone());
+        while cache.len() > CACHE_SIZE {
+            cache.pop_first();
+        }
+
+        Ok(timestamp)
+    }
+}
@@ -0,0 +1,110 @@
+use crate::contract_models::SyncCheckpoint;
+use crate::schema::sync_checkpoints;
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::{PgConnection, RunQueryDsl};
+use ethers::types::U64;
+use eyre::Result;
+
+// Event streams tracked in sync_checkpoints, one per listener
+pub const AUTHENTICITY_STREAM: &str = "authenticity";
+pub const OWNERSHIP_STREAM: &str = "ownership";
+
+// How far back to look on first run when no deployment block is configured
+const DEFAULT_LOOKBACK: u64 = 20;
+
+pub fn load_checkpoint(
+    conn: &mut PgConnection,
+    chain_id: u64,
+    contract_address: &str,
+    stream: &str,
+) -> Result<Option<U64>> {
+    let last_processed_block = sync_checkpoints::table
+        .filter(sync_checkpoints::chain_id.eq(chain_id as i64))
+        .filter(sync_checkpoints::contract_address.eq(contract_address))
+        .filter(sync_checkpoints::stream.eq(stream))
+        .select(sync_checkpoints::last_processed_block)
+        .first::<i64>(conn)
+        .optional()
+        .map_err(|e| {
+            eprintln!(
+                "Failed to load checkpoint for {} ({}): {:?}",
+                stream, contract_address, e
+            );
+            eyre::eyre!("Failed to load checkpoint: {}", e)
+        })?;
+
+    Ok(last_processed_block.map(|block| U64::from(block as u64)))
+}
+
+pub fn save_checkpoint(
+    conn: &mut PgConnection,
+    chain_id: u64,
+    contract_address: &str,
+    stream: &str,
+    block: U64,
+) -> Result<()> {
+    let checkpoint = SyncCheckpoint {
+        contract_address: contract_address.to_string(),
+        stream: stream.to_string(),
+        last_processed_block: block.as_u64() as i64,
+        updated_at: Utc::now().to_rfc3339(),
+        chain_id: Some(chain_id as i64),
+    };
+
+    diesel::insert_into(sync_checkpoints::table)
+        .values(&checkpoint)
+        .on_conflict((
+            sync_checkpoints::chain_id,
+            sync_checkpoints::contract_address,
+            sync_checkpoints::stream,
+        ))
+        .do_update()
+        .set((
+            sync_checkpoints::last_processed_block.eq(checkpoint.last_processed_block),
+            sync_checkpoints::updated_at.eq(&checkpoint.updated_at),
+        ))
+        .execute(conn)
+        .map_err(|e| {
+            eprintln!(
+                "Failed to save checkpoint {} for {} ({}): {:?}",
+                block, stream, contract_address, e
+            );
+            eyre::eyre!("Failed to save checkpoint: {}", e)
+        })?;
+
+    Ok(())
+}
+
+// First block a listener should process: the block after its checkpoint, the configured
+// deployment block on first run, or a short lookback from the head when neither is known
+pub fn resolve_start_block(
+    conn: &mut PgConnection,
+    chain_id: u64,
+    contract_address: &str,
+    stream: &str,
+    deployment_block: Option<U64>,
+    latest_block: U64,
+) -> Result<U64> {
+    if let Some(last_processed_block) = load_checkpoint(conn, chain_id, contract_address, stream)? {
+        eprintln!(
+            "Resuming {} events from checkpoint block {}",
+            stream, last_processed_block
+        );
+        return Ok(last_processed_block + 1);
+    }
+
+    match deployment_block {
+        Some(block) => {
+            eprintln!("No {} checkpoint found, starting from deployment block {}", stream, block);
+            Ok(block)
+        }
+        None => {
+            eprintln!(
+                "No {} checkpoint or deployment block configured, starting {} blocks behind head",
+                stream, DEFAULT_LOOKBACK
+            );
+            Ok(latest_block.saturating_sub(U64::from(DEFAULT_LOOKBACK)))
+        }
+    }
+}
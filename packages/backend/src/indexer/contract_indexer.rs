@@ -0,0 +1,622 @@
+use crate::config::app_state::AppState;
+use crate::contract_models::ChainEvent;
+use crate::indexer::block_timestamps::BlockTimestamps;
+use crate::indexer::checkpoint::{resolve_start_block, save_checkpoint};
+use crate::indexer::dead_letter::{earlier_failed_event, record_failed_event};
+use crate::indexer::event_meta::EventMeta;
+use crate::indexer::event_store::store_chain_event;
+use crate::indexer::handler::{EventHandler, PreparedEvent, RegisteredHandler};
+use crate::indexer::log_source::{LogFile, LogSource, RecordedLog};
+use crate::indexer::metrics::{IndexerMetrics, IndexerMode};
+use crate::indexer::reorg::{detect_reorg, record_block, record_canonical_block, rewind_stream};
+use crate::indexer::transport::StreamTransport;
+use diesel::PgConnection;
+use diesel::prelude::*;
+use ethers::abi::RawLog;
+use ethers::contract::LogMeta;
+use ethers::core::utils::to_checksum;
+use ethers::prelude::{Address, H256, Log, Middleware, Provider, PubsubClient, StreamExt, U64, Ws};
+use eyre::Result;
+use std::sync::Arc;
+use std::time::Duration;
+
+const MIN_RECONNECT_DELAY: Duration = Duration::from_secs(1);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(60);
+
+// Undoes a contract's projections on a chain for blocks after the given ancestor
+pub type RollbackFn = fn(&mut PgConnection, u64, &str, U64) -> Result<()>;
+
+// A log decoded into what chain_events stores for it
+pub struct DecodedLog {
+    pub event_name: String,
+    pub meta: EventMeta,
+    pub payload: serde_json::Value,
+}
+
+// Follows one contract: fetches its logs, dispatches each to the handler registered for its
+// event and keeps the checkpoint and reorg bookkeeping for the stream
+pub struct ContractIndexer {
+    stream: &'static str,
+    chain_id: u64,
+    address: Address,
+    deployment_block: Option<U64>,
+    rollback: RollbackFn,
+    handlers: Vec<Box<dyn RegisteredHandler>>,
+    source: LogSource,
+    timestamps: BlockTimestamps,
+}
+
+impl ContractIndexer {
+    pub fn new(
+        stream: &'static str,
+        chain_id: u64,
+        address: Address,
+        deployment_block: Option<U64>,
+        source: LogSource,
+        rollback: RollbackFn,
+    ) -> Self {
+        Self {
+            stream,
+            chain_id,
+            address,
+            deployment_block,
+            rollback,
+            handlers: Vec::new(),
+            source,
+            timestamps: BlockTimestamps::new(),
+        }
+    }
+
+    pub fn with_handler<H: EventHandler>(mut self, handler: H) -> Self {
+        self.handlers.push(Box::new(Arc::new(handler)));
+        self
+    }
+
+    pub fn stream(&self) -> &'static str {
+        self.stream
+    }
+
+    pub fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+
+    pub fn deployment_block(&self) -> Option<U64> {
+        self.deployment_block
+    }
+
+    pub fn contract_address(&self) -> String {
+        to_checksum(&self.address, None)
+    }
+
+    fn handler_for_log(&self, log: &Log) -> Option<&dyn RegisteredHandler> {
+        let topic0 = log.topics.first()?;
+        self.handlers
+            .iter()
+            .find(|handler| handler.signature() == *topic0)
+            .map(|handler| handler.as_ref())
+    }
+
+    fn handler_named(&self, event_name: &str) -> Option<&dyn RegisteredHandler> {
+        self.handlers
+            .iter()
+            .find(|handler| handler.event_name() == event_name)
+            .map(|handler| handler.as_ref())
+    }
+
+    // Logs for the registered events from `from_block` onwards, in chain order, along with
+    // the last block the provider let us cover (never past `to_block`)
+    pub async fn fetch_logs<M: Middleware>(
+        &self,
+        client: &M,
+        from_block: U64,
+        to_block: U64,
+    ) -> Result<(Vec<Log>, U64)> {
+        let signatures: Vec<H256> = self.handlers.iter().map(|h| h.signature()).collect();
+        self.source
+            .fetch(client, self.address, signatures, from_block, to_block)
+            .await
+    }
+
+    async fn block_timestamp<M: Middleware>(
+        &self,
+        client: &M,
+        block_number: U64,
+    ) -> Result<String> {
+        match self.source.block_timestamp(block_number) {
+            Some(timestamp) => Ok(timestamp),
+            None => self.timestamps.get(client, block_number).await,
+        }
+    }
+
+    async fn event_meta<M: Middleware>(&self, client: &M, log: &Log) -> Result<EventMeta> {
+        let log_meta = LogMeta::from(log);
+        let block_timestamp = self.block_timestamp(client, log_meta.block_number).await?;
+        Ok(EventMeta::new(&log_meta, block_timestamp, self.chain_id))
+    }
+
+    // Decodes a log into the line written by the `record` command
+    pub async fn record_log<M: Middleware>(
+        &self,
+        client: &M,
+        log: &Log,
+    ) -> Result<Option<RecordedLog>> {
+        let Some(handler) = self.handler_for_log(log) else {
+            return Ok(None);
+        };
+        let meta = self.event_meta(client, log).await?;
+        let event = handler.decode_payload(&RawLog::from(log.clone()), &meta)?;
+        Ok(Some(RecordedLog {
+            event_name: handler.event_name(),
+            event,
+            meta: LogMeta::from(log),
+            block_timestamp: meta.block_timestamp,
+            topics: log.topics.clone(),
+            data: log.data.clone(),
+        }))
+    }
+
+    // Decodes a log for the event store without writing it, or None for unregistered events
+    pub async fn decode_log<M: Middleware>(
+        &self,
+        client: &M,
+        log: &Log,
+    ) -> Result<Option<DecodedLog>> {
+        let Some(handler) = self.handler_for_log(log) else {
+            return Ok(None);
+        };
+        let meta = self.event_meta(client, log).await?;
+        let payload = handler.decode_payload(&RawLog::from(log.clone()), &meta)?;
+        Ok(Some(DecodedLog {
+            event_name: handler.event_name(),
+            meta,
+            payload,
+        }))
+    }
+
+    // Stores a decoded log in chain_events without projecting it. Returns false when it was
+    // already stored.
+    pub fn store_decoded(&self, conn: &mut PgConnection, decoded: &DecodedLog) -> Result<bool> {
+        store_chain_event(
+            conn,
+            &self.contract_address(),
+            &decoded.event_name,
+            &decoded.meta,
+            &decoded.payload,
+        )
+    }
+
+    // Stores the raw log and applies its projection in one transaction. Logs already present
+    // in chain_events are skipped, which makes replaying a block range safe. Returns whether
+    // the log was projected.
+    pub async fn handle_log<M: Middleware>(
+        &self,
+        client: &M,
+        conn: &mut PgConnection,
+        log: &Log,
+    ) -> Result<bool> {
+        let Some(handler) = self.handler_for_log(log) else {
+            return Ok(false);
+        };
+        let meta = self.event_meta(client, log).await?;
+        let event_name = handler.event_name();
+        let prepared = handler
+            .prepare_log(&RawLog::from(log.clone()), &meta)
+            .await?;
+        let contract_address = self.contract_address();
+
+        conn.transaction::<_, eyre::Error, _>(|conn| {
+            let payload = prepared.payload()?;
+            if !store_chain_event(conn, &contract_address, &event_name, &meta, &payload)? {
+                eprintln!(
+                    "Skipping already indexed {} event (tx: {}, log: {})",
+                    event_name, meta.tnx_hash, meta.log_index
+                );
+                return Ok(false);
+            }
+
+            prepared.project(conn, &meta)?;
+            Ok(true)
+        })
+    }
+
+    // Like handle_log, but a log that fails to project is dead-lettered and skipped so the
+    // stream keeps moving. A log about the same entity as an earlier dead-lettered one is
+    // dead-lettered behind it without being projected, so the entity's events are applied in
+    // chain order. Only failures to record the dead letter are returned.
+    pub async fn index_log<M: Middleware>(
+        &self,
+        client: &M,
+        conn: &mut PgConnection,
+        metrics: &IndexerMetrics,
+        log: &Log,
+    ) -> Result<()> {
+        let handler = self.handler_for_log(log);
+        let event_name = handler
+            .map(|handler| handler.event_name())
+            .unwrap_or_default();
+        let ordering_key =
+            handler.and_then(|handler| handler.ordering_key(&RawLog::from(log.clone())));
+        let contract_address = self.contract_address();
+
+        let blocked_by = match &ordering_key {
+            Some(ordering_key) => earlier_failed_event(
+                conn,
+                self.chain_id,
+                &contract_address,
+                ordering_key,
+                log.block_number.unwrap_or_default().as_u64() as i64,
+                log.log_index.unwrap_or_default().as_u64() as i64,
+            )?,
+            None => None,
+        };
+        let result = match blocked_by {
+            Some(earlier) => Err(eyre::eyre!("Waiting on earlier failed event {}", earlier)),
+            None => self.handle_log(client, conn, log).await,
+        };
+
+        match result {
+            Ok(_) => metrics.record_event(
+                self.chain_id,
+                self.stream,
+                &contract_address,
+                &event_name,
+            ),
+            Err(error) => {
+                metrics.record_failed_event(
+                    self.chain_id,
+                    self.stream,
+                    &contract_address,
+                    &event_name,
+                    &error,
+                );
+                eprintln!(
+                "Failed to index {} event on {}, moving it to failed_events: {:?}",
+                    event_name, self.stream, error
+                );
+                record_failed_event(conn, self, &event_name, ordering_key.as_deref(), log, &error)?;
+            }
+        }
+        Ok(())
+    }
+
+    // Decodes a stored event and reads its context so it can be projected again. Events stored
+    // before block timestamps were recorded fetch theirs from the chain.
+    pub async fn prepare_stored<M: Middleware>(
+        &self,
+        client: &M,
+        stored: &ChainEvent,
+    ) -> Result<(Box<dyn PreparedEvent>, EventMeta)> {
+        let block_timestamp = match &stored.block_timestamp {
+            Some(timestamp) => timestamp.clone(),
+            None => {
+                self.block_timestamp(client, U64::from(stored.block_number as u64))
+                    .await?
+            }
+        };
+        let meta = EventMeta::from_stored(stored, block_timestamp, self.chain_id);
+        let prepared = self
+            .prepare_payload(&stored.event_name, &stored.payload, &meta)
+            .await?;
+        Ok((prepared, meta))
+    }
+
+    // Reads the context of a decoded log that has not been stored yet
+    pub async fn prepare_decoded(
+        &self,
+        decoded: &DecodedLog,
+    ) -> Result<(Box<dyn PreparedEvent>, EventMeta)> {
+        let prepared = self
+            .prepare_payload(&decoded.event_name, &decoded.payload, &decoded.meta)
+            .await?;
+        Ok((prepared, decoded.meta.clone()))
+    }
+
+    async fn prepare_payload(
+        &self,
+        event_name: &str,
+        payload: &serde_json::Value,
+        meta: &EventMeta,
+    ) -> Result<Box<dyn PreparedEvent>> {
+        let handler = self.handler_named(event_name).ok_or_else(|| {
+            eyre::eyre!("No handler registered for {} on {}", event_name, self.stream)
+        })?;
+        handler.prepare_stored(payload, meta).await
+    }
+
+    pub fn rollback(&self, conn: &mut PgConnection, ancestor: U64) -> Result<()> {
+        (self.rollback)(conn, self.chain_id, &self.contract_address(), ancestor)
+    }
+
+    // Follows the chain from the last checkpoint. New heads come from a pub/sub subscription
+    // when a stream transport is configured, otherwise the node is polled. A recorded log file
+    // is replayed once instead.
+    pub async fn run<M: Middleware>(&self, state: &Arc<AppState>, client: &M) -> Result<()> {
+        if let LogSource::File(file) = &self.source {
+            self.replay_file(state, client, file).await?;
+            eprintln!(
+                "Replayed recorded {} logs up to block {}",
+                self.stream,
+                file.last_block()
+            );
+            return std::future::pending().await;
+        }
+
+        let mut current_block = self.start_block(state, client).await?;
+
+        let Some(transport) = &state.chain(Some(self.chain_id))?.stream_transport else {
+            let poll_interval = client.provider().get_interval();
+            loop {
+                self.sync_to_head(state, client, &mut current_block).await?;
+                tokio::time::sleep(poll_interval).await;
+            }
+        };
+
+        let mut reconnect_delay = MIN_RECONNECT_DELAY;
+        loop {
+            let subscribed = match transport {
+                StreamTransport::Ws(url) => match Provider::<Ws>::connect(url).await {
+                    Ok(provider) => {
+                        self.follow_heads(state, &provider, &mut current_block)
+                            .await?
+                    }
+                    Err(e) => {
+                        eprintln!("Failed to connect to {} for {}: {:?}", url, self.stream, e);
+                        false
+                    }
+                },
+                StreamTransport::Ipc(path) => match Provider::connect_ipc(path).await {
+                    Ok(provider) => {
+                        self.follow_heads(state, &provider, &mut current_block)
+                            .await?
+                    }
+                    Err(e) => {
+                        eprintln!("Failed to connect to {} for {}: {:?}", path, self.stream, e);
+                        false
+                    }
+                },
+            };
+
+            if subscribed {
+                reconnect_delay = MIN_RECONNECT_DELAY;
+            }
+            eprintln!(
+                "Head subscription for {} closed, resubscribing in {:?}",
+                self.stream, reconnect_delay
+            );
+            tokio::time::sleep(reconnect_delay).await;
+            reconnect_delay = (reconnect_delay * 2).min(MAX_RECONNECT_DELAY);
+        }
+    }
+
+    // Indexes the recorded logs after the last checkpoint. Without a checkpoint or deployment
+    // block the whole file is replayed from its earliest log, since its last block is not a
+    // head a lookback could be measured from. The file is taken as canonical, so there is no
+    // reorg bookkeeping.
+    async fn replay_file<M: Middleware>(
+        &self,
+        state: &Arc<AppState>,
+        client: &M,
+        file: &LogFile,
+    ) -> Result<()> {
+        let contract_address = self.contract_address();
+        let metrics = &state.indexer_metrics;
+        let last_block = file.last_block();
+        let conn = &mut state.db_pool.get().map_err(|e| {
+            eprintln!("Failed to get DB connection: {:?}", e);
+            eyre::eyre!("Failed to get DB connection: {}", e)
+        })?;
+        let current_block = resolve_start_block(
+            conn,
+            self.chain_id,
+            &contract_address,
+            self.stream,
+            self.deployment_block.or(Some(file.first_block())),
+            last_block,
+        )?;
+        if current_block > last_block {
+            return Ok(());
+        }
+
+        let (logs, to_block) = self.fetch_logs(client, current_block, last_block).await?;
+        eprintln!(
+            "Replaying {} recorded {} events from block {} to {}",
+            logs.len(),
+            self.stream,
+            current_block,
+            to_block
+        );
+        for log in &logs {
+            self.index_log(client, conn, metrics, log).await?;
+        }
+        save_checkpoint(conn, self.chain_id, &contract_address, self.stream, to_block)?;
+
+        let to_block_timestamp = self.block_timestamp(client, to_block).await?;
+        metrics.record_progress(
+            self.chain_id,
+            self.stream,
+            &contract_address,
+            to_block,
+            &to_block_timestamp,
+            IndexerMode::Streaming,
+        );
+
+        Ok(())
+    }
+
+    // Resume after the last checkpoint, or from the deployment block on first run
+    async fn start_block<M: Middleware>(&self, state: &Arc<AppState>, client: &M) -> Result<U64> {
+        let safe_block = state.confirmation_policy.confirmed_head(client).await?;
+        let conn = &mut state.db_pool.get().map_err(|e| {
+            eprintln!("Failed to get DB connection: {:?}", e);
+            eyre::eyre!("Failed to get DB connection: {}", e)
+        })?;
+        resolve_start_block(
+            conn,
+            self.chain_id,
+            &self.contract_address(),
+            self.stream,
+            self.deployment_block,
+            safe_block,
+        )
+    }
+
+    // Indexes every new head until the subscription ends. Blocks produced while disconnected
+    // are filled in with eth_getLogs from the last processed block before following heads.
+    // Returns whether the subscription was established.
+    async fn follow_heads<P: PubsubClient>(
+        &self,
+        state: &Arc<AppState>,
+        provider: &Provider<P>,
+        current_block: &mut U64,
+    ) -> Result<bool> {
+        let mut heads = match provider.subscribe_blocks().await {
+            Ok(heads) => heads,
+            Err(e) => {
+                eprintln!(
+                    "Failed to subscribe to new heads for {}: {:?}",
+                    self.stream, e
+                );
+                return Ok(false);
+            }
+        };
+        eprintln!("Subscribed to new heads for {}", self.stream);
+
+        self.sync_to_head(state, provider, current_block).await?;
+        while heads.next().await.is_some() {
+            self.sync_to_head(state, provider, current_block).await?;
+        }
+
+        Ok(true)
+    }
+
+    // Indexes up to the confirmed head, recording any failure for the status endpoint
+    async fn sync_to_head<M: Middleware>(
+        &self,
+        state: &Arc<AppState>,
+        client: &M,
+        current_block: &mut U64,
+    ) -> Result<()> {
+        let result = self.index_to_head(state, client, current_block).await;
+        if let Err(error) = &result {
+            state.indexer_metrics.record_error(
+                self.chain_id,
+                self.stream,
+                &self.contract_address(),
+                error,
+            );
+        }
+        result
+    }
+
+    // Indexes everything between `current_block` and the confirmed head, rolling back first
+    // if the chain reorganised since the last run
+    async fn index_to_head<M: Middleware>(
+        &self,
+        state: &Arc<AppState>,
+        client: &M,
+        current_block: &mut U64,
+    ) -> Result<()> {
+        let contract_address = self.contract_address();
+        let metrics = &state.indexer_metrics;
+        let safe_block = state.confirmation_policy.confirmed_head(client).await?;
+        let chain_head = client.get_block_number().await.map_err(|e| {
+            eprintln!("Failed to get latest block: {:?}", e.to_string());
+            eyre::eyre!("Failed to get latest block: {}", e)
+        })?;
+        let chain_head_timestamp = self.timestamps.get(client, chain_head).await?;
+        metrics.record_heads(
+            self.chain_id,
+            self.stream,
+            &contract_address,
+            chain_head,
+            &chain_head_timestamp,
+            safe_block,
+        );
+        let conn = &mut state.db_pool.get().map_err(|e| {
+            eprintln!("Failed to get DB connection: {:?}", e);
+            eyre::eyre!("Failed to get DB connection: {}", e)
+        })?;
+
+        // Undo anything indexed from blocks that are no longer canonical
+        if let Some(ancestor) =
+            detect_reorg(conn, client, self.chain_id, &contract_address, self.stream).await?
+        {
+            conn.transaction::<_, eyre::Error, _>(|conn| {
+                self.rollback(conn, ancestor)?;
+                rewind_stream(conn, self.chain_id, &contract_address, self.stream, ancestor)
+            })?;
+            *current_block = ancestor + 1;
+        }
+
+        // Process confirmed events in chunks
+        let caught_up = *current_block > safe_block;
+        while *current_block <= safe_block {
+            let (logs, to_block) = self.fetch_logs(client, *current_block, safe_block).await?;
+            eprintln!(
+                "Fetched {} {} events from block {} to {}",
+                logs.len(),
+                self.stream,
+                current_block,
+                to_block
+            );
+
+            for log in logs {
+                self.index_log(client, conn, metrics, &log).await?;
+                let meta = LogMeta::from(&log);
+                record_block(
+                    conn,
+                    self.chain_id,
+                    &contract_address,
+                    self.stream,
+                    meta.block_number.as_u64() as i64,
+                    &format!("0x{}", hex::encode(meta.block_hash)),
+                )?;
+            }
+
+            save_checkpoint(conn, self.chain_id, &contract_address, self.stream, to_block)?;
+            *current_block = to_block + 1;
+
+            // More than one window left means the listener is still catching up
+            let mode = if to_block < safe_block {
+                IndexerMode::Backfilling
+            } else {
+                IndexerMode::Streaming
+            };
+            let to_block_timestamp = self.timestamps.get(client, to_block).await?;
+            metrics.record_progress(
+                self.chain_id,
+                self.stream,
+                &contract_address,
+                to_block,
+                &to_block_timestamp,
+                mode,
+            );
+        }
+
+        if caught_up {
+            // Nothing new; the last processed block is the one before `current_block`
+            let last_block = current_block.saturating_sub(U64::one());
+            let last_block_timestamp = self.timestamps.get(client, last_block).await?;
+            metrics.record_progress(
+                self.chain_id,
+                self.stream,
+                &contract_address,
+                last_block,
+                &last_block_timestamp,
+                IndexerMode::Streaming,
+            );
+        } else {
+            record_canonical_block(
+                conn,
+                client,
+                self.chain_id,
+                &contract_address,
+                self.stream,
+                safe_block,
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,476 @@
+use crate::config::app_state::AppState;
+use crate::contract_models::{FailedEvent, NewFailedEvent};
+use crate::deployments::all_indexers;
+use crate::indexer::contract_indexer::ContractIndexer;
+use crate::schema::failed_events;
+use crate::utility::timestamp_after;
+use diesel::prelude::*;
+use diesel::{PgConnection, RunQueryDsl};
+use ethers::prelude::Log;
+use eyre::Result;
+use std::sync::Arc;
+use std::time::Duration;
+
+// Statuses of a dead-lettered event
+pub const STATUS_PENDING: &str = "pending";
+pub const STATUS_FAILED: &str = "failed";
+pub const STATUS_RESOLVED: &str = "resolved";
+
+// Automatic retries before an event is left for manual replay
+const MAX_ATTEMPTS: i32 = 8;
+const BASE_RETRY_DELAY: Duration = Duration::from_secs(30);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(3600);
+
+// How often the background job looks for events that are due
+const RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
+fn retry_delay(attempts: i32) -> Duration {
+    let exponent = (attempts - 1).clamp(0, 16) as u32;
+    (BASE_RETRY_DELAY * 2u32.pow(exponent)).min(MAX_RETRY_DELAY)
+}
+
+// Records a log whose projection failed. A log that fails again is updated in place with
+// the latest error and attempt count, and its next retry is pushed back accordingly.
+pub fn record_failed_event(
+    conn: &mut PgConnection,
+    indexer: &ContractIndexer,
+    event_name: &str,
+    ordering_key: Option<&str>,
+    log: &Log,
+    error: &eyre::Report,
+) -> Result<()> {
+    let raw_log = serde_json::to_value(log)
+        .map_err(|e| eyre::eyre!("Failed to serialize failed log: {}", e))?;
+    let now = timestamp_after(Duration::ZERO);
+    let chain_id = indexer.chain_id() as i64;
+    let tnx_hash = format!(
+        "0x{}",
+        hex::encode(log.transaction_hash.unwrap_or_default())
+    );
+    let log_index = log.log_index.unwrap_or_default().as_u64() as i64;
+
+    let previous_attempts: Option<i32> = failed_events::table
+        .filter(failed_events::chain_id.eq(chain_id))
+        .filter(failed_events::tnx_hash.eq(&tnx_hash))
+        .filter(failed_events::log_index.eq(log_index))
+        .select(failed_events::attempts)
+        .first(conn)
+        .optional()
+        .map_err(|e| eyre::eyre!("Failed to load failed event: {}", e))?;
+    let attempts = previous_attempts.map_or(1, |attempts| attempts + 1);
+    let next_retry_at = timestamp_after(retry_delay(attempts));
+
+    diesel::insert_into(failed_events::table)
+        .values(NewFailedEvent {
+            contract_address: indexer.contract_address(),
+            stream: indexer.stream().to_string(),
+            event_name: event_name.to_string(),
+            block_number: log.block_number.unwrap_or_default().as_u64() as i64,
+            log_index,
+            tnx_hash,
+            raw_log,
+            error: error.to_string(),
+            attempts,
+            status: STATUS_PENDING.to_string(),
+            next_retry_at: next_retry_at.clone(),
+            created_at: now.clone(),
+            updated_at: now.clone(),
+            chain_id,
+            ordering_key: ordering_key.map(str::to_string),
+        })
+        .on_conflict((
+            failed_events::chain_id,
+            failed_events::tnx_hash,
+            failed_events::log_index,
+        ))
+        .do_update()
+        .set((
+            failed_events::error.eq(error.to_string()),
+            failed_events::attempts.eq(attempts),
+            failed_events::status.eq(STATUS_PENDING),
+            failed_events::next_retry_at.eq(&next_retry_at),
+            failed_events::updated_at.eq(&now),
+        ))
+        .execute(conn)
+        .map_err(|e| {
+            eprintln!("Failed to record failed {} event: {:?}", event_name, e);
+            eyre::eyre!("Failed to record failed event: {}", e)
+        })?;
+
+    Ok(())
+}
+
+// The earliest unresolved dead-lettered event with the same ordering key that comes before
+// the given log. Such an event has to be projected first.
+pub fn earlier_failed_event(
+    conn: &mut PgConnection,
+    chain_id: u64,
+    contract_address: &str,
+    ordering_key: &str,
+    block_number: i64,
+    log_index: i64,
+) -> Result<Option<i64>> {
+    failed_events::table
+        .filter(failed_events::chain_id.eq(chain_id as i64))
+        .filter(failed_events::contract_address.eq(contract_address))
+        .filter(failed_events::ordering_key.eq(ordering_key))
+        .filter(failed_events::status.ne(STATUS_RESOLVED))
+        .filter(
+            failed_events::block_number.lt(block_number).or(failed_events::block_number
+                .eq(block_number)
+                .and(failed_events::log_index.lt(log_index))),
+        )
+        .order((
+            failed_events::block_number.asc(),
+            failed_events::log_index.asc(),
+        ))
+        .select(failed_events::id)
+        .first(conn)
+        .optional()
+        .map_err(|e| eyre::eyre!("Failed to look up earlier failed events: {}", e))
+}
+
+fn blocking_failed_event(conn: &mut PgConnection, failed: &FailedEvent) -> Result<Option<i64>> {
+    match (&failed.ordering_key, failed.chain_id) {
+        (Some(ordering_key), Some(chain_id)) => earlier_failed_event(
+            conn,
+            chain_id as u64,
+            &failed.contract_address,
+            ordering_key,
+            failed.block_number,
+            failed.log_index,
+        ),
+        _ => Ok(None),
+    }
+}
+
+// Dead-lettered events from orphaned blocks are dropped along with the blocks
+pub fn delete_failed_events_after(
+    conn: &mut PgConnection,
+    chain_id: u64,
+    contract_address: &str,
+    block_number: i64,
+) -> Result<usize> {
+    diesel::delete(
+        failed_events::table
+            .filter(failed_events::chain_id.eq(chain_id as i64))
+            .filter(failed_events::contract_address.eq(contract_address))
+            .filter(failed_events::block_number.gt(block_number)),
+    )
+    .execute(conn)
+    .map_err(|e| eyre::eyre!("Failed to delete orphaned failed events: {}", e))
+}
+
+pub fn list_failed_events(
+    conn: &mut PgConnection,
+    chain_id: Option<i64>,
+    status: Option<&str>,
+    limit: i64,
+) -> Result<Vec<FailedEvent>> {
+    let mut query = failed_events::table
+        .order((
+            failed_events::block_number.asc(),
+            failed_events::log_index.asc(),
+        ))
+        .limit(limit)
+        .select(FailedEvent::as_select())
+        .into_boxed();
+    if let Some(status) = status {
+        query = query.filter(failed_events::status.eq(status.to_string()));
+    }
+    if let Some(chain_id) = chain_id {
+        query = query.filter(failed_events::chain_id.eq(chain_id));
+    }
+
+    query
+        .load(conn)
+        .map_err(|e| eyre::eyre!("Failed to load failed events: {}", e))
+}
+
+pub fn find_failed_event(conn: &mut PgConnection, id: i64) -> Result<Option<FailedEvent>> {
+    failed_events::table
+        .find(id)
+        .select(FailedEvent::as_select())
+        .first(conn)
+        .optional()
+        .map_err(|e| eyre::eyre!("Failed to load failed event {}: {}", id, e))
+}
+
+// Projects a dead-lettered log again. On success the event is marked resolved; on failure
+// the attempt is recorded and the next retry scheduled, or the event is parked for manual
+// replay once it runs out of attempts. An event parked behind an earlier failed event for
+// the same entity is refused until that one is resolved.
+pub async fn replay_failed_event(
+    state: &AppState,
+    indexers: &[ContractIndexer],
+    conn: &mut PgConnection,
+    failed: &FailedEvent,
+) -> Result<bool> {
+    if let Some(earlier) = blocking_failed_event(conn, failed)? {
+        return Err(eyre::eyre!(
+            "Failed event {} is waiting on earlier failed event {}",
+            failed.id,
+            earlier
+        ));
+    }
+    let indexer = indexers
+        .iter()
+        .find(|indexer| {
+            Some(indexer.chain_id() as i64) == failed.chain_id
+                && indexer.contract_address() == failed.contract_address
+        })
+        .ok_or_else(|| {
+            eyre::eyre!(
+                "No indexer configured for contract {} on chain {:?}",
+                failed.contract_address,
+                failed.chain_id
+            )
+        })?;
+    let client = state
+        .chain(Some(indexer.chain_id()))?
+        .ownership_contract
+        .client();
+    let log: Log = serde_json::from_value(failed.raw_log.clone())
+        .map_err(|e| eyre::eyre!("Failed to decode failed event {}: {}", failed.id, e))?;
+
+    let outcome = indexer.handle_log(client.as_ref(), conn, &log).await;
+    record_replay(conn, failed, outcome)
+}
+
+// Records the outcome of a replay. Only a log that was projected resolves the event. One
+// that was skipped because chain_events already holds it was never applied here, so it
+// counts as a failed attempt and is left for a reindex to project.
+fn record_replay(
+    conn: &mut PgConnection,
+    failed: &FailedEvent,
+    outcome: Result<bool>,
+) -> Result<bool> {
+    let now = timestamp_after(Duration::ZERO);
+    let error = match outcome {
+        Ok(true) => {
+            diesel::update(failed_events::table.find(failed.id))
+                .set((
+                    failed_events::status.eq(STATUS_RESOLVED),
+                    failed_events::updated_at.eq(&now),
+                ))
+                .execute(conn)
+                .map_err(|e| eyre::eyre!("Failed to resolve failed event {}: {}", failed.id, e))?;
+            eprintln!(
+                "Replayed {} event (tx: {}, log: {})",
+                failed.event_name, failed.tnx_hash, failed.log_index
+            );
+            return Ok(true);
+        }
+        Ok(false) => eyre::eyre!(
+            "Event is already in chain_events but was never projected; reindex chain {:?} to apply it",
+            failed.chain_id
+        ),
+        Err(error) => error,
+    };
+
+    let attempts = failed.attempts + 1;
+    let status = if attempts >= MAX_ATTEMPTS {
+        STATUS_FAILED
+    } else {
+        STATUS_PENDING
+    };
+    eprintln!(
+        "Retry {} of {} event (tx: {}, log: {}) failed: {:?}",
+        attempts, failed.event_name, failed.tnx_hash, failed.log_index, error
+    );
+    diesel::update(failed_events::table.find(failed.id))
+        .set((
+            failed_events::error.eq(error.to_string()),
+            failed_events::attempts.eq(attempts),
+            failed_events::status.eq(status),
+            failed_events::next_retry_at.eq(timestamp_after(retry_delay(attempts))),
+            failed_events::updated_at.eq(&now),
+        ))
+        .execute(conn)
+        .map_err(|e| eyre::eyre!("Failed to update failed event {}: {}", failed.id, e))?;
+    Ok(false)
+}
+
+// Background job retrying pending dead-lettered events once their backoff has elapsed
+pub async fn retry_failed_events(state: &Arc<AppState>) -> Result<()> {
+    let indexers = all_indexers(state)?;
+
+    loop {
+        {
+            let conn = &mut state.db_pool.get().map_err(|e| {
+                eprintln!("Failed to get DB connection: {:?}", e);
+                eyre::eyre!("Failed to get DB connection: {}", e)
+            })?;
+
+            let due: Vec<FailedEvent> = failed_events::table
+                .filter(failed_events::status.eq(STATUS_PENDING))
+                .filter(failed_events::next_retry_at.le(timestamp_after(Duration::ZERO)))
+                .order((
+                    failed_events::block_number.asc(),
+                    failed_events::log_index.asc(),
+                ))
+                .select(FailedEvent::as_select())
+                .load(conn)
+                .map_err(|e| eyre::eyre!("Failed to load due failed events: {}", e))?;
+
+            // In chain order, so an event parked behind another is retried right after it
+            for failed in &due {
+                if blocking_failed_event(conn, failed)?.is_some() {
+                    continue;
+                }
+                replay_failed_event(state, &indexers, conn, failed).await?;
+            }
+        }
+
+        tokio::time::sleep(RETRY_INTERVAL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_db::{test_connection, test_indexer};
+    use ethers::types::{H256, U64};
+
+    const CHAIN_ID: u64 = 31_337;
+
+    fn log(block_number: u64, log_index: u64) -> Log {
+        Log {
+            block_number: Some(U64::from(block_number)),
+            log_index: Some(log_index.into()),
+            transaction_hash: Some(H256::from_low_u64_be(block_number * 1_000 + log_index)),
+            ..Default::default()
+        }
+    }
+
+    fn record(conn: &mut PgConnection, ordering_key: &str, block_number: u64, log_index: u64) {
+        let indexer = test_indexer(CHAIN_ID);
+        let error = eyre::eyre!("Projection failed");
+        record_failed_event(
+            conn,
+            &indexer,
+            "OwnershipTransferred",
+            Some(ordering_key),
+            &log(block_number, log_index),
+            &error,
+        )
+        .unwrap();
+    }
+
+    fn load(conn: &mut PgConnection, block_number: u64, log_index: u64) -> FailedEvent {
+        failed_events::table
+            .filter(failed_events::chain_id.eq(CHAIN_ID as i64))
+            .filter(failed_events::block_number.eq(block_number as i64))
+            .filter(failed_events::log_index.eq(log_index as i64))
+            .select(FailedEvent::as_select())
+            .first(conn)
+            .unwrap()
+    }
+
+    fn blocker(
+        conn: &mut PgConnection,
+        ordering_key: &str,
+        block_number: i64,
+        log_index: i64,
+    ) -> Option<i64> {
+        let contract_address = test_indexer(CHAIN_ID).contract_address();
+        earlier_failed_event(
+            conn,
+            CHAIN_ID,
+            &contract_address,
+            ordering_key,
+            block_number,
+            log_index,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn later_events_for_the_same_entity_are_parked() {
+        let Some(mut conn) = test_connection() else {
+            return;
+        };
+        let conn = &mut conn;
+        record(conn, "item-1", 10, 2);
+        let earlier = load(conn, 10, 2);
+
+        assert_eq!(blocker(conn, "item-1", 10, 3), Some(earlier.id));
+        assert_eq!(blocker(conn, "item-1", 11, 0), Some(earlier.id));
+        assert_eq!(blocker(conn, "item-1", 10, 1), None);
+        assert_eq!(blocker(conn, "item-2", 11, 0), None);
+
+        // A parked event is retried only once the one it waits on is resolved
+        record(conn, "item-1", 11, 0);
+        let parked = load(conn, 11, 0);
+        assert_eq!(blocking_failed_event(conn, &parked).unwrap(), Some(earlier.id));
+        assert!(record_replay(conn, &earlier, Ok(true)).unwrap());
+        assert_eq!(blocking_failed_event(conn, &parked).unwrap(), None);
+    }
+
+    #[test]
+    fn only_a_projected_replay_resolves_the_event() {
+        let Some(mut conn) = test_connection() else {
+            return;
+        };
+        let conn = &mut conn;
+        record(conn, "item-1", 10, 0);
+        record(conn, "item-2", 20, 0);
+
+        let projected = load(conn, 10, 0);
+        assert!(record_replay(conn, &projected, Ok(true)).unwrap());
+        assert_eq!(load(conn, 10, 0).status, STATUS_RESOLVED);
+
+        let skipped = load(conn, 20, 0);
+        assert!(!record_replay(conn, &skipped, Ok(false)).unwrap());
+        let skipped = load(conn, 20, 0);
+        assert_eq!(skipped.status, STATUS_PENDING);
+        assert_eq!(skipped.attempts, 2);
+    }
+
+    #[test]
+    fn events_stop_retrying_after_the_attempt_cap() {
+        let Some(mut conn) = test_connection() else {
+            return;
+        };
+        let conn = &mut conn;
+        record(conn, "item-1", 10, 0);
+
+        for attempt in 2..=MAX_ATTEMPTS {
+            let failed = load(conn, 10, 0);
+            let outcome = record_replay(conn, &failed, Err(eyre::eyre!("Still failing")));
+            assert!(!outcome.unwrap());
+            let failed = load(conn, 10, 0);
+            assert_eq!(failed.attempts, attempt);
+            let expected = if attempt < MAX_ATTEMPTS {
+                STATUS_PENDING
+            } else {
+                STATUS_FAILED
+            };
+            assert_eq!(failed.status, expected);
+        }
+    }
+
+    #[test]
+    fn failing_again_pushes_the_next_retry_back() {
+        let Some(mut conn) = test_connection() else {
+            return;
+        };
+        let conn = &mut conn;
+        record(conn, "item-1", 10, 0);
+        let first = load(conn, 10, 0);
+
+        record(conn, "item-1", 10, 0);
+        let second = load(conn, 10, 0);
+
+        assert_eq!(second.attempts, 2);
+        assert!(second.next_retry_at > first.next_retry_at);
+    }
+
+    #[test]
+    fn retry_delay_doubles_up_to_the_maximum() {
+        assert_eq!(retry_delay(1), BASE_RETRY_DELAY);
+        assert_eq!(retry_delay(2), BASE_RETRY_DELAY * 2);
+        assert_eq!(retry_delay(4), BASE_RETRY_DELAY * 8);
+        assert_eq!(retry_delay(MAX_ATTEMPTS * 4), MAX_RETRY_DELAY);
+    }
+}
@@ -0,0 +1,38 @@
+use crate::contract_models::ChainEvent;
+use ethers::contract::LogMeta;
+
+// Position of a decoded log on chain, stored alongside every indexed row
+#[derive(Clone, Debug)]
+pub struct EventMeta {
+    pub tnx_hash: String,
+    pub block_number: i64,
+    pub block_hash: String,
+    pub log_index: i64,
+    // RFC 3339 timestamp of the block, used for the rows' created/registered times
+    pub block_timestamp: String,
+    pub chain_id: i64,
+}
+
+impl EventMeta {
+    pub fn new(meta: &LogMeta, block_timestamp: String, chain_id: u64) -> Self {
+        Self {
+            tnx_hash: format!("0x{}", hex::encode(meta.transaction_hash)),
+            block_number: meta.block_number.as_u64() as i64,
+            block_hash: format!("0x{}", hex::encode(meta.block_hash)),
+            log_index: meta.log_index.as_u64() as i64,
+            block_timestamp,
+            chain_id: chain_id as i64,
+        }
+    }
+
+    pub fn from_stored(event: &ChainEvent, block_timestamp: String, chain_id: u64) -> Self {
+        Self {
+            tnx_hash: event.tnx_hash.clone(),
+            block_number: event.block_number,
+            block_hash: event.block_hash.clone(),
+            log_index: event.log_index,
+            block_timestamp,
+            chain_id: chain_id as i64,
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use crate::contract_models::{ChainEvent, NewChainEvent};
+use crate::indexer::event_meta::EventMeta;
+use crate::schema::chain_events;
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::{PgConnection, RunQueryDsl};
+use eyre::Result;
+use serde::Serialize;
+
+// Appends a decoded log to chain_events. Returns false when the log was already stored for
+// the chain, in which case its projections have already been applied.
+pub fn store_chain_event<E: Serialize>(
+    conn: &mut PgConnection,
+    contract_address: &str,
+    event_name: &str,
+    meta: &EventMeta,
+    event: &E,
+) -> Result<bool> {
+    let payload = serde_json::to_value(event).map_err(|e| {
+        eprintln!("Failed to serialize {} event (tx: {}): {:?}", event_name, meta.tnx_hash, e);
+        eyre::eyre!("Failed to serialize event: {}", e)
+    })?;
+
+    let inserted = diesel::insert_into(chain_events::table)
+        .values(NewChainEvent {
+            contract_address: contract_address.to_string(),
+            event_name: event_name.to_string(),
+            block_number: meta.block_number,
+            block_hash: meta.block_hash.clone(),
+            log_index: meta.log_index,
+            tnx_hash: meta.tnx_hash.clone(),
+            payload,
+            created_at: Utc::now().to_rfc3339(),
+            block_timestamp: Some(meta.block_timestamp.clone()),
+            chain_id: meta.chain_id,
+        })
+        .on_conflict((
+            chain_events::chain_id,
+            chain_events::tnx_hash,
+            chain_events::log_index,
+        ))
+        .do_nothing()
+        .execute(conn)
+        .map_err(|e| {
+            eprintln!(
+                "Failed to store {} event (tx: {}, log: {}): {:?}",
+                event_name, meta.tnx_hash, meta.log_index, e
+            );
+            eyre::eyre!("Failed to store chain event: {}", e)
+        })?;
+
+    Ok(inserted > 0)
+}
+
+// Events from orphaned blocks were never canonical, so they are removed rather than kept
+pub fn delete_chain_events_after(
+    conn: &mut PgConnection,
+    chain_id: u64,
+    contract_address: &str,
+    block_number: i64,
+) -> Result<usize> {
+    diesel::delete(
+        chain_events::table
+            .filter(chain_events::chain_id.eq(chain_id as i64))
+            .filter(chain_events::contract_address.eq(contract_address))
+            .filter(chain_events::block_number.gt(block_number)),
+    )
+    .execute(conn)
+    .map_err(|e| eyre::eyre!("Failed to delete orphaned chain events: {}", e))
+}
+
+// Stored events of one chain in the inclusive block range across all contracts, in chain order
+pub fn load_chain_events(
+    conn: &mut PgConnection,
+    chain_id: u64,
+    from_block: i64,
+    to_block: i64,
+) -> Result<Vec<ChainEvent>> {
+    chain_events::table
+        .filter(chain_events::chain_id.eq(chain_id as i64))
+        .filter(chain_events::block_number.between(from_block, to_block))
+        .order((chain_events::block_number.asc(), chain_events::log_index.asc()))
+        .select(ChainEvent::as_select())
+        .load(conn)
+        .map_err(|e| {
+            eprintln!(
+                "Failed to load chain events for blocks {} to {}: {:?}",
+                from_block, to_block, e
+            );
+            eyre::eyre!("Failed to load chain events: {}", e)
+        })
+}
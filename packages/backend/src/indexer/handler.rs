@@ -0,0 +1,151 @@
+use crate::indexer::event_meta::EventMeta;
+use async_trait::async_trait;
+use diesel::PgConnection;
+use ethers::abi::RawLog;
+use ethers::contract::EthEvent;
+use ethers::types::H256;
+use eyre::Result;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::sync::Arc;
+
+// Projects one contract event into the database. `prepare` runs before the transaction is
+// opened and reads anything the projection needs from the chain; `project` runs inside the
+// transaction that also stores the raw event, so it must not touch the network.
+#[async_trait]
+pub trait EventHandler: Send + Sync + 'static {
+    type Event: EthEvent + Serialize + DeserializeOwned + 'static;
+    type Context: Send + 'static;
+
+    async fn prepare(&self, event: &Self::Event, meta: &EventMeta) -> Result<Self::Context>;
+
+    // Entity whose events must be projected in chain order, such as the item an ownership
+    // event is about. While an event is dead-lettered, later events with the same key are
+    // parked behind it.
+    fn ordering_key(&self, _event: &Self::Event) -> Option<String> {
+        None
+    }
+
+    fn project(
+        &self,
+        conn: &mut PgConnection,
+        event: &Self::Event,
+        context: Self::Context,
+        meta: &EventMeta,
+    ) -> Result<()>;
+}
+
+// A decoded event with its context, ready to be stored and projected
+pub trait PreparedEvent: Send {
+    fn payload(&self) -> Result<serde_json::Value>;
+
+    fn project(self: Box<Self>, conn: &mut PgConnection, meta: &EventMeta) -> Result<()>;
+}
+
+// Type-erased view of an EventHandler so handlers for different events can share a registry
+#[async_trait]
+pub trait RegisteredHandler: Send + Sync {
+    fn event_name(&self) -> String;
+
+    fn signature(&self) -> H256;
+
+    fn decode_payload(&self, log: &RawLog, meta: &EventMeta) -> Result<serde_json::Value>;
+
+    // None for logs that do not decode; handling them reports the error
+    fn ordering_key(&self, log: &RawLog) -> Option<String>;
+
+    async fn prepare_log(&self, log: &RawLog, meta: &EventMeta) -> Result<Box<dyn PreparedEvent>>;
+
+    async fn prepare_stored(
+        &self,
+        payload: &serde_json::Value,
+        meta: &EventMeta,
+    ) -> Result<Box<dyn PreparedEvent>>;
+}
+
+struct Prepared<H: EventHandler> {
+    handler: Arc<H>,
+    event: H::Event,
+    context: H::Context,
+}
+
+impl<H: EventHandler> PreparedEvent for Prepared<H> {
+    fn payload(&self) -> Result<serde_json::Value> {
+        serde_json::to_value(&self.event)
+            .map_err(|e| eyre::eyre!("Failed to serialize {} event: {}", H::Event::name(), e))
+    }
+
+    fn project(self: Box<Self>, conn: &mut PgConnection, meta: &EventMeta) -> Result<()> {
+        self.handler.project(conn, &self.event, self.context, meta)
+    }
+}
+
+impl<H: EventHandler> Prepared<H> {
+    async fn new(handler: Arc<H>, event: H::Event, meta: &EventMeta) -> Result<Self> {
+        let context = handler.prepare(&event, meta).await?;
+        Ok(Self {
+            handler,
+            event,
+            context,
+        })
+    }
+}
+
+#[async_trait]
+impl<H: EventHandler> RegisteredHandler for Arc<H> {
+    fn event_name(&self) -> String {
+        H::Event::name().into_owned()
+    }
+
+    fn signature(&self) -> H256 {
+        H::Event::signature()
+    }
+
+    fn decode_payload(&self, log: &RawLog, meta: &EventMeta) -> Result<serde_json::Value> {
+        let event = decode_event::<H::Event>(log, meta)?;
+        serde_json::to_value(&event)
+            .map_err(|e| eyre::eyre!("Failed to serialize {} event: {}", H::Event::name(), e))
+    }
+
+    fn ordering_key(&self, log: &RawLog) -> Option<String> {
+        let event = H::Event::decode_log(log).ok()?;
+        EventHandler::ordering_key(self.as_ref(), &event)
+    }
+
+    async fn prepare_log(&self, log: &RawLog, meta: &EventMeta) -> Result<Box<dyn PreparedEvent>> {
+        let event = decode_event::<H::Event>(log, meta)?;
+        Ok(Box::new(Prepared::new(self.clone(), event, meta).await?))
+    }
+
+    async fn prepare_stored(
+        &self,
+        payload: &serde_json::Value,
+        meta: &EventMeta,
+    ) -> Result<Box<dyn PreparedEvent>> {
+        let event: H::Event = serde_json::from_value(payload.clone()).map_err(|e| {
+            eprintln!(
+                "Failed to decode stored {} event (tx: {}, log: {}): {:?}",
+                H::Event::name(),
+                meta.tnx_hash,
+                meta.log_index,
+                e
+            );
+            eyre::eyre!("Failed to decode stored {} event: {}", H::Event::name(), e)
+        })?;
+
+        Ok(Box::new(Prepared::new(self.clone(), event, meta).await?))
+    }
+}
+
+fn decode_event<E: EthEvent>(log: &RawLog, meta: &EventMeta) -> Result<E> {
+    E::decode_log(log).map_err(|e| {
+        eprintln!(
+            "Failed to decode {} event (tx: {}, log: {}): {:?}",
+            E::name(),
+            meta.tnx_hash,
+            meta.log_index,
+            e
+        );
+        eyre::eyre!("Failed to decode {} event: {}", E::name(), e)
+    })
+}
@@ -0,0 +1,204 @@
+use ethers::prelude::{Filter, Log, Middleware, U64};
+use eyre::Result;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+// Window used for the first request, before anything is known about the provider's limits
+const INITIAL_WINDOW: u64 = 10;
+
+// Transient failures retried per request before giving up
+const MAX_RETRIES: u32 = 5;
+const BASE_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+// Error fragments providers use when they throttle requests. Some share JSON-RPC code -32005
+// and "limit exceeded" wording with range errors, so these are checked first.
+const RATE_LIMIT_ERRORS: [&str; 8] = [
+    "rate limit",
+    "rate exceeded",
+    "request rate",
+    "too many requests",
+    "request count exceeded",
+    "compute units",
+    "throughput",
+    "try again later",
+];
+
+// Error fragments providers use when a range or its result set is too large
+const RANGE_ERRORS: [&str; 8] = [
+    "block range",
+    "range too large",
+    "range is too large",
+    "too many results",
+    "too many logs",
+    "more than 10000 results",
+    "query returned more than",
+    "response size",
+];
+
+#[derive(Debug, PartialEq, Eq)]
+enum FetchError {
+    // The provider is throttling; back off and retry the same window
+    RateLimited,
+    // The range or its result set is too large; retry at once with a smaller window
+    RangeTooLarge,
+    Other,
+}
+
+// Fetches logs with one eth_getLogs call per window. The window doubles after each successful
+// call up to `max_window` and halves whenever the provider rejects the range or result size,
+// so it settles just under whatever limit the provider enforces.
+pub struct LogFetcher {
+    window: AtomicU64,
+    max_window: u64,
+}
+
+impl LogFetcher {
+    pub fn new(max_window: u64) -> Self {
+        let max_window = max_window.max(1);
+        Self {
+            window: AtomicU64::new(INITIAL_WINDOW.min(max_window)),
+            max_window,
+        }
+    }
+
+    // Fetches logs matching `filter` starting at `from_block`, covering as much of the range up
+    // to `to_block` as the provider allows. Returns the logs in chain order together with the
+    // last block covered.
+    pub async fn fetch<M: Middleware>(
+        &self,
+        client: &M,
+        filter: &Filter,
+        from_block: U64,
+        to_block: U64,
+    ) -> Result<(Vec<Log>, U64)> {
+        let mut retries = 0;
+
+        loop {
+            let window = self.window.load(Ordering::Relaxed);
+            let end_block = (from_block + window - 1).min(to_block);
+            let range_filter = filter.clone().from_block(from_block).to_block(end_block);
+
+            match client.get_logs(&range_filter).await {
+                Ok(mut logs) => {
+                    self.window
+                        .store(grown_window(window, self.max_window), Ordering::Relaxed);
+                    logs.retain(|log| log.removed != Some(true));
+                    logs.sort_by_key(|log| (log.block_number, log.log_index));
+                    return Ok((logs, end_block));
+                }
+                Err(e)
+                    if classify_error(&e.to_string()) == FetchError::RangeTooLarge
+                        && end_block > from_block =>
+                {
+                    let smaller = shrunk_window(from_block.as_u64(), end_block.as_u64());
+                    eprintln!(
+                        "Provider rejected blocks {} to {}, shrinking window to {}: {}",
+                        from_block, end_block, smaller, e
+                    );
+                    self.window.store(smaller, Ordering::Relaxed);
+                }
+                Err(e) if retries < MAX_RETRIES => {
+                    let delay = BASE_RETRY_DELAY * 2u32.pow(retries);
+                    retries += 1;
+                    eprintln!(
+                        "Failed to get logs for blocks {} to {} (attempt {}), retrying in {:?}: {}",
+                        from_block, end_block, retries, delay, e
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                Err(e) => {
+                    eprintln!(
+                        "Failed to get logs for blocks {} to {}: {:?}",
+                        from_block,
+                        end_block,
+                        e.to_string()
+                    );
+                    return Err(eyre::eyre!("Failed to get logs: {}", e));
+                }
+            }
+        }
+    }
+}
+
+fn classify_error(message: &str) -> FetchError {
+    let message = message.to_lowercase();
+    let matches = |fragments: &[&str]| fragments.iter().any(|fragment| message.contains(fragment));
+    if matches(&RATE_LIMIT_ERRORS) {
+        FetchError::RateLimited
+    } else if matches(&RANGE_ERRORS) {
+        FetchError::RangeTooLarge
+    } else {
+        FetchError::Other
+    }
+}
+
+fn grown_window(window: u64, max_window: u64) -> u64 {
+    (window * 2).min(max_window)
+}
+
+// Half of the rejected range, rounded up so a two-block range still makes progress
+fn shrunk_window(from_block: u64, end_block: u64) -> u64 {
+    (end_block - from_block + 1).div_ceil(2).max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn window_starts_small_and_respects_the_maximum() {
+        assert_eq!(LogFetcher::new(2_000).window.load(Ordering::Relaxed), INITIAL_WINDOW);
+        assert_eq!(LogFetcher::new(4).window.load(Ordering::Relaxed), 4);
+        assert_eq!(LogFetcher::new(0).window.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn window_doubles_up_to_the_maximum() {
+        assert_eq!(grown_window(10, 2_000), 20);
+        assert_eq!(grown_window(1_500, 2_000), 2_000);
+        assert_eq!(grown_window(2_000, 2_000), 2_000);
+    }
+
+    #[test]
+    fn window_halves_the_rejected_range() {
+        assert_eq!(shrunk_window(100, 199), 50);
+        assert_eq!(shrunk_window(100, 104), 3);
+        assert_eq!(shrunk_window(100, 101), 1);
+        assert_eq!(shrunk_window(100, 100), 1);
+    }
+
+    #[test]
+    fn range_errors_shrink_the_window() {
+        for message in [
+            "(code: -32005, message: query returned more than 10000 results, data: None)",
+            "Log response size exceeded. You can make eth_getLogs requests with up to a 2K block range",
+            "block range is too wide",
+            "exceed maximum block range: 5000",
+            "Too many logs in the requested range",
+        ] {
+            assert_eq!(classify_error(message), FetchError::RangeTooLarge, "{}", message);
+        }
+    }
+
+    #[test]
+    fn rate_limit_errors_back_off_instead_of_shrinking() {
+        for message in [
+            "(code: -32005, message: project ID request rate exceeded, data: None)",
+            "(code: -32005, message: daily request count exceeded, request rate limited, data: None)",
+            "(code: 429, message: Your app has exceeded its compute units per second capacity, data: None)",
+            "HTTP error 429 Too Many Requests",
+            "limit exceeded: rate limit reached, try again later",
+        ] {
+            assert_eq!(classify_error(message), FetchError::RateLimited, "{}", message);
+        }
+    }
+
+    #[test]
+    fn other_errors_are_retried() {
+        assert_eq!(classify_error("connection reset by peer"), FetchError::Other);
+        assert_eq!(
+            classify_error("(code: -32005, message: limit exceeded, data: None)"),
+            FetchError::Other
+        );
+    }
+}
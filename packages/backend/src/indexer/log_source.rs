@@ -0,0 +1,246 @@
+use crate::config::app_state::AppState;
+use crate::deployments::all_indexers;
+use crate::indexer::log_fetcher::LogFetcher;
+use ethers::contract::LogMeta;
+use ethers::prelude::{Address, Bytes, Filter, H256, Log, Middleware, U64, ValueOrArray};
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::sync::Arc;
+
+// Where an indexer reads its logs from: the chain's node, or a file recorded with the
+// `record` command
+pub enum LogSource {
+    Rpc(LogFetcher),
+    File(Arc<LogFile>),
+}
+
+impl LogSource {
+    // The chain's recorded log file when one is configured, the node otherwise
+    pub fn for_chain(state: &AppState, chain_id: u64) -> Self {
+        match state
+            .chain(Some(chain_id))
+            .ok()
+            .and_then(|chain| chain.log_file.clone())
+        {
+            Some(file) => LogSource::File(file),
+            None => LogSource::Rpc(LogFetcher::new(state.max_log_range)),
+        }
+    }
+
+    // Logs of `address` for the given event signatures from `from_block` onwards, in chain
+    // order, along with the last block covered (never past `to_block`)
+    pub async fn fetch<M: Middleware>(
+        &self,
+        client: &M,
+        address: Address,
+        signatures: Vec<H256>,
+        from_block: U64,
+        to_block: U64,
+    ) -> Result<(Vec<Log>, U64)> {
+        match self {
+            LogSource::Rpc(fetcher) => {
+                let filter = Filter::new()
+                    .address(address)
+                    .topic0(ValueOrArray::Array(signatures));
+                fetcher.fetch(client, &filter, from_block, to_block).await
+            }
+            LogSource::File(file) => Ok((
+                file.logs(address, &signatures, from_block, to_block),
+                to_block,
+            )),
+        }
+    }
+
+    // Timestamp recorded alongside the block's logs; the node is asked otherwise
+    pub fn block_timestamp(&self, block_number: U64) -> Option<String> {
+        match self {
+            LogSource::Rpc(_) => None,
+            LogSource::File(file) => file.timestamps.get(&block_number.as_u64()).cloned(),
+        }
+    }
+}
+
+// One line of a recorded log file. `event` and `meta` are what `query_with_meta` returns for
+// the log; `topics` and `data` keep the raw encoding so the log can be decoded again.
+#[derive(Serialize, Deserialize)]
+pub struct RecordedLog {
+    pub event_name: String,
+    pub event: serde_json::Value,
+    pub meta: LogMeta,
+    pub block_timestamp: String,
+    pub topics: Vec<H256>,
+    pub data: Bytes,
+}
+
+impl RecordedLog {
+    fn to_log(&self) -> Log {
+        Log {
+            address: self.meta.address,
+            topics: self.topics.clone(),
+            data: self.data.clone(),
+            block_hash: Some(self.meta.block_hash),
+            block_number: Some(self.meta.block_number),
+            transaction_hash: Some(self.meta.transaction_hash),
+            transaction_index: Some(self.meta.transaction_index),
+            log_index: Some(self.meta.log_index),
+            removed: Some(false),
+            ..Default::default()
+        }
+    }
+}
+
+// Recorded logs of every contract on a chain, loaded once at startup
+pub struct LogFile {
+    logs: Vec<Log>,
+    timestamps: HashMap<u64, String>,
+    first_block: U64,
+    last_block: U64,
+}
+
+impl LogFile {
+    pub fn load(path: &str) -> Result<Self> {
+        let file = File::open(path).map_err(|e| {
+            eprintln!("Failed to open log file {}: {:?}", path, e);
+            eyre::eyre!("Failed to open log file {}: {}", path, e)
+        })?;
+
+        let mut logs = Vec::new();
+        let mut timestamps = HashMap::new();
+        for (index, line) in BufReader::new(file).lines().enumerate() {
+            let line = line.map_err(|e| eyre::eyre!("Failed to read log file {}: {}", path, e))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let recorded: RecordedLog = serde_json::from_str(&line).map_err(|e| {
+                eprintln!("Invalid recorded log on line {} of {}: {:?}", index + 1, path, e);
+                eyre::eyre!("Invalid recorded log on line {} of {}: {}", index + 1, path, e)
+            })?;
+            timestamps.insert(
+                recorded.meta.block_number.as_u64(),
+                recorded.block_timestamp.clone(),
+            );
+            logs.push(recorded.to_log());
+        }
+        logs.sort_by_key(|log| (log.block_number, log.log_index));
+        let first_block = logs
+            .first()
+            .and_then(|log| log.block_number)
+            .unwrap_or_default();
+        let last_block = logs
+            .last()
+            .and_then(|log| log.block_number)
+            .unwrap_or_default();
+        eprintln!("Loaded {} recorded logs from {}", logs.len(), path);
+
+        Ok(Self {
+            logs,
+            timestamps,
+            first_block,
+            last_block,
+        })
+    }
+
+    // Block of the earliest recorded log, where a replay on an empty database starts
+    pub fn first_block(&self) -> U64 {
+        self.first_block
+    }
+
+    // The file stands in for the chain head, so replays index up to its last log
+    pub fn last_block(&self) -> U64 {
+        self.last_block
+    }
+
+    fn logs(
+        &self,
+        address: Address,
+        signatures: &[H256],
+        from_block: U64,
+        to_block: U64,
+    ) -> Vec<Log> {
+        self.logs
+            .iter()
+            .filter(|log| log.address == address)
+            .filter(|log| {
+                log.topics
+                    .first()
+                    .is_some_and(|topic0| signatures.contains(topic0))
+            })
+            .filter(|log| {
+                log.block_number
+                    .is_some_and(|block| block >= from_block && block <= to_block)
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+// Writes every log of the chain's deployments in the block range to `path`, one
+// RecordedLog per line in chain order
+pub async fn record_logs(
+    state: &Arc<AppState>,
+    chain_id: Option<u64>,
+    from_block: u64,
+    to_block: u64,
+    path: &str,
+) -> Result<()> {
+    let chain = state.chain(chain_id)?;
+    let chain_id = chain.chain_id;
+    let client = chain.ownership_contract.client();
+    if from_block > to_block {
+        return Err(eyre::eyre!(
+            "Invalid block range: {} is after {}",
+            from_block,
+            to_block
+        ));
+    }
+    let (from_block, to_block) = (U64::from(from_block), U64::from(to_block));
+
+    let mut recorded = Vec::new();
+    for indexer in all_indexers(state)?
+        .iter()
+        .filter(|indexer| indexer.chain_id() == chain_id)
+    {
+        let mut current_block = from_block;
+        while current_block <= to_block {
+            let (logs, chunk_end) = indexer
+                .fetch_logs(client.as_ref(), current_block, to_block)
+                .await?;
+            for log in &logs {
+                if let Some(entry) = indexer.record_log(client.as_ref(), log).await? {
+                    recorded.push(entry);
+                }
+            }
+            current_block = chunk_end + 1;
+        }
+    }
+    recorded.sort_by_key(|entry| (entry.meta.block_number, entry.meta.log_index));
+
+    let file = File::create(path).map_err(|e| {
+        eprintln!("Failed to create log file {}: {:?}", path, e);
+        eyre::eyre!("Failed to create log file {}: {}", path, e)
+    })?;
+    let mut writer = BufWriter::new(file);
+    for entry in &recorded {
+        let line = serde_json::to_string(entry)
+            .map_err(|e| eyre::eyre!("Failed to serialize recorded log: {}", e))?;
+        writeln!(writer, "{}", line)
+            .map_err(|e| eyre::eyre!("Failed to write log file {}: {}", path, e))?;
+    }
+    writer
+        .flush()
+        .map_err(|e| eyre::eyre!("Failed to write log file {}: {}", path, e))?;
+
+    eprintln!(
+        "Recorded {} logs on chain {} from block {} to {} to {}",
+        recorded.len(),
+        chain_id,
+        from_block,
+        to_block,
+        path
+    );
+
+    Ok(())
+}
@@ -0,0 +1,255 @@
+use chrono::{DateTime, Utc};
+use ethers::prelude::U64;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Arc, RwLock};
+use utoipa::ToSchema;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum IndexerMode {
+    // Working through a range wider than a single eth_getLogs window
+    Backfilling,
+    // Caught up with the confirmed head and following new blocks
+    Streaming,
+}
+
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct StreamStatus {
+    pub chain_id: u64,
+    pub stream: String,
+    pub contract_address: String,
+    pub mode: IndexerMode,
+    pub chain_head: Option<u64>,
+    pub confirmed_head: Option<u64>,
+    pub last_processed_block: Option<u64>,
+    pub lag_blocks: Option<u64>,
+    pub lag_seconds: Option<i64>,
+    pub events_processed: BTreeMap<String, u64>,
+    pub events_failed: BTreeMap<String, u64>,
+    pub last_error: Option<String>,
+    pub last_error_at: Option<String>,
+    pub updated_at: String,
+    #[serde(skip)]
+    chain_head_timestamp: Option<DateTime<Utc>>,
+    #[serde(skip)]
+    last_processed_timestamp: Option<DateTime<Utc>>,
+}
+
+// Metric name, help text and how to read it from a stream's status
+type Gauge = (&'static str, &'static str, fn(&StreamStatus) -> Option<f64>);
+type Counter = (&'static str, &'static str, fn(&StreamStatus) -> &BTreeMap<String, u64>);
+
+// Progress of each indexed stream, as reported by the listeners themselves
+#[derive(Clone, Default)]
+pub struct IndexerMetrics {
+    streams: Arc<RwLock<BTreeMap<String, StreamStatus>>>,
+}
+
+impl IndexerMetrics {
+    fn update(
+        &self,
+        chain_id: u64,
+        stream: &str,
+        contract_address: &str,
+        apply: impl FnOnce(&mut StreamStatus),
+    ) {
+        let mut streams = self.streams.write().unwrap();
+        // Each deployment has its own stream per contract and chain
+        let status = streams
+            .entry(format!("{}:{}:{}", chain_id, stream, contract_address))
+            .or_insert_with(|| StreamStatus {
+                chain_id,
+                stream: stream.to_string(),
+                contract_address: contract_address.to_string(),
+                mode: IndexerMode::Backfilling,
+                chain_head: None,
+                confirmed_head: None,
+                last_processed_block: None,
+                lag_blocks: None,
+                lag_seconds: None,
+                events_processed: BTreeMap::new(),
+                events_failed: BTreeMap::new(),
+                last_error: None,
+                last_error_at: None,
+                updated_at: Utc::now().to_rfc3339(),
+                chain_head_timestamp: None,
+                last_processed_timestamp: None,
+            });
+        apply(status);
+
+        // Lag is derived from the last block the listener has checkpointed
+        if let (Some(head), Some(processed)) = (status.chain_head, status.last_processed_block) {
+            status.lag_blocks = Some(head.saturating_sub(processed));
+        }
+        if let (Some(head), Some(processed)) =
+            (status.chain_head_timestamp, status.last_processed_timestamp)
+        {
+            status.lag_seconds = Some((head - processed).num_seconds().max(0));
+        }
+        status.updated_at = Utc::now().to_rfc3339();
+    }
+
+    pub fn record_heads(
+        &self,
+        chain_id: u64,
+        stream: &str,
+        contract_address: &str,
+        chain_head: U64,
+        chain_head_timestamp: &str,
+        confirmed_head: U64,
+    ) {
+        self.update(chain_id, stream, contract_address, |status| {
+            status.chain_head = Some(chain_head.as_u64());
+            status.chain_head_timestamp = parse_timestamp(chain_head_timestamp);
+            status.confirmed_head = Some(confirmed_head.as_u64());
+        });
+    }
+
+    pub fn record_progress(
+        &self,
+        chain_id: u64,
+        stream: &str,
+        contract_address: &str,
+        block: U64,
+        block_timestamp: &str,
+        mode: IndexerMode,
+    ) {
+        self.update(chain_id, stream, contract_address, |status| {
+            status.last_processed_block = Some(block.as_u64());
+            status.last_processed_timestamp = parse_timestamp(block_timestamp);
+            status.mode = mode;
+        });
+    }
+
+    pub fn record_event(
+        &self,
+        chain_id: u64,
+        stream: &str,
+        contract_address: &str,
+        event_name: &str,
+    ) {
+        self.update(chain_id, stream, contract_address, |status| {
+            *status
+                .events_processed
+                .entry(event_name.to_string())
+                .or_default() += 1;
+        });
+    }
+
+    pub fn record_failed_event(
+        &self,
+        chain_id: u64,
+        stream: &str,
+        contract_address: &str,
+        event_name: &str,
+        error: &eyre::Report,
+    ) {
+        self.update(chain_id, stream, contract_address, |status| {
+            *status
+                .events_failed
+                .entry(event_name.to_string())
+                .or_default() += 1;
+            status.last_error = Some(error.to_string());
+            status.last_error_at = Some(Utc::now().to_rfc3339());
+        });
+    }
+
+    pub fn record_error(
+        &self,
+        chain_id: u64,
+        stream: &str,
+        contract_address: &str,
+        error: &eyre::Report,
+    ) {
+        self.update(chain_id, stream, contract_address, |status| {
+            status.last_error = Some(error.to_string());
+            status.last_error_at = Some(Utc::now().to_rfc3339());
+        });
+    }
+
+    pub fn statuses(&self) -> Vec<StreamStatus> {
+        self.streams.read().unwrap().values().cloned().collect()
+    }
+
+    // The same figures in the Prometheus text exposition format
+    pub fn render_prometheus(&self) -> String {
+        let statuses = self.statuses();
+        let mut out = String::new();
+
+        let gauges: [Gauge; 5] = [
+            ("indexer_chain_head", "Latest block reported by the node", |s| {
+                s.chain_head.map(|v| v as f64)
+            }),
+            ("indexer_confirmed_head", "Latest block considered confirmed", |s| {
+                s.confirmed_head.map(|v| v as f64)
+            }),
+            ("indexer_last_processed_block", "Last block checkpointed by the listener", |s| {
+                s.last_processed_block.map(|v| v as f64)
+            }),
+            ("indexer_lag_blocks", "Blocks between the chain head and the last processed block", |s| {
+                s.lag_blocks.map(|v| v as f64)
+            }),
+            ("indexer_lag_seconds", "Seconds between the chain head and the last processed block", |s| {
+                s.lag_seconds.map(|v| v as f64)
+            }),
+        ];
+        for (name, help, value) in gauges {
+            let _ = writeln!(out, "# HELP {} {}\n# TYPE {} gauge", name, help, name);
+            for status in &statuses {
+                if let Some(value) = value(status) {
+                    let _ = writeln!(out, "{}{{{}}} {}", name, labels(status), value);
+                }
+            }
+        }
+
+        let _ = writeln!(
+            out,
+            "# HELP indexer_backfilling Whether the listener is backfilling (1) or streaming (0)\n# TYPE indexer_backfilling gauge"
+        );
+        for status in &statuses {
+            let backfilling = (status.mode == IndexerMode::Backfilling) as u8;
+            let _ = writeln!(out, "indexer_backfilling{{{}}} {}", labels(status), backfilling);
+        }
+
+        let counters: [Counter; 2] = [
+            ("indexer_events_processed_total", "Events projected, per event type", |s| {
+                &s.events_processed
+            }),
+            ("indexer_events_failed_total", "Events that failed to project, per event type", |s| {
+                &s.events_failed
+            }),
+        ];
+        for (name, help, events) in counters {
+            let _ = writeln!(out, "# HELP {} {}\n# TYPE {} counter", name, help, name);
+            for status in &statuses {
+                for (event, count) in events(status) {
+                    let _ = writeln!(
+                        out,
+                        "{}{{{},event=\"{}\"}} {}",
+                        name,
+                        labels(status),
+                        event,
+                        count
+                    );
+                }
+            }
+        }
+
+        out
+    }
+}
+
+fn labels(status: &StreamStatus) -> String {
+    format!(
+        "chain_id=\"{}\",stream=\"{}\",contract=\"{}\"",
+        status.chain_id, status.stream, status.contract_address
+    )
+}
+
+fn parse_timestamp(timestamp: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .ok()
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+}
@@ -0,0 +1,14 @@
+pub mod block_timestamps;
+pub mod checkpoint;
+pub mod contract_indexer;
+pub mod dead_letter;
+pub mod event_meta;
+pub mod event_store;
+pub mod handler;
+pub mod log_fetcher;
+pub mod log_source;
+pub mod metrics;
+pub mod reconcile;
+pub mod reindex;
+pub mod reorg;
+pub mod transport;
@@ -0,0 +1,298 @@
+use crate::config::app_state::AppState;
+use crate::contract_models::{NewReconciliationRun, ReconciliationRun};
+use crate::deployments::{deployment_contracts, DeploymentContracts};
+use crate::indexer::checkpoint::{load_checkpoint, OWNERSHIP_STREAM};
+use crate::schema::{items, reconciliation_runs};
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::{PgConnection, RunQueryDsl};
+use ethers::core::utils::to_checksum;
+use ethers::prelude::{Address, U64};
+use eyre::Result;
+use serde::Serialize;
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+
+// Snapshot of the fields compared between an items row and `getItem`
+#[derive(Queryable, Serialize)]
+struct ItemState {
+    name: String,
+    serial: String,
+    date: i64,
+    owner: String,
+    manufacturer: String,
+    metadata: Vec<Option<String>>,
+}
+
+#[derive(Serialize)]
+struct ItemMismatch {
+    deployment_id: i32,
+    item_id: String,
+    // Block both sides were read at
+    block_number: i64,
+    // Differing fields, or `missing_on_chain` when `getItem` reverts
+    fields: Vec<&'static str>,
+    database: ItemState,
+    chain: Option<ItemState>,
+    repaired: bool,
+}
+
+// Compares every indexed item of a chain against `getItem` and `isOwner` and stores a
+// summary in reconciliation_runs. Each deployment is read at its ownership checkpoint so
+// blocks the listener has not processed yet are not reported as drift. With `repair` the
+// row is overwritten with the on-chain values, unless the listener changed its owner in the
+// meantime; items missing on chain are only reported.
+pub async fn reconcile(
+    state: &Arc<AppState>,
+    chain_id: Option<u64>,
+    repair: bool,
+) -> Result<ReconciliationRun> {
+    let chain_id = state.chain(chain_id)?.chain_id;
+    let started_at = Utc::now().to_rfc3339();
+    let deployments: Vec<_> = deployment_contracts(state)?
+        .into_iter()
+        .filter(|deployment| deployment.chain_id == chain_id)
+        .collect();
+
+    let mut items_checked = 0;
+    let mut mismatches = Vec::new();
+    for deployment in &deployments {
+        items_checked += reconcile_deployment(state, deployment, repair, &mut mismatches).await?;
+    }
+
+    let repaired = mismatches.iter().filter(|mismatch| mismatch.repaired).count();
+    let conn = &mut state.db_pool.get().map_err(|e| {
+        eprintln!("Failed to get DB connection: {:?}", e);
+        eyre::eyre!("Failed to get DB connection: {}", e)
+    })?;
+    let run = diesel::insert_into(reconciliation_runs::table)
+        .values(NewReconciliationRun {
+            chain_id: chain_id as i64,
+            repair,
+            items_checked: items_checked as i64,
+            mismatches: mismatches.len() as i64,
+            repaired: repaired as i64,
+            report: json!({ "mismatches": mismatches }),
+            started_at,
+            finished_at: Utc::now().to_rfc3339(),
+        })
+        .returning(ReconciliationRun::as_returning())
+        .get_result(conn)
+        .map_err(|e| {
+            eprintln!("Failed to store reconciliation run: {:?}", e);
+            eyre::eyre!("Failed to store reconciliation run: {}", e)
+        })?;
+
+    eprintln!(
+        "Reconciled {} item(s) on chain {}: {} mismatch(es), {} repaired",
+        run.items_checked, chain_id, run.mismatches, run.repaired
+    );
+
+    Ok(run)
+}
+
+async fn reconcile_deployment(
+    state: &Arc<AppState>,
+    deployment: &DeploymentContracts,
+    repair: bool,
+    mismatches: &mut Vec<ItemMismatch>,
+) -> Result<usize> {
+    let contract = &deployment.ownership_contract;
+    let contract_address = to_checksum(&contract.address(), None);
+    let conn = &mut state.db_pool.get().map_err(|e| {
+        eprintln!("Failed to get DB connection: {:?}", e);
+        eyre::eyre!("Failed to get DB connection: {}", e)
+    })?;
+
+    let Some(block) = load_checkpoint(conn, deployment.chain_id, &contract_address, OWNERSHIP_STREAM)?
+    else {
+        eprintln!(
+            "Skipping reconciliation of deployment {}: ownership events not indexed yet",
+            deployment.id
+        );
+        return Ok(0);
+    };
+
+    let rows: Vec<(String, ItemState)> = items::table
+        .filter(items::deployment_id.eq(deployment.id))
+        .order(items::id.asc())
+        .select((
+            items::item_id,
+            (
+                items::name,
+                items::serial,
+                items::date,
+                items::owner,
+                items::manufacturer,
+                items::metadata,
+            ),
+        ))
+        .load(conn)
+        .map_err(|e| {
+            eprintln!("Failed to load items of deployment {}: {:?}", deployment.id, e);
+            eyre::eyre!("Failed to load items: {}", e)
+        })?;
+    let items_checked = rows.len();
+
+    for (item_id, database) in rows {
+        let chain = match contract.get_item(item_id.clone()).block(block).call().await {
+            Ok(item) => ItemState {
+                name: item.name,
+                serial: item.serial,
+                date: item.date.to_string().parse::<i64>().map_err(|e| {
+                    eprintln!("Failed to parse item date: {:?}", e);
+                    eyre::eyre!("Failed to parse item date: {}", e)
+                })?,
+                owner: to_checksum(&item.owner, None),
+                manufacturer: item.manufacturer,
+                metadata: item.metadata.into_iter().map(Some).collect(),
+            },
+            Err(e) if e.is_revert() => {
+                mismatches.push(ItemMismatch {
+                    deployment_id: deployment.id,
+                    item_id,
+                    block_number: block.as_u64() as i64,
+                    fields: vec!["missing_on_chain"],
+                    database,
+                    chain: None,
+                    repaired: false,
+                });
+                continue;
+            }
+            Err(e) => {
+                eprintln!("Failed to call get_item for item_id {}: {:?}", item_id, e);
+                return Err(eyre::eyre!("Failed to call get_item: {}", e));
+            }
+        };
+
+        let is_owner = is_owner(deployment, &database.owner, &item_id, block).await?;
+        let fields = differing_fields(&database, &chain, is_owner);
+        if fields.is_empty() {
+            continue;
+        }
+
+        let repaired = repair && repair_item(conn, deployment.id, &item_id, &database, &chain)?;
+        mismatches.push(ItemMismatch {
+            deployment_id: deployment.id,
+            item_id,
+            block_number: block.as_u64() as i64,
+            fields,
+            database,
+            chain: Some(chain),
+            repaired,
+        });
+    }
+
+    Ok(items_checked)
+}
+
+// An owner that does not parse as an address cannot own anything on chain
+async fn is_owner(
+    deployment: &DeploymentContracts,
+    owner: &str,
+    item_id: &str,
+    block: U64,
+) -> Result<bool> {
+    let Ok(owner) = owner.parse::<Address>() else {
+        return Ok(false);
+    };
+
+    deployment
+        .ownership_contract
+        .is_owner(owner, item_id.to_string())
+        .block(block)
+        .call()
+        .await
+        .map_err(|e| {
+            eprintln!("Failed to call is_owner for item_id {}: {:?}", item_id, e);
+            eyre::eyre!("Failed to call is_owner: {}", e)
+        })
+}
+
+fn differing_fields(database: &ItemState, chain: &ItemState, is_owner: bool) -> Vec<&'static str> {
+    let mut fields = Vec::new();
+    if !is_owner || !database.owner.eq_ignore_ascii_case(&chain.owner) {
+        fields.push("owner");
+    }
+    if database.name != chain.name {
+        fields.push("name");
+    }
+    if database.serial != chain.serial {
+        fields.push("serial");
+    }
+    if database.date != chain.date {
+        fields.push("date");
+    }
+    if database.manufacturer != chain.manufacturer {
+        fields.push("manufacturer");
+    }
+    if database.metadata != chain.metadata {
+        fields.push("metadata");
+    }
+    fields
+}
+
+// Only overwrites the row if its owner is still the one that was compared
+fn repair_item(
+    conn: &mut PgConnection,
+    deployment_id: i32,
+    item_id: &str,
+    database: &ItemState,
+    chain: &ItemState,
+) -> Result<bool> {
+    let updated = diesel::update(
+        items::table
+            .filter(items::deployment_id.eq(deployment_id))
+            .filter(items::item_id.eq(item_id))
+            .filter(items::owner.eq(&database.owner)),
+    )
+    .set((
+        items::name.eq(&chain.name),
+        items::serial.eq(&chain.serial),
+        items::date.eq(chain.date),
+        items::owner.eq(&chain.owner),
+        items::manufacturer.eq(&chain.manufacturer),
+        items::metadata.eq(&chain.metadata),
+    ))
+    .execute(conn)
+    .map_err(|e| {
+        eprintln!("Failed to repair item {}: {:?}", item_id, e);
+        eyre::eyre!("Failed to repair item: {}", e)
+    })?;
+
+    Ok(updated > 0)
+}
+
+// Background variant, enabled by RECONCILE_INTERVAL_SECS
+pub async fn reconcile_periodically(
+    state: &Arc<AppState>,
+    interval: Duration,
+    repair: bool,
+) -> Result<()> {
+    loop {
+        tokio::time::sleep(interval).await;
+        for chain in &state.chains {
+            reconcile(state, Some(chain.chain_id), repair).await?;
+        }
+    }
+}
+
+pub fn list_reconciliation_runs(
+    conn: &mut PgConnection,
+    chain_id: Option<i64>,
+    limit: i64,
+) -> Result<Vec<ReconciliationRun>> {
+    let mut query = reconciliation_runs::table
+        .order(reconciliation_runs::id.desc())
+        .limit(limit)
+        .select(ReconciliationRun::as_select())
+        .into_boxed();
+    if let Some(chain_id) = chain_id {
+        query = query.filter(reconciliation_runs::chain_id.eq(chain_id));
+    }
+
+    query
+        .load(conn)
+        .map_err(|e| eyre::eyre!("Failed to load reconciliation runs: {}", e))
+}
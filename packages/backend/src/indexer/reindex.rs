@@ -0,0 +1,373 @@
+use crate::config::app_state::AppState;
+use crate::config::leader::hold_indexer_lock;
+use crate::deployments::{all_indexers, register_configured_deployments};
+use crate::indexer::checkpoint::{load_checkpoint, save_checkpoint};
+use crate::indexer::contract_indexer::{ContractIndexer, DecodedLog};
+use crate::indexer::event_meta::EventMeta;
+use crate::indexer::event_store::load_chain_events;
+use crate::indexer::handler::PreparedEvent;
+use diesel::prelude::*;
+use diesel::sql_types::BigInt;
+use diesel::{PgConnection, RunQueryDsl};
+use ethers::prelude::U64;
+use eyre::Result;
+use std::collections::{BTreeSet, HashSet};
+use std::sync::Arc;
+
+// Tables derived from contract events and tagged with their chain, rebuilt by a reindex
+const PROJECTION_TABLES: [&str; 7] = [
+    "items",
+    "ownership_claims",
+    "manufacturers",
+    "users_info",
+    "contracts",
+    "authenticity_settings",
+    "eip712_domains",
+];
+
+// Clears one chain's projections and rebuilds them from its event store. Logs in the block
+// range are fetched and decoded first, then every event of the chain, stored or backfilled,
+// is prepared in chain order. Only then are the backfilled logs written to chain_events and
+// the projections rebuilt, all in a single transaction, so a failed reindex leaves both the
+// event store and the previous projections untouched. The range only bounds the backfill;
+// events stored outside it are replayed and kept. The indexer lock is held throughout, so it
+// refuses to run while an indexer is writing to the same tables.
+pub async fn reindex(
+    state: &Arc<AppState>,
+    chain_id: Option<u64>,
+    from_block: Option<u64>,
+    to_block: Option<u64>,
+) -> Result<()> {
+    let _lock = hold_indexer_lock()?;
+    let chain = state.chain(chain_id)?;
+    let chain_id = chain.chain_id;
+    let client = chain.ownership_contract.client();
+    {
+        let conn = &mut state.db_pool.get().map_err(|e| {
+            eprintln!("Failed to get DB connection: {:?}", e);
+            eyre::eyre!("Failed to get DB connection: {}", e)
+        })?;
+        register_configured_deployments(conn, state)?;
+    }
+    let indexers: Vec<_> = all_indexers(state)?
+        .into_iter()
+        .filter(|indexer| indexer.chain_id() == chain_id)
+        .collect();
+
+    let from_block = match from_block {
+        Some(block) => U64::from(block),
+        None => indexers
+            .iter()
+            .filter_map(|indexer| indexer.deployment_block())
+            .min()
+            .ok_or_else(|| {
+                eyre::eyre!("Pass --from-block or set a deployment block for the deployments")
+            })?,
+    };
+    let to_block = match to_block {
+        Some(block) => U64::from(block),
+        None => {
+            state
+                .confirmation_policy
+                .confirmed_head(client.as_ref())
+                .await?
+        }
+    };
+    if from_block > to_block {
+        return Err(eyre::eyre!(
+            "Invalid block range: {} is after {}",
+            from_block,
+            to_block
+        ));
+    }
+
+    let conn = &mut state.db_pool.get().map_err(|e| {
+        eprintln!("Failed to get DB connection: {:?}", e);
+        eyre::eyre!("Failed to get DB connection: {}", e)
+    })?;
+
+    // Decode every log in the range; nothing is stored until the rebuild transaction
+    let mut backfill = Vec::new();
+    for indexer in &indexers {
+        let mut current_block = from_block;
+        while current_block <= to_block {
+            let (logs, chunk_end) = indexer
+                .fetch_logs(client.as_ref(), current_block, to_block)
+                .await?;
+            eprintln!(
+                "Backfilling {} {} events from block {} to {}",
+                logs.len(),
+                indexer.stream(),
+                current_block,
+                chunk_end
+            );
+
+            for log in &logs {
+                if let Some(decoded) = indexer.decode_log(client.as_ref(), log).await? {
+                    backfill.push((indexer, decoded));
+                }
+            }
+
+            current_block = chunk_end + 1;
+        }
+    }
+
+    // Projections are cleared for the whole chain, so every stored event is replayed, not
+    // only the backfilled range
+    let stored_events = load_chain_events(conn, chain_id, 0, i64::MAX)?;
+
+    // Clearing the chain would drop the projections of contracts nobody can replay
+    let unconfigured: BTreeSet<&str> = stored_events
+        .iter()
+        .map(|stored| stored.contract_address.as_str())
+        .filter(|address| {
+            !indexers
+                .iter()
+                .any(|indexer| indexer.contract_address() == *address)
+        })
+        .collect();
+    if !unconfigured.is_empty() {
+        return Err(eyre::eyre!(
+            "Stored events on chain {} come from contracts without a deployment: {}",
+            chain_id,
+            unconfigured.into_iter().collect::<Vec<_>>().join(", ")
+        ));
+    }
+
+    let stored_logs: HashSet<(&str, i64)> = stored_events
+        .iter()
+        .map(|stored| (stored.tnx_hash.as_str(), stored.log_index))
+        .collect();
+    backfill.retain(|(_, decoded)| {
+        !stored_logs.contains(&(decoded.meta.tnx_hash.as_str(), decoded.meta.log_index))
+    });
+
+    // Read each event's context up front, since the rebuild transaction cannot wait on the
+    // node
+    let mut replay = Vec::with_capacity(stored_events.len() + backfill.len());
+    for stored in &stored_events {
+        let indexer = indexers
+            .iter()
+            .find(|indexer| indexer.contract_address() == stored.contract_address)
+            .ok_or_else(|| eyre::eyre!("No indexer for {}", stored.contract_address))?;
+        replay.push(indexer.prepare_stored(client.as_ref(), stored).await?);
+    }
+    for (indexer, decoded) in &backfill {
+        replay.push(indexer.prepare_decoded(decoded).await?);
+    }
+    replay.sort_by_key(|(_, meta)| (meta.block_number, meta.log_index));
+
+    let replayed = replay.len();
+    rebuild(conn, chain_id, &indexers, &backfill, replay, from_block, to_block)?;
+
+    eprintln!(
+        "Reindexed {} events on chain {} after backfilling {} new events from blocks {} to {}",
+        replayed,
+        chain_id,
+        backfill.len(),
+        from_block,
+        to_block
+    );
+
+    Ok(())
+}
+
+// Stores the backfilled logs, replays every prepared event over cleared projections and
+// moves the checkpoints up, in one transaction
+fn rebuild(
+    conn: &mut PgConnection,
+    chain_id: u64,
+    indexers: &[ContractIndexer],
+    backfill: &[(&ContractIndexer, DecodedLog)],
+    replay: Vec<(Box<dyn PreparedEvent>, EventMeta)>,
+    from_block: U64,
+    to_block: U64,
+) -> Result<()> {
+    conn.transaction::<_, eyre::Error, _>(|conn| {
+        for (indexer, decoded) in backfill {
+            indexer.store_decoded(conn, decoded)?;
+        }
+
+        clear_projections(conn, chain_id)?;
+
+        for (event, meta) in replay {
+            event.project(conn, &meta)?;
+        }
+
+        // The event store now covers the backfilled range. A checkpoint already past it is
+        // kept along with the events stored there; one short of the range only moves up to
+        // it when no blocks are left unfetched in between.
+        for indexer in indexers {
+            let contract_address = indexer.contract_address();
+            let checkpoint =
+                load_checkpoint(conn, chain_id, &contract_address, indexer.stream())?;
+            let contiguous = match checkpoint {
+                Some(block) => block + 1 >= from_block,
+                None => indexer.deployment_block().is_some_and(|block| block >= from_block),
+            };
+            if contiguous && checkpoint.is_none_or(|block| block < to_block) {
+                save_checkpoint(conn, chain_id, &contract_address, indexer.stream(), to_block)?;
+            }
+        }
+        Ok(())
+    })
+}
+
+// Deletes one chain's projections; other chains' rows are left alone
+fn clear_projections(conn: &mut PgConnection, chain_id: u64) -> Result<()> {
+    for table in PROJECTION_TABLES {
+        diesel::sql_query(format!("DELETE FROM {} WHERE chain_id = $1", table))
+            .bind::<BigInt, _>(chain_id as i64)
+            .execute(conn)
+            .map_err(|e| {
+                eprintln!("Failed to clear {} for chain {}: {:?}", table, chain_id, e);
+                eyre::eyre!("Failed to clear projection tables: {}", e)
+            })?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indexer::checkpoint::OWNERSHIP_STREAM;
+    use crate::schema::{authenticity_settings, chain_events};
+    use crate::test_db::{test_connection, test_indexer};
+    use serde_json::json;
+
+    const CHAIN_ID: u64 = 31_337;
+
+    // Stands in for a handler's projection: records the event as an authenticity setting
+    struct TestProjection {
+        fails: bool,
+    }
+
+    impl PreparedEvent for TestProjection {
+        fn payload(&self) -> Result<serde_json::Value> {
+            Ok(json!({}))
+        }
+
+        fn project(self: Box<Self>, conn: &mut PgConnection, meta: &EventMeta) -> Result<()> {
+            if self.fails {
+                return Err(eyre::eyre!("Projection failed"));
+            }
+            insert_setting(conn, &meta.tnx_hash)
+        }
+    }
+
+    fn meta(block_number: i64, log_index: i64) -> EventMeta {
+        EventMeta {
+            tnx_hash: format!("0x{:064x}", block_number * 1_000 + log_index),
+            block_number,
+            block_hash: format!("0x{:064x}", block_number),
+            log_index,
+            block_timestamp: "2026-01-01T00:00:00Z".to_string(),
+            chain_id: CHAIN_ID as i64,
+        }
+    }
+
+    fn decoded(block_number: i64, log_index: i64) -> DecodedLog {
+        DecodedLog {
+            event_name: "ItemCreated".to_string(),
+            meta: meta(block_number, log_index),
+            payload: json!({}),
+        }
+    }
+
+    fn insert_setting(conn: &mut PgConnection, tnx_hash: &str) -> Result<()> {
+        diesel::insert_into(authenticity_settings::table)
+            .values((
+                authenticity_settings::authenticity_address.eq("0x01"),
+                authenticity_settings::tnx_hash.eq(tnx_hash),
+                authenticity_settings::created_at.eq("2026-01-01T00:00:00Z"),
+                authenticity_settings::chain_id.eq(CHAIN_ID as i64),
+            ))
+            .execute(conn)?;
+        Ok(())
+    }
+
+    fn settings(conn: &mut PgConnection) -> Vec<String> {
+        authenticity_settings::table
+            .filter(authenticity_settings::chain_id.eq(CHAIN_ID as i64))
+            .order(authenticity_settings::id.asc())
+            .select(authenticity_settings::tnx_hash)
+            .load(conn)
+            .unwrap()
+    }
+
+    fn stored_events(conn: &mut PgConnection) -> i64 {
+        chain_events::table
+            .filter(chain_events::chain_id.eq(CHAIN_ID as i64))
+            .count()
+            .get_result(conn)
+            .unwrap()
+    }
+
+    fn replay(events: &[(i64, i64, bool)]) -> Vec<(Box<dyn PreparedEvent>, EventMeta)> {
+        events
+            .iter()
+            .map(|&(block_number, log_index, fails)| {
+                let event: Box<dyn PreparedEvent> = Box::new(TestProjection { fails });
+                (event, meta(block_number, log_index))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn rebuild_stores_backfill_and_replaces_projections() {
+        let Some(mut conn) = test_connection() else {
+            return;
+        };
+        let conn = &mut conn;
+        let indexer = test_indexer(CHAIN_ID);
+        insert_setting(conn, "stale").unwrap();
+        let backfill = vec![(&indexer, decoded(110, 0)), (&indexer, decoded(120, 1))];
+
+        rebuild(
+            conn,
+            CHAIN_ID,
+            std::slice::from_ref(&indexer),
+            &backfill,
+            replay(&[(110, 0, false), (120, 1, false)]),
+            U64::from(100),
+            U64::from(200),
+        )
+        .unwrap();
+
+        assert_eq!(stored_events(conn), 2);
+        assert_eq!(settings(conn), vec![meta(110, 0).tnx_hash, meta(120, 1).tnx_hash]);
+        let checkpoint =
+            load_checkpoint(conn, CHAIN_ID, &indexer.contract_address(), OWNERSHIP_STREAM);
+        assert_eq!(checkpoint.unwrap(), Some(U64::from(200)));
+    }
+
+    #[test]
+    fn failed_rebuild_keeps_event_store_and_projections() {
+        let Some(mut conn) = test_connection() else {
+            return;
+        };
+        let conn = &mut conn;
+        let indexer = test_indexer(CHAIN_ID);
+        insert_setting(conn, "previous").unwrap();
+        let backfill = vec![(&indexer, decoded(110, 0)), (&indexer, decoded(120, 1))];
+
+        let result = rebuild(
+            conn,
+            CHAIN_ID,
+            std::slice::from_ref(&indexer),
+            &backfill,
+            replay(&[(110, 0, false), (120, 1, true)]),
+            U64::from(100),
+            U64::from(200),
+        );
+
+        assert!(result.is_err());
+        // The backfilled logs were not stored, so the live indexer still projects them
+        assert_eq!(stored_events(conn), 0);
+        assert_eq!(settings(conn), vec!["previous".to_string()]);
+        let checkpoint =
+            load_checkpoint(conn, CHAIN_ID, &indexer.contract_address(), OWNERSHIP_STREAM);
+        assert_eq!(checkpoint.unwrap(), None);
+    }
+}
@@ -0,0 +1,207 @@
+use crate::contract_models::IndexedBlock;
+use crate::indexer::checkpoint::save_checkpoint;
+use crate::indexer::dead_letter::delete_failed_events_after;
+use crate::indexer::event_store::delete_chain_events_after;
+use crate::schema::indexed_blocks;
+use diesel::prelude::*;
+use diesel::{PgConnection, RunQueryDsl};
+use ethers::prelude::{BlockNumber, Middleware, U64};
+use eyre::Result;
+use std::env;
+
+// Number of recent block hashes kept per stream to find the common ancestor after a reorg
+const REORG_WINDOW: i64 = 256;
+
+// How far behind the chain head the listeners stay before indexing a block
+#[derive(Clone, Copy, Debug)]
+pub enum ConfirmationPolicy {
+    Depth(u64),
+    Safe,
+    Finalized,
+}
+
+impl ConfirmationPolicy {
+    // INDEXER_CONFIRMATIONS is either a block count or one of the `safe`/`finalized` tags
+    pub fn from_env() -> Result<Self> {
+        let value = env::var("INDEXER_CONFIRMATIONS").unwrap_or_else(|_| "3".to_string());
+        match value.to_lowercase().as_str() {
+            "safe" => Ok(ConfirmationPolicy::Safe),
+            "finalized" => Ok(ConfirmationPolicy::Finalized),
+            depth => depth
+                .parse::<u64>()
+                .map(ConfirmationPolicy::Depth)
+                .map_err(|e| eyre::eyre!("Invalid INDEXER_CONFIRMATIONS {}: {}", value, e)),
+        }
+    }
+
+    pub async fn confirmed_head<M: Middleware>(&self, client: &M) -> Result<U64> {
+        let tag = match self {
+            ConfirmationPolicy::Depth(depth) => {
+                let latest_block = client.get_block_number().await.map_err(|e| {
+                    eprintln!("Failed to get latest block: {:?}", e.to_string());
+                    eyre::eyre!("Failed to get latest block: {}", e)
+                })?;
+                return Ok(latest_block.saturating_sub(U64::from(*depth)));
+            }
+            ConfirmationPolicy::Safe => BlockNumber::Safe,
+            ConfirmationPolicy::Finalized => BlockNumber::Finalized,
+        };
+
+        client
+            .get_block(tag)
+            .await
+            .map_err(|e| {
+                eprintln!("Failed to get {:?} block: {:?}", tag, e.to_string());
+                eyre::eyre!("Failed to get {:?} block: {}", tag, e)
+            })?
+            .and_then(|block| block.number)
+            .ok_or_else(|| eyre::eyre!("Node returned no {:?} block", tag))
+    }
+}
+
+pub fn record_block(
+    conn: &mut PgConnection,
+    chain_id: u64,
+    contract_address: &str,
+    stream: &str,
+    block_number: i64,
+    block_hash: &str,
+) -> Result<()> {
+    diesel::insert_into(indexed_blocks::table)
+        .values(IndexedBlock {
+            contract_address: contract_address.to_string(),
+            stream: stream.to_string(),
+            block_number,
+            block_hash: block_hash.to_string(),
+            chain_id: Some(chain_id as i64),
+        })
+        .on_conflict((
+            indexed_blocks::chain_id,
+            indexed_blocks::contract_address,
+            indexed_blocks::stream,
+            indexed_blocks::block_number,
+        ))
+        .do_update()
+        .set(indexed_blocks::block_hash.eq(block_hash))
+        .execute(conn)
+        .map_err(|e| {
+            eprintln!("Failed to record block {} for {}: {:?}", block_number, stream, e);
+            eyre::eyre!("Failed to record indexed block: {}", e)
+        })?;
+
+    diesel::delete(
+        indexed_blocks::table
+            .filter(indexed_blocks::chain_id.eq(chain_id as i64))
+            .filter(indexed_blocks::contract_address.eq(contract_address))
+            .filter(indexed_blocks::stream.eq(stream))
+            .filter(indexed_blocks::block_number.lt(block_number - REORG_WINDOW)),
+    )
+    .execute(conn)
+    .map_err(|e| eyre::eyre!("Failed to prune indexed blocks: {}", e))?;
+
+    Ok(())
+}
+
+// Fetches the canonical hash of `block_number` and records it for later reorg checks
+pub async fn record_canonical_block<M: Middleware>(
+    conn: &mut PgConnection,
+    client: &M,
+    chain_id: u64,
+    contract_address: &str,
+    stream: &str,
+    block_number: U64,
+) -> Result<()> {
+    let block_hash = client
+        .get_block(block_number)
+        .await
+        .map_err(|e| eyre::eyre!("Failed to get block {}: {}", block_number, e))?
+        .and_then(|block| block.hash)
+        .ok_or_else(|| eyre::eyre!("Block {} not found", block_number))?;
+
+    record_block(
+        conn,
+        chain_id,
+        contract_address,
+        stream,
+        block_number.as_u64() as i64,
+        &format!("0x{}", hex::encode(block_hash)),
+    )
+}
+
+// Compares the recorded block hashes with the chain, newest first. Returns the last block
+// both agree on when the most recent recorded block has been orphaned.
+pub async fn detect_reorg<M: Middleware>(
+    conn: &mut PgConnection,
+    client: &M,
+    chain_id: u64,
+    contract_address: &str,
+    stream: &str,
+) -> Result<Option<U64>> {
+    let recorded: Vec<IndexedBlock> = indexed_blocks::table
+        .filter(indexed_blocks::chain_id.eq(chain_id as i64))
+        .filter(indexed_blocks::contract_address.eq(contract_address))
+        .filter(indexed_blocks::stream.eq(stream))
+        .order(indexed_blocks::block_number.desc())
+        .limit(REORG_WINDOW)
+        .select(IndexedBlock::as_select())
+        .load(conn)
+        .map_err(|e| eyre::eyre!("Failed to load indexed blocks: {}", e))?;
+
+    for (position, block) in recorded.iter().enumerate() {
+        let canonical_hash = client
+            .get_block(U64::from(block.block_number as u64))
+            .await
+            .map_err(|e| eyre::eyre!("Failed to get block {}: {}", block.block_number, e))?
+            .and_then(|b| b.hash)
+            .map(|hash| format!("0x{}", hex::encode(hash)));
+
+        if canonical_hash.as_deref() == Some(block.block_hash.as_str()) {
+            if position == 0 {
+                return Ok(None);
+            }
+            eprintln!(
+                "Reorg detected on {} ({}): common ancestor at block {}",
+                stream, contract_address, block.block_number
+            );
+            return Ok(Some(U64::from(block.block_number as u64)));
+        }
+    }
+
+    match recorded.last() {
+        Some(oldest) => {
+            let ancestor = (oldest.block_number - 1).max(0);
+            eprintln!(
+                "Reorg on {} ({}) is deeper than the {} recorded blocks, rewinding to {}",
+                stream, contract_address, REORG_WINDOW, ancestor
+            );
+            Ok(Some(U64::from(ancestor as u64)))
+        }
+        None => Ok(None),
+    }
+}
+
+// Drops the orphaned block hashes and stored events and moves the checkpoint back so the
+// canonical blocks are replayed. Projections must be undone by the caller within the same
+// transaction.
+pub fn rewind_stream(
+    conn: &mut PgConnection,
+    chain_id: u64,
+    contract_address: &str,
+    stream: &str,
+    ancestor: U64,
+) -> Result<()> {
+    diesel::delete(
+        indexed_blocks::table
+            .filter(indexed_blocks::chain_id.eq(chain_id as i64))
+            .filter(indexed_blocks::contract_address.eq(contract_address))
+            .filter(indexed_blocks::stream.eq(stream))
+            .filter(indexed_blocks::block_number.gt(ancestor.as_u64() as i64)),
+    )
+    .execute(conn)
+    .map_err(|e| eyre::eyre!("Failed to delete orphaned blocks: {}", e))?;
+
+    delete_chain_events_after(conn, chain_id, contract_address, ancestor.as_u64() as i64)?;
+    delete_failed_events_after(conn, chain_id, contract_address, ancestor.as_u64() as i64)?;
+
+    save_checkpoint(conn, chain_id, contract_address, stream, ancestor)
+}
@@ -0,0 +1,24 @@
+use std::env;
+
+// Pub/sub transport the listeners subscribe to for new heads. Transactions are still sent
+// over the HTTP provider.
+#[derive(Clone, Debug)]
+pub enum StreamTransport {
+    Ws(String),
+    Ipc(String),
+}
+
+impl StreamTransport {
+    // The variable (INDEXER_STREAM_URL, prefixed per chain) holds a ws:// or wss:// URL, or
+    // the path of a node's IPC socket. The listeners poll over HTTP when it is not set.
+    pub fn from_env(key: &str) -> Option<Self> {
+        let url = env::var(key)
+            .ok()
+            .filter(|url| !url.is_empty())?;
+        if url.starts_with("ws://") || url.starts_with("wss://") {
+            Some(StreamTransport::Ws(url))
+        } else {
+            Some(StreamTransport::Ipc(url))
+        }
+    }
+}
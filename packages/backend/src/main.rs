@@ -1,4 +1,13 @@
-use crate::config::server::server;
+use crate::admin::{run_admin, AdminCommand};
+use crate::config::app_state::AppState;
+use crate::config::server::{default_addr, index, migrate, serve, server};
+use crate::indexer::log_source::record_logs;
+use crate::indexer::reconcile::reconcile;
+use crate::indexer::reindex::reindex;
+use clap::{Parser, Subcommand};
+use dotenv::dotenv;
+use std::net::SocketAddr;
+use std::sync::Arc;
 
 mod config;
 mod models;
@@ -10,10 +19,110 @@ mod ownership;
 mod contract_models;
 mod sync;
 mod certificate;
+mod indexer;
+mod deployments;
+mod admin;
+mod auth;
+mod rate_limit;
+mod relayer;
+#[cfg(test)]
+mod test_db;
+
+// Without a subcommand, migrations, the indexer and the API all run in one process
+#[derive(Parser)]
+#[command(name = "backend")]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    #[command(about = "Serve the HTTP API and relay its transactions without indexing")]
+    Serve {
+        #[arg(long, default_value_t = default_addr())]
+        addr: SocketAddr,
+    },
+    #[command(about = "Run the indexer, serving only health and indexer status")]
+    Index {
+        #[arg(long, default_value_t = SocketAddr::from(([127, 0, 0, 1], 8081)))]
+        addr: SocketAddr,
+    },
+    #[command(about = "Apply pending database migrations and exit")]
+    Migrate,
+    #[command(about = "Backfill contract logs in a block range and rebuild a chain's projections from its stored events")]
+    Reindex {
+        // Defaults to the default chain
+        #[arg(long)]
+        chain_id: Option<u64>,
+        // Defaults to the earliest configured deployment block
+        #[arg(long)]
+        from_block: Option<u64>,
+        // Defaults to the latest confirmed block
+        #[arg(long)]
+        to_block: Option<u64>,
+    },
+    #[command(about = "Compare indexed items against the ownership contract and store a report")]
+    Reconcile {
+        // Defaults to the default chain
+        #[arg(long)]
+        chain_id: Option<u64>,
+        // Overwrite mismatching rows with the on-chain values
+        #[arg(long)]
+        repair: bool,
+    },
+    #[command(about = "Write a chain's contract logs in a block range to a JSONL file")]
+    // The file is replayed by pointing INDEXER_LOG_FILE at it
+    Record {
+        // Defaults to the default chain
+        #[arg(long)]
+        chain_id: Option<u64>,
+        #[arg(long)]
+        from_block: u64,
+        #[arg(long)]
+        to_block: u64,
+        #[arg(long)]
+        output: String,
+    },
+    #[command(about = "Inspect and repair indexer state")]
+    Admin {
+        #[command(subcommand)]
+        command: AdminCommand,
+    },
+}
 
 #[tokio::main]
 async fn main() {
-    
-    
-    server().await.expect("Error!");
+    let cli = Cli::parse();
+
+    match cli.command {
+        None => server().await.expect("Error!"),
+        Some(Command::Serve { addr }) => serve(addr).await.expect("Error!"),
+        Some(Command::Index { addr }) => index(addr).await.expect("Error!"),
+        Some(Command::Migrate) => migrate().await.expect("Migration failed"),
+        Some(Command::Reindex { chain_id, from_block, to_block }) => {
+            let state = cli_state().await;
+            reindex(&state, chain_id, from_block, to_block).await.expect("Reindex failed");
+        }
+        Some(Command::Reconcile { chain_id, repair }) => {
+            let state = cli_state().await;
+            let run = reconcile(&state, chain_id, repair).await.expect("Reconciliation failed");
+            println!("{}", serde_json::to_string_pretty(&run).expect("Error!"));
+        }
+        Some(Command::Record { chain_id, from_block, to_block, output }) => {
+            let state = cli_state().await;
+            record_logs(&state, chain_id, from_block, to_block, &output)
+                .await
+                .expect("Recording failed");
+        }
+        Some(Command::Admin { command }) => {
+            let state = cli_state().await;
+            run_admin(&state, command).await.expect("Admin command failed");
+        }
+    }
+}
+
+async fn cli_state() -> Arc<AppState> {
+    dotenv().ok();
+    Arc::new(AppState::init_app_state().await.expect("Error!"))
 }
@@ -22,6 +22,42 @@ pub struct Certificate {
     pub owner: Address,
     pub metadata_hash: [u8; 32],
     pub metadata: Vec<String>,
+    // Domain to sign or verify under; the env-configured domain when unset
+    #[serde(skip)]
+    pub domain: Option<EIP712Domain>,
+}
+
+impl Certificate {
+    pub fn with_domain(mut self, domain: EIP712Domain) -> Self {
+        self.domain = Some(domain);
+        self
+    }
+}
+
+// Domain from the env, used until the indexer has recorded one from the contract
+pub fn env_domain() -> EIP712Domain {
+    let factory_address: Address = env::var("CONTRACT_ADDRESS")
+        .expect("CONTRACT ADDRESS NOT SET")
+        .parse()
+        .expect("Invalid contract address");
+
+    let chain_id = env::var("CHAIN_ID").unwrap().parse::<u64>().unwrap();
+
+    chain_domain(chain_id, factory_address)
+}
+
+// Domain of the authenticity contract deployed on `chain_id`. The name and version are the
+// same on every chain, so a certificate only verifies on the chain it was signed for.
+pub fn chain_domain(chain_id: u64, verifying_contract: Address) -> EIP712Domain {
+    EIP712Domain {
+        // name: Some("CertificateAuth".to_string()),
+        name: Some(env::var("SIGNING_DOMAIN").unwrap()),
+        // version: Some("1".to_string()),
+        version: Some(env::var("SIGNATURE_VERSION").unwrap()),
+        chain_id: Some(U256::from(chain_id)),
+        verifying_contract: Some(verifying_contract),
+        salt: None,
+    }
 }
 
 // EIP-712 implementation
@@ -49,22 +85,7 @@ impl Eip712 for Certificate {
         Ok(keccak256(&encoded))
     }
     fn domain(&self) -> Result<EIP712Domain, Self::Error> {
-        let factory_address: Address = env::var("CONTRACT_ADDRESS")
-            .expect("CONTRACT ADDRESS NOT SET")
-            .parse()
-            .expect("Invalid contract address");
-
-        let chain_id = env::var("CHAIN_ID").unwrap().parse::<usize>().unwrap();
-
-        Ok(EIP712Domain {
-            // name: Some("CertificateAuth".to_string()),
-            name: Some(env::var("SIGNING_DOMAIN").unwrap()),
-            // version: Some("1".to_string()),
-            version: Some(env::var("SIGNATURE_VERSION").unwrap()),
-            chain_id: Some(U256::from(chain_id).into()),
-            verifying_contract: Some(factory_address),
-            salt: None,
-        })
+        Ok(self.domain.clone().unwrap_or_else(env_domain))
     }
 
     fn type_hash() -> Result<[u8; 32], Self::Error> {
@@ -154,6 +175,7 @@ impl TryFrom<SignedCertificate> for Certificate {
                 .map_err(|_| anyhow::anyhow!("Invalid address format"))?,
             metadata_hash: to_meta_hash(&dto.metadata),
             metadata: dto.metadata,
+            domain: None,
         })
     }
 }
@@ -237,13 +259,8 @@ impl TryFrom<CertificateData> for Certificate {
                 .map_err(|_| anyhow::anyhow!("Invalid address format"))?,
             metadata_hash: to_meta_hash(&dto.metadata),
             metadata: dto.metadata,
+            domain: None,
         })
     }
 }
 
-#[derive(Clone, Serialize, Deserialize, Debug, ToSchema)]
-pub struct RegInput {
-    pub name: String,
-    pub address: String,
-}
-
@@ -1,4 +1,4 @@
 pub(crate) mod certificate_model;
-pub(crate) mod emitted_events;
+pub(crate) mod ownership_intent_model;
 pub(crate) mod router_path;
 // pub mod auth;
@@ -0,0 +1,117 @@
+use ethabi::ethereum_types::{Address, U256};
+use ethers::types::transaction::eip712::{EIP712Domain, Eip712, Eip712Error};
+use ethers::utils::keccak256;
+use ethers::utils::hex::ToHexExt;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+const OWNERSHIP_INTENT_TYPE: &str = "OwnershipIntent(string action,string itemId,string ownershipCode,address tempOwner,uint256 nonce,uint256 deadline)";
+
+pub const ACTION_GENERATE: &str = "generate";
+pub const ACTION_REVOKE: &str = "revoke";
+pub const ACTION_CLAIM: &str = "claim";
+
+// What the acting wallet signs before an ownership code is generated, revoked or claimed.
+// Fields an action does not use are signed empty (strings) or as the zero address.
+#[derive(Clone, Debug)]
+pub struct OwnershipIntent {
+    pub action: String,
+    pub item_id: String,
+    pub ownership_code: String,
+    pub temp_owner: Address,
+    pub nonce: U256,
+    pub deadline: U256,
+    pub domain: EIP712Domain,
+}
+
+impl OwnershipIntent {
+    // EIP-712 types in the shape wallets expect for eth_signTypedData_v4
+    pub fn types() -> serde_json::Value {
+        serde_json::json!({
+            "OwnershipIntent": [
+                { "name": "action", "type": "string" },
+                { "name": "itemId", "type": "string" },
+                { "name": "ownershipCode", "type": "string" },
+                { "name": "tempOwner", "type": "address" },
+                { "name": "nonce", "type": "uint256" },
+                { "name": "deadline", "type": "uint256" }
+            ]
+        })
+    }
+
+    pub fn value(&self) -> serde_json::Value {
+        serde_json::json!({
+            "action": self.action,
+            "itemId": self.item_id,
+            "ownershipCode": self.ownership_code,
+            "tempOwner": ToHexExt::encode_hex_upper_with_prefix(&self.temp_owner),
+            "nonce": self.nonce.to_string(),
+            "deadline": self.deadline.to_string(),
+        })
+    }
+}
+
+// EIP-712 implementation, encoded the same way as `Certificate`
+impl Eip712 for OwnershipIntent {
+    type Error = Eip712Error;
+
+    fn domain_separator(&self) -> Result<[u8; 32], Self::Error> {
+        let domain = self.domain()?;
+        let type_hash = keccak256(
+            "EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)",
+        );
+
+        let encoded = ethers::abi::encode(&[
+            ethers::abi::Token::FixedBytes(type_hash.to_vec()),
+            ethers::abi::Token::FixedBytes(keccak256(domain.name.unwrap_or_default().as_bytes()).to_vec()),
+            ethers::abi::Token::FixedBytes(keccak256(domain.version.unwrap_or_default().as_bytes()).to_vec()),
+            ethers::abi::Token::Uint(domain.chain_id.unwrap_or_default()),
+            ethers::abi::Token::Address(domain.verifying_contract.unwrap_or_default()),
+        ]);
+        Ok(keccak256(&encoded))
+    }
+
+    fn domain(&self) -> Result<EIP712Domain, Self::Error> {
+        Ok(self.domain.clone())
+    }
+
+    fn type_hash() -> Result<[u8; 32], Self::Error> {
+        Ok(keccak256(OWNERSHIP_INTENT_TYPE))
+    }
+
+    fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
+        let encoded = ethers::abi::encode(&[
+            ethers::abi::Token::FixedBytes(Self::type_hash()?.to_vec()),
+            ethers::abi::Token::FixedBytes(keccak256(self.action.as_bytes()).to_vec()),
+            ethers::abi::Token::FixedBytes(keccak256(self.item_id.as_bytes()).to_vec()),
+            ethers::abi::Token::FixedBytes(keccak256(self.ownership_code.as_bytes()).to_vec()),
+            ethers::abi::Token::Address(self.temp_owner),
+            ethers::abi::Token::Uint(self.nonce),
+            ethers::abi::Token::Uint(self.deadline),
+        ]);
+
+        Ok(keccak256(&encoded))
+    }
+
+    fn encode_eip712(&self) -> Result<[u8; 32], Self::Error> {
+        let mut bytes = Vec::with_capacity(2 + 32 + 32);
+        bytes.extend_from_slice(b"\x19\x01");
+        bytes.extend_from_slice(&self.domain_separator()?);
+        bytes.extend_from_slice(&self.struct_hash()?);
+
+        Ok(keccak256(&bytes))
+    }
+}
+
+// Nonce, deadline and signature sent with a signed ownership request
+#[derive(Clone, Serialize, Deserialize, Debug, ToSchema)]
+pub struct IntentSignature {
+    // Decimal uint256, unique per signer and chain
+    #[schema(example = "1")]
+    pub nonce: String,
+    // Unix timestamp after which the signature is rejected
+    #[schema(example = 1792195200)]
+    pub deadline: u64,
+    #[schema(example = "0xad71ff20241e4a798f4416f71411b4343ed8f939c45375d4f78125a84fdfd1fd0ca5f8d0b037cddb02bf2989b2c1b0a9e0845260867160172e07cb0cc452148b1c")]
+    pub signature: String,
+}
@@ -16,6 +16,10 @@ use crate::schema::items;
 #[derive(Deserialize, ToSchema)]
 pub struct BatchItemsPayload {
     item_ids: Vec<String>,
+    // Only return items indexed from this deployment
+    deployment_id: Option<i32>,
+    // Only return items indexed from this chain; every chain when omitted
+    chain_id: Option<i64>,
 }
 
 // Response structs
@@ -30,6 +34,7 @@ pub struct ItemResponse {
     pub manufacturer: String,
     pub metadata: Vec<Option<String>>,
     pub created_at: String,
+    pub chain_id: Option<i64>,
 }
 
 #[derive(Serialize, ToSchema)]
@@ -66,9 +71,18 @@ pub async fn batch_items(
     }).unwrap();
 
     // Query items by item_id
-    let item_list: Vec<ItemResponse> = items
+    let mut item_query = items
         .filter(item_id.eq_any(&payload.item_ids))
         .select(ItemResponse::as_select())
+        .into_boxed();
+    if let Some(deployment) = payload.deployment_id {
+        item_query = item_query.filter(deployment_id.eq(deployment));
+    }
+    if let Some(chain) = payload.chain_id {
+        item_query = item_query.filter(chain_id.eq(chain));
+    }
+
+    let item_list: Vec<ItemResponse> = item_query
         .load(conn)
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
@@ -1,5 +1,5 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     response::IntoResponse,
     Json as AxumJson,
@@ -10,7 +10,7 @@ use serde_json::json;
 use std::sync::Arc;
 use utoipa::ToSchema;
 use crate::config::app_state::AppState;
-use crate::contract_models::Item;
+use crate::contract_models::{DeploymentQuery, Item};
 use crate::schema::items;
 
 
@@ -24,7 +24,9 @@ struct ErrorResponse {
     get,
     path = "/api/item/{item_id}",
     params(
-        ("item_id" = String, Path, description = "The unique ID of the item", example = "item123")
+        ("item_id" = String, Path, description = "The unique ID of the item", example = "item123"),
+        ("deployment_id" = Option<i32>, Query, description = "Only return rows indexed from this deployment", example = 1),
+        ("chain_id" = Option<i64>, Query, description = "Only return rows indexed from this chain; every chain when omitted", example = 84532)
     ),
     responses(
         (status = 200, description = "Item retrieved successfully", body = Item, example = json!({
@@ -37,7 +39,9 @@ struct ErrorResponse {
             "manufacturer": "Acme Corp",
             "metadata": ["color: blue", "size: medium"],
             "created_at": "2023-09-01T00:00:00Z",
-            "tnx_hash": "0xabcdef1234567890abcdef1234567890abcdef1234567890abcdef1234567890"
+            "tnx_hash": "0xabcdef1234567890abcdef1234567890abcdef1234567890abcdef1234567890",
+            "deployment_id": 1,
+            "chain_id": 84532
         })),
         (status = 404, description = "Item not found", body = ErrorResponse, example = json!({"error": "Item not found"})),
         (status = 500, description = "Internal server error (e.g., database failure)", body = ErrorResponse, example = json!({"error": "Internal server error: Failed to query database"}))
@@ -47,8 +51,9 @@ struct ErrorResponse {
 pub async fn get_item(
     State(state): State<Arc<AppState>>,
     Path(item_id): Path<String>,
+    Query(query): Query<DeploymentQuery>,
 ) -> impl IntoResponse {
-    match get_item_internal(&state, &item_id).await {
+    match get_item_internal(&state, &item_id, &query).await {
         Ok(response) => (
             StatusCode::OK,
             AxumJson(response),
@@ -67,7 +72,11 @@ pub async fn get_item(
     }
 }
 
-async fn get_item_internal(state: &Arc<AppState>, item_id: &str) -> eyre::Result<Item> {
+async fn get_item_internal(
+    state: &Arc<AppState>,
+    item_id: &str,
+    query: &DeploymentQuery,
+) -> eyre::Result<Item> {
     // Validate item_id
     if item_id.is_empty() {
         return Err(eyre::eyre!("Item ID cannot be empty"));
@@ -80,9 +89,18 @@ async fn get_item_internal(state: &Arc<AppState>, item_id: &str) -> eyre::Result
         .map_err(|e| eyre::eyre!("Failed to get database connection: {}", e))?;
 
     // Query the items table
-    let item = items::table
+    let mut item_query = items::table
         .filter(items::item_id.eq(item_id))
         .select(Item::as_select())
+        .into_boxed();
+    if let Some(deployment_id) = query.deployment_id {
+        item_query = item_query.filter(items::deployment_id.eq(deployment_id));
+    }
+    if let Some(chain_id) = query.chain_id {
+        item_query = item_query.filter(items::chain_id.eq(chain_id));
+    }
+
+    let item = item_query
         .first(conn)
         .optional()
         .map_err(|e| eyre::eyre!("Failed to query database: {}", e))?
@@ -17,13 +17,19 @@ pub struct ItemsResponse {
 pub struct ItemQuery {
     #[schema(example = "0x1234567890abcdef1234567890abcdef12345678")]
     pub owner: String,
+    #[schema(example = 1)]
+    pub deployment_id: Option<i32>,
+    #[schema(example = 84532)]
+    pub chain_id: Option<i64>,
 }
 
 #[utoipa::path(
     get,
     path = "/api/items/owner",
     params(
-        ("owner" = String, Query, description = "Owner's blockchain address", example = "0x1234567890abcdef1234567890abcdef12345678")
+        ("owner" = String, Query, description = "Owner's blockchain address", example = "0x1234567890abcdef1234567890abcdef12345678"),
+        ("deployment_id" = Option<i32>, Query, description = "Only return rows indexed from this deployment", example = 1),
+        ("chain_id" = Option<i64>, Query, description = "Only return rows indexed from this chain; every chain when omitted", example = 84532)
     ),
     responses(
         (status = 200, description = "Items found for the owner", body = ItemsResponse, example = json!({
@@ -38,7 +44,9 @@ pub struct ItemQuery {
                     "manufacturer": "SAMSUNG",
                     "metadata": ["color: gold", null],
                     "created_at": "2025-08-25T19:47:00Z",
-                    "tnx_hash": "0xabcdef1234567890abcdef1234567890abcdef1234567890abcdef1234567890"
+                    "tnx_hash": "0xabcdef1234567890abcdef1234567890abcdef1234567890abcdef1234567890",
+                    "deployment_id": 1,
+                    "chain_id": 84532
                 }
             ]
         })),
@@ -79,9 +87,18 @@ async fn get_items_by_owner_internal(
         eyre::eyre!("Failed to get DB connection: {}", e)
     })?;
 
-    let items = items::table
+    let mut items_query = items::table
         .select(Item::as_select())
         .filter(items::owner.eq(query.owner.clone()))
+        .into_boxed();
+    if let Some(deployment_id) = query.deployment_id {
+        items_query = items_query.filter(items::deployment_id.eq(deployment_id));
+    }
+    if let Some(chain_id) = query.chain_id {
+        items_query = items_query.filter(items::chain_id.eq(chain_id));
+    }
+
+    let items = items_query
         .load::<Item>(conn)
         .map_err(|e| {
             eprintln!("Failed to fetch items for owner {}: {:?}", query.owner, e);
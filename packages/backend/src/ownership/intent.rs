@@ -0,0 +1,117 @@
+use crate::config::chains::ChainConfig;
+use crate::contract_models::NewOwnershipIntent;
+use crate::models::certificate_model::chain_domain;
+use crate::models::ownership_intent_model::{IntentSignature, OwnershipIntent};
+use crate::schema::ownership_intents;
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+use diesel::{PgConnection, RunQueryDsl};
+use ethers::core::utils::to_checksum;
+use ethers::types::transaction::eip712::Eip712;
+use ethers::types::{Address, Signature, U256};
+use eyre::Result;
+
+// Intents are signed under the chain's ownership contract, with the configured signing
+// name and version
+pub fn intent_for_chain(
+    chain: &ChainConfig,
+    action: &str,
+    item_id: &str,
+    ownership_code: &str,
+    temp_owner: Address,
+    nonce: &str,
+    deadline: u64,
+) -> Result<OwnershipIntent> {
+    let nonce = U256::from_dec_str(nonce).map_err(|_| eyre::eyre!("Invalid intent nonce"))?;
+    // Stored as BIGINT
+    if i64::try_from(deadline).is_err() {
+        return Err(eyre::eyre!("Invalid intent deadline"));
+    }
+
+    Ok(OwnershipIntent {
+        action: action.to_string(),
+        item_id: item_id.to_string(),
+        ownership_code: ownership_code.to_string(),
+        temp_owner,
+        nonce,
+        deadline: U256::from(deadline),
+        domain: chain_domain(chain.chain_id, chain.ownership_contract.address()),
+    })
+}
+
+// Checks the signature was made by `signer` over `intent` before its deadline, then stores
+// it. The (chain, signer, nonce) key rejects a replayed signature. Returns the intent's id.
+pub fn verify_and_record_intent(
+    conn: &mut PgConnection,
+    chain_id: u64,
+    intent: &OwnershipIntent,
+    signed: &IntentSignature,
+    signer: &str,
+) -> Result<i64> {
+    if signed.deadline <= Utc::now().timestamp().max(0) as u64 {
+        return Err(eyre::eyre!("Intent deadline has passed"));
+    }
+
+    let digest = intent
+        .encode_eip712()
+        .map_err(|e| eyre::eyre!("Failed to encode intent: {}", e))?;
+    let signature: Signature = signed
+        .signature
+        .parse()
+        .map_err(|_| eyre::eyre!("Invalid intent signature format"))?;
+    let recovered = signature
+        .recover(digest)
+        .map_err(|_| eyre::eyre!("Invalid intent signature"))?;
+    if !to_checksum(&recovered, None).eq_ignore_ascii_case(signer) {
+        return Err(eyre::eyre!("Intent signature does not match the caller"));
+    }
+
+    diesel::insert_into(ownership_intents::table)
+        .values(NewOwnershipIntent {
+            chain_id: chain_id as i64,
+            action: intent.action.clone(),
+            signer: to_checksum(&recovered, None),
+            item_id: intent.item_id.clone(),
+            ownership_code: intent.ownership_code.clone(),
+            temp_owner: to_checksum(&intent.temp_owner, None),
+            nonce: intent.nonce.to_string(),
+            deadline: signed.deadline as i64,
+            digest: format!("0x{}", hex::encode(digest)),
+            signature: format!("0x{}", signature),
+            created_at: Utc::now().to_rfc3339(),
+        })
+        .returning(ownership_intents::id)
+        .get_result(conn)
+        .map_err(|e| match e {
+            DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => {
+                eyre::eyre!("Intent nonce already used")
+            }
+            e => {
+                eprintln!("Failed to store ownership intent: {:?}", e);
+                eyre::eyre!("Failed to store ownership intent: {}", e)
+            }
+        })
+}
+
+// Links a claim intent to the transaction it was sent in, so the indexer can attach it to
+// the resulting ownership_claims row
+pub fn set_intent_transaction(conn: &mut PgConnection, intent_id: i64, tnx_hash: &str) -> Result<()> {
+    diesel::update(ownership_intents::table.find(intent_id))
+        .set(ownership_intents::tnx_hash.eq(tnx_hash))
+        .execute(conn)
+        .map_err(|e| {
+            eprintln!("Failed to link intent {} to {}: {:?}", intent_id, tnx_hash, e);
+            eyre::eyre!("Failed to link intent to transaction: {}", e)
+        })?;
+    Ok(())
+}
+
+pub fn intent_for_transaction(conn: &mut PgConnection, tnx_hash: &str) -> Result<Option<i64>> {
+    ownership_intents::table
+        .filter(ownership_intents::tnx_hash.eq(tnx_hash))
+        .select(ownership_intents::id)
+        .first(conn)
+        .optional()
+        .map_err(|e| eyre::eyre!("Failed to load intent for transaction: {}", e))
+}
@@ -10,6 +10,10 @@ use crate::config::app_state::AppState;
 pub struct UserExistsQuery {
     #[schema(example = "john_doe")]
     username: String,
+    #[schema(example = 1)]
+    deployment_id: Option<i32>,
+    #[schema(example = 84532)]
+    chain_id: Option<i64>,
 }
 
 #[derive(Serialize, ToSchema)]
@@ -21,7 +25,9 @@ pub struct UserExistsResponse {
     get,
     path = "/api/user/exists",
     params(
-        ("username" = String, Query, description = "User's username", example = "john_doe")
+        ("username" = String, Query, description = "User's username", example = "john_doe"),
+        ("deployment_id" = Option<i32>, Query, description = "Only return rows indexed from this deployment", example = 1),
+        ("chain_id" = Option<i64>, Query, description = "Only return rows indexed from this chain; every chain when omitted", example = 84532)
     ),
     responses(
         (status = 200, description = "Check if user exists", body = UserExistsResponse, example = json!({
@@ -45,8 +51,17 @@ pub async fn user_exists(
     }).unwrap();
 
     // Check if user exists
-    let exists: bool = users_info::table
+    let mut exists_query = users_info::table
         .filter(users_info::username.eq(&query.username))
+        .into_boxed();
+    if let Some(deployment_id) = query.deployment_id {
+        exists_query = exists_query.filter(users_info::deployment_id.eq(deployment_id));
+    }
+    if let Some(chain_id) = query.chain_id {
+        exists_query = exists_query.filter(users_info::chain_id.eq(chain_id));
+    }
+
+    let exists: bool = exists_query
         .select(diesel::dsl::count_star())
         .first::<i64>(conn)
         .map(|count| count > 0)
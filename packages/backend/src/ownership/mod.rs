@@ -8,4 +8,4 @@ pub mod get_transfer_code;
 pub mod revoke_ownership_code;
 pub mod get_item;
 pub mod batch_items;
-pub mod check_before_claim;
\ No newline at end of file
+pub mod check_before_claim;pub mod intent;
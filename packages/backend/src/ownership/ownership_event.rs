@@ -1,16 +1,23 @@
 use crate::config::app_state::AppState;
+use crate::deployments::{deployment_id_for_contract, DeploymentContracts};
 use crate::contract_models::{
     NewAuthenticitySetting, NewContract, NewItem, NewOwnershipClaim, UserInfo,
 };
+use crate::indexer::checkpoint::OWNERSHIP_STREAM;
+use crate::indexer::contract_indexer::ContractIndexer;
+use crate::indexer::event_meta::EventMeta;
+use crate::indexer::handler::EventHandler;
+use crate::indexer::log_source::LogSource;
 use crate::ownership::ownership_abi::{
     AuthenticitySetFilter, ItemCreatedFilter, OwnershipCreatedFilter, OwnershipTransferredFilter,
     UserRegisteredFilter,
 };
-use crate::ownership::ownership_abi::{TrueOwnership, TrueOwnershipEvents};
+use crate::ownership::ownership_abi::{Item, TrueOwnership};
 use crate::schema::{
     authenticity_settings, contracts, items, ownership_claims, ownership_codes, users_info,
 };
-use chrono::Utc;
+use crate::ownership::intent::intent_for_transaction;
+use async_trait::async_trait;
 use diesel::prelude::*;
 use diesel::{PgConnection, RunQueryDsl};
 use ecdsa::SigningKey;
@@ -20,218 +27,325 @@ use ethers::prelude::*;
 use eyre::Result;
 use std::sync::Arc;
 
-pub async fn listen_for_ownership_events(state: &Arc<AppState>) -> Result<()> {
-    let contract = state.ownership_contract.clone();
-    let client = contract.client();
+pub async fn listen_for_ownership_events(
+    state: &Arc<AppState>,
+    deployment: &DeploymentContracts,
+) -> Result<()> {
+    let client = deployment.ownership_contract.client();
+    ownership_indexer(state, deployment)
+        .run(state, client.as_ref())
+        .await
+}
 
-    // Fetch historical events from the last 1,000 blocks in chunks
-    let latest_block = client.get_block_number().await.map_err(|e| {
-        eprintln!("Failed to get latest block: {:?}", e.to_string());
-        eyre::eyre!("Failed to get latest block: {}", e)
-    })?;
-    let from_block = latest_block.saturating_sub(U64::from(20));
-    let chunk_size = U64::from(4);
+pub fn ownership_indexer(state: &AppState, deployment: &DeploymentContracts) -> ContractIndexer {
+    let contract = deployment.ownership_contract.clone();
+    let deployment_id = deployment.id;
+    ContractIndexer::new(
+        OWNERSHIP_STREAM,
+        deployment.chain_id,
+        contract.address(),
+        deployment.ownership_deployment_block,
+        LogSource::for_chain(state, deployment.chain_id),
+        rollback_ownership_projections,
+    )
+    .with_handler(OwnershipCreatedHandler { deployment_id })
+    .with_handler(UserRegisteredHandler { deployment_id })
+    .with_handler(ItemCreatedHandler {
+        contract,
+        deployment_id,
+    })
+    .with_handler(OwnershipTransferredHandler { deployment_id })
+    .with_handler(AuthenticitySetHandler { deployment_id })
+}
 
-    // Process historical events in chunks
-    let mut current_block = from_block;
-    while current_block < latest_block {
-        let to_block = (current_block + chunk_size).min(latest_block);
-        eprintln!(
-            "Querying Ownership historical events from block {} to {} (range: {})",
-            current_block,
-            to_block,
-            to_block - current_block + 1
-        );
+struct OwnershipCreatedHandler {
+    deployment_id: i32,
+}
 
-        // Event filters for the chunk
-        let ownership_created_filter = contract
-            .event::<OwnershipCreatedFilter>()
-            .from_block(current_block)
-            .to_block(to_block);
-        let user_registered_filter = contract
-            .event::<UserRegisteredFilter>()
-            .from_block(current_block)
-            .to_block(to_block);
-        let item_created_filter = contract
-            .event::<ItemCreatedFilter>()
-            .from_block(current_block)
-            .to_block(to_block);
-        let ownership_transferred_filter = contract
-            .event::<OwnershipTransferredFilter>()
-            .from_block(current_block)
-            .to_block(to_block);
-        let authenticity_set_filter = contract
-            .event::<AuthenticitySetFilter>()
-            .from_block(current_block)
-            .to_block(to_block);
-
-        // Fetch historical events with metadata
-        let ownership_created_logs =
-            ownership_created_filter
-                .query_with_meta()
-                .await
-                .map_err(|e| {
-                    eprintln!(
-                        "Failed to query OwnershipCreated events for blocks {} to {}: {:?}",
-                        current_block,
-                        to_block,
-                        e.to_string()
-                    );
-                    eyre::eyre!("Failed to query OwnershipCreated events: {}", e)
-                })?;
-        let user_registered_logs = user_registered_filter
-            .query_with_meta()
-            .await
-            .map_err(|e| {
-                eprintln!(
-                    "Failed to query UserRegistered events for blocks {} to {}: {:?}",
-                    current_block,
-                    to_block,
-                    e.to_string()
-                );
-                eyre::eyre!("Failed to query UserRegistered events: {}", e)
-            })?;
-        let item_created_logs = item_created_filter.query_with_meta().await.map_err(|e| {
+#[async_trait]
+impl EventHandler for OwnershipCreatedHandler {
+    type Event = OwnershipCreatedFilter;
+    type Context = ();
+
+    async fn prepare(&self, _event: &Self::Event, _meta: &EventMeta) -> Result<()> {
+        Ok(())
+    }
+
+    fn project(
+        &self,
+        conn: &mut PgConnection,
+        event: &Self::Event,
+        _context: (),
+        meta: &EventMeta,
+    ) -> Result<()> {
+        process_ownership_created_event(event, self.deployment_id, conn, meta)
+    }
+}
+
+struct UserRegisteredHandler {
+    deployment_id: i32,
+}
+
+#[async_trait]
+impl EventHandler for UserRegisteredHandler {
+    type Event = UserRegisteredFilter;
+    type Context = ();
+
+    async fn prepare(&self, _event: &Self::Event, _meta: &EventMeta) -> Result<()> {
+        Ok(())
+    }
+
+    fn project(
+        &self,
+        conn: &mut PgConnection,
+        event: &Self::Event,
+        _context: (),
+        meta: &EventMeta,
+    ) -> Result<()> {
+        process_user_registered_event(event, self.deployment_id, conn, meta)
+    }
+}
+
+// Item details are not part of the event, so they are read from the contract
+struct ItemCreatedHandler {
+    contract: TrueOwnership<SignerMiddleware<Provider<Http>, Wallet<SigningKey<Secp256k1>>>>,
+    deployment_id: i32,
+}
+
+#[async_trait]
+impl EventHandler for ItemCreatedHandler {
+    type Event = ItemCreatedFilter;
+    type Context = Item;
+
+    async fn prepare(&self, event: &Self::Event, meta: &EventMeta) -> Result<Item> {
+        fetch_item(&self.contract, &event.item_id, meta.block_number).await
+    }
+
+    fn ordering_key(&self, event: &Self::Event) -> Option<String> {
+        Some(format!("item:{}", event.item_id))
+    }
+
+    fn project(
+        &self,
+        conn: &mut PgConnection,
+        event: &Self::Event,
+        item: Item,
+        meta: &EventMeta,
+    ) -> Result<()> {
+        process_item_created_event(event, item, self.deployment_id, conn, meta)
+    }
+}
+
+struct OwnershipTransferredHandler {
+    deployment_id: i32,
+}
+
+#[async_trait]
+impl EventHandler for OwnershipTransferredHandler {
+    type Event = OwnershipTransferredFilter;
+    type Context = ();
+
+    async fn prepare(&self, _event: &Self::Event, _meta: &EventMeta) -> Result<()> {
+        Ok(())
+    }
+
+    fn ordering_key(&self, event: &Self::Event) -> Option<String> {
+        Some(format!("item:{}", event.item_id))
+    }
+
+    fn project(
+        &self,
+        conn: &mut PgConnection,
+        event: &Self::Event,
+        _context: (),
+        meta: &EventMeta,
+    ) -> Result<()> {
+        process_ownership_transferred_event(event, self.deployment_id, conn, meta)
+    }
+}
+
+struct AuthenticitySetHandler {
+    deployment_id: i32,
+}
+
+#[async_trait]
+impl EventHandler for AuthenticitySetHandler {
+    type Event = AuthenticitySetFilter;
+    type Context = ();
+
+    async fn prepare(&self, _event: &Self::Event, _meta: &EventMeta) -> Result<()> {
+        Ok(())
+    }
+
+    fn project(
+        &self,
+        conn: &mut PgConnection,
+        event: &Self::Event,
+        _context: (),
+        meta: &EventMeta,
+    ) -> Result<()> {
+        process_authenticity_set_event(event, self.deployment_id, conn, meta)
+    }
+}
+
+// Error fragments nodes return when they no longer keep the state of an old block
+const PRUNED_STATE_ERRORS: [&str; 5] = [
+    "missing trie node",
+    "header not found",
+    "state not available",
+    "historical state",
+    "pruned",
+];
+
+// Reads the item as it was at the block that created it, so replays see the same details.
+// That needs an archive RPC once the block is older than the node's state history; a full
+// node falls back to the latest state. Item details do not change after creation, but the
+// owner read then is the current one, and the later transfers replayed after it leave it as
+// it is.
+async fn fetch_item(
+    contract: &TrueOwnership<SignerMiddleware<Provider<Http>, Wallet<SigningKey<Secp256k1>>>>,
+    item_id: &str,
+    block_number: i64,
+) -> Result<Item> {
+    let at_block = contract
+        .get_item(item_id.to_string())
+        .block(U64::from(block_number as u64))
+        .call()
+        .await;
+    let result = match at_block {
+        Err(e) if is_pruned_state_error(&e.to_string()) => {
             eprintln!(
-                "Failed to query ItemCreated events for blocks {} to {}: {:?}",
-                current_block,
-                to_block,
-                e.to_string()
+                "State at block {} is not available for item {}, reading the latest state: {}",
+                block_number, item_id, e
             );
-            eyre::eyre!("Failed to query ItemCreated events: {}", e)
+            contract.get_item(item_id.to_string()).call().await
+        }
+        result => result,
+    };
+
+    result.map_err(|e| {
+        eprintln!(
+            "Failed to call get_item for item_id {}: {:?}",
+            item_id,
+            e.to_string()
+        );
+        eyre::eyre!("Failed to call get_item: {}", e)
+    })
+}
+
+fn is_pruned_state_error(message: &str) -> bool {
+    let message = message.to_lowercase();
+    PRUNED_STATE_ERRORS
+        .iter()
+        .any(|fragment| message.contains(fragment))
+}
+
+// Undoes rows indexed from orphaned blocks so the canonical ones can be replayed. Transfers
+// are reverted newest first to restore the previous owner; ownership codes deleted by an
+// orphaned transfer are not restored.
+fn rollback_ownership_projections(
+    conn: &mut PgConnection,
+    chain_id: u64,
+    contract_address: &str,
+    ancestor: U64,
+) -> Result<()> {
+    let ancestor = ancestor.as_u64() as i64;
+    let deployment_id = deployment_id_for_contract(conn, chain_id, contract_address)?;
+
+    let orphaned_claims: Vec<(i32, String, String, String)> = ownership_claims::table
+        .filter(ownership_claims::deployment_id.eq(deployment_id))
+        .filter(ownership_claims::block_number.gt(ancestor))
+        .order((
+            ownership_claims::block_number.desc(),
+            ownership_claims::log_index.desc(),
+        ))
+        .select((
+            ownership_claims::id,
+            ownership_claims::item_id,
+            ownership_claims::old_owner,
+            ownership_claims::new_owner,
+        ))
+        .load(conn)
+        .map_err(|e| {
+            eprintln!("Failed to load orphaned ownership claims: {:?}", e);
+            eyre::eyre!("Failed to load orphaned ownership claims: {}", e)
         })?;
-        let ownership_transferred_logs = ownership_transferred_filter
-            .query_with_meta()
-            .await
-            .map_err(|e| {
-                eprintln!(
-                    "Failed to query OwnershipTransferred events for blocks {} to {}: {:?}",
-                    current_block,
-                    to_block,
-                    e.to_string()
-                );
-                eyre::eyre!("Failed to query OwnershipTransferred events: {}", e)
-            })?;
-        let authenticity_set_logs =
-            authenticity_set_filter
-                .query_with_meta()
-                .await
-                .map_err(|e| {
-                    eprintln!(
-                        "Failed to query AuthenticitySet events for blocks {} to {}: {:?}",
-                        current_block,
-                        to_block,
-                        e.to_string()
-                    );
-                    eyre::eyre!("Failed to query AuthenticitySet events: {}", e)
-                })?;
-
-        // Process historical events
-        let conn = &mut state.db_pool.get().map_err(|e| {
-            eprintln!("Failed to get DB connection: {:?}", e);
-            eyre::eyre!("Failed to get DB connection: {}", e)
+
+    for (id, item_id, old_owner, new_owner) in &orphaned_claims {
+        diesel::update(
+            items::table
+                .filter(items::deployment_id.eq(deployment_id))
+                .filter(items::item_id.eq(item_id))
+                .filter(items::owner.eq(new_owner)),
+        )
+        .set(items::owner.eq(old_owner))
+        .execute(conn)
+        .map_err(|e| {
+            eprintln!("Failed to restore owner for item {}: {:?}", item_id, e);
+            eyre::eyre!("Failed to restore item owner: {}", e)
         })?;
-        for (event, meta) in ownership_created_logs {
-            let txn_hash = Some(format!("0x{}", hex::encode(meta.transaction_hash)));
-            process_ownership_created_event(&event, conn, txn_hash)?;
-        }
-        for (event, meta) in user_registered_logs {
-            let txn_hash = Some(format!("0x{}", hex::encode(meta.transaction_hash)));
-            process_user_registered_event(&event, conn, txn_hash, &contract).await?;
-        }
-        for (event, meta) in item_created_logs {
-            let txn_hash = Some(format!("0x{}", hex::encode(meta.transaction_hash)));
-            process_item_created_event(&event, conn, txn_hash, &contract).await?;
-        }
-        for (event, meta) in ownership_transferred_logs {
-            let txn_hash = Some(format!("0x{}", hex::encode(meta.transaction_hash)));
-            process_ownership_transferred_event(&event, conn, txn_hash)?;
-        }
-        for (event, meta) in authenticity_set_logs {
-            let txn_hash = Some(format!("0x{}", hex::encode(meta.transaction_hash)));
-            process_authenticity_set_event(&event, conn, txn_hash)?;
-        }
 
-        current_block = to_block + 1;
+        diesel::delete(ownership_claims::table.filter(ownership_claims::id.eq(id)))
+            .execute(conn)
+            .map_err(|e| eyre::eyre!("Failed to delete ownership claim: {}", e))?;
     }
 
-    // Stream future events
+    let removed_items = diesel::delete(
+        items::table
+            .filter(items::deployment_id.eq(deployment_id))
+            .filter(items::block_number.gt(ancestor)),
+    )
+    .execute(conn)
+    .map_err(|e| eyre::eyre!("Failed to roll back items: {}", e))?;
+
+    let removed_users = diesel::delete(
+        users_info::table
+            .filter(users_info::deployment_id.eq(deployment_id))
+            .filter(users_info::block_number.gt(ancestor)),
+    )
+    .execute(conn)
+    .map_err(|e| eyre::eyre!("Failed to roll back users: {}", e))?;
+
+    diesel::delete(
+        authenticity_settings::table
+            .filter(authenticity_settings::deployment_id.eq(deployment_id))
+            .filter(authenticity_settings::block_number.gt(ancestor)),
+    )
+    .execute(conn)
+    .map_err(|e| eyre::eyre!("Failed to roll back authenticity settings: {}", e))?;
+
+    diesel::delete(
+        contracts::table
+            .filter(contracts::deployment_id.eq(deployment_id))
+            .filter(contracts::contract_address.eq(contract_address))
+            .filter(contracts::block_number.gt(ancestor)),
+    )
+    .execute(conn)
+    .map_err(|e| eyre::eyre!("Failed to roll back contracts: {}", e))?;
+
     eprintln!(
-        "Starting Ownership event stream from block {}",
-        latest_block + 1
+        "Rolled back {} ownership claim(s), {} item(s) and {} user(s) after block {}",
+        orphaned_claims.len(),
+        removed_items,
+        removed_users,
+        ancestor
     );
-    let events = contract.events().from_block(latest_block + 1);
-    let mut stream = events.stream_with_meta().await.map_err(|e| {
-        eprintln!("Failed to create event stream: {:?}", e.to_string());
-        eyre::eyre!("Failed to create event stream: {}", e)
-    })?;
 
-    loop {
-        match stream.next().await {
-            Some(Ok((TrueOwnershipEvents::OwnershipCreatedFilter(event), meta))) => {
-                let txn_hash = Some(format!("0x{}", hex::encode(meta.transaction_hash)));
-                let conn = &mut state.db_pool.get().map_err(|e| {
-                    eprintln!("Failed to get DB connection: {:?}", e);
-                    eyre::eyre!("Failed to get DB connection: {}", e)
-                })?;
-                process_ownership_created_event(&event, conn, txn_hash)?;
-            }
-            Some(Ok((TrueOwnershipEvents::UserRegisteredFilter(event), meta))) => {
-                let txn_hash = Some(format!("0x{}", hex::encode(meta.transaction_hash)));
-                let conn = &mut state.db_pool.get().map_err(|e| {
-                    eprintln!("Failed to get DB connection: {:?}", e);
-                    eyre::eyre!("Failed to get DB connection: {}", e)
-                })?;
-                process_user_registered_event(&event, conn, txn_hash, &contract).await?;
-            }
-            Some(Ok((TrueOwnershipEvents::ItemCreatedFilter(event), meta))) => {
-                let txn_hash = Some(format!("0x{}", hex::encode(meta.transaction_hash)));
-                let conn = &mut state.db_pool.get().map_err(|e| {
-                    eprintln!("Failed to get DB connection: {:?}", e);
-                    eyre::eyre!("Failed to get DB connection: {}", e)
-                })?;
-                process_item_created_event(&event, conn, txn_hash, &contract).await?;
-            }
-            Some(Ok((TrueOwnershipEvents::OwnershipTransferredFilter(event), meta))) => {
-                let txn_hash = Some(format!("0x{}", hex::encode(meta.transaction_hash)));
-                let conn = &mut state.db_pool.get().map_err(|e| {
-                    eprintln!("Failed to get DB connection: {:?}", e);
-                    eyre::eyre!("Failed to get DB connection: {}", e)
-                })?;
-                process_ownership_transferred_event(&event, conn, txn_hash)?;
-            }
-            Some(Ok((TrueOwnershipEvents::AuthenticitySetFilter(event), meta))) => {
-                let txn_hash = Some(format!("0x{}", hex::encode(meta.transaction_hash)));
-                let conn = &mut state.db_pool.get().map_err(|e| {
-                    eprintln!("Failed to get DB connection: {:?}", e);
-                    eyre::eyre!("Failed to get DB connection: {}", e)
-                })?;
-                process_authenticity_set_event(&event, conn, txn_hash)?;
-            }
-            Some(Err(e)) => {
-                eprintln!("Event stream error: {:?}", e.to_string());
-                tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
-                continue;
-            }
-            None => {
-                eprintln!("Event stream ended unexpectedly");
-                tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
-                continue;
-            }
-        }
-    }
+    Ok(())
 }
 
 fn process_ownership_created_event(
     event: &OwnershipCreatedFilter,
+    deployment_id: i32,
     conn: &mut PgConnection,
-    txn_hash: Option<String>,
+    meta: &EventMeta,
 ) -> Result<()> {
     let contract_address = to_checksum(&event.contract_address, None);
     let owner = to_checksum(&event.owner, None);
 
     // Check if contract exists
     let exists: bool = contracts::table
+        .filter(contracts::deployment_id.eq(deployment_id))
         .filter(contracts::contract_address.eq(&contract_address))
         .select(diesel::dsl::count_star())
         .first::<i64>(conn)
@@ -246,8 +360,8 @@ fn process_ownership_created_event(
 
     if exists {
         eprintln!(
-            "Skipping duplicate contract created for {} (tx: {:?})",
-            contract_address, txn_hash
+            "Skipping duplicate contract created for {} (tx: {})",
+            contract_address, meta.tnx_hash
         );
         return Ok(());
     }
@@ -257,8 +371,13 @@ fn process_ownership_created_event(
         .values(NewContract {
             contract_address,
             owner,
-            tnx_hash: txn_hash.unwrap(),
-            created_at: Utc::now().to_rfc3339(),
+            tnx_hash: meta.tnx_hash.clone(),
+            created_at: meta.block_timestamp.clone(),
+            block_number: meta.block_number,
+            block_hash: meta.block_hash.clone(),
+            log_index: meta.log_index,
+            deployment_id,
+            chain_id: meta.chain_id,
         })
         .execute(conn)
         .map_err(|e| {
@@ -269,16 +388,17 @@ fn process_ownership_created_event(
     Ok(())
 }
 
-async fn process_user_registered_event(
+fn process_user_registered_event(
     event: &UserRegisteredFilter,
+    deployment_id: i32,
     conn: &mut PgConnection,
-    txn_hash: Option<String>,
-    ownership_contract: &TrueOwnership<SignerMiddleware<Provider<Http>, Wallet<SigningKey<Secp256k1>>>>,
+    meta: &EventMeta,
 ) -> Result<()> {
     let user_address = to_checksum(&event.user_address, None);
 
     // Check if user exists
     let exists: bool = users_info::table
+        .filter(users_info::deployment_id.eq(deployment_id))
         .filter(users_info::user_address.eq(&user_address))
         .select(diesel::dsl::count_star())
         .first::<i64>(conn)
@@ -290,8 +410,8 @@ async fn process_user_registered_event(
 
     if exists {
         eprintln!(
-            "Skipping duplicate user registration for {} (tx: {:?})",
-            user_address, txn_hash
+            "Skipping duplicate user registration for {} (tx: {})",
+            user_address, meta.tnx_hash
         );
         return Ok(());
     }
@@ -302,8 +422,13 @@ async fn process_user_registered_event(
             user_address,
             username: event.username.to_string(),
             is_registered: true,
-            created_at: Utc::now().to_rfc3339(),
-            tnx_hash: txn_hash.unwrap(),
+            created_at: meta.block_timestamp.clone(),
+            tnx_hash: meta.tnx_hash.clone(),
+            block_number: Some(meta.block_number),
+            block_hash: Some(meta.block_hash.clone()),
+            log_index: Some(meta.log_index),
+            deployment_id: Some(deployment_id),
+            chain_id: Some(meta.chain_id),
         })
         .execute(conn)
         .map_err(|e| {
@@ -314,31 +439,19 @@ async fn process_user_registered_event(
     Ok(())
 }
 
-async fn process_item_created_event(
+fn process_item_created_event(
     event: &ItemCreatedFilter,
+    item: Item,
+    deployment_id: i32,
     conn: &mut PgConnection,
-    txn_hash: Option<String>,
-    contract: &TrueOwnership<SignerMiddleware<Provider<Http>, Wallet<SigningKey<Secp256k1>>>>,
+    meta: &EventMeta,
 ) -> Result<()> {
     let item_id = event.item_id.to_string();
     eprintln!("Item ID: {:?}", item_id);
 
-    // Fetch item details from the contract
-    let item = contract
-        .get_item(item_id.clone())
-        .call()
-        .await
-        .map_err(|e| {
-            eprintln!(
-                "Failed to call get_item for item_id {}: {:?}",
-                item_id,
-                e.to_string()
-            );
-            eyre::eyre!("Failed to call get_item: {}", e)
-        })?;
-
     // Check if item exists
     let exists: bool = items::table
+        .filter(items::deployment_id.eq(deployment_id))
         .filter(items::item_id.eq(&item_id))
         .select(diesel::dsl::count_star())
         .first::<i64>(conn)
@@ -350,8 +463,8 @@ async fn process_item_created_event(
 
     if exists {
         eprintln!(
-            "Skipping duplicate item creation for {} (tx: {:?})",
-            item_id, txn_hash
+            "Skipping duplicate item creation for {} (tx: {})",
+            item_id, meta.tnx_hash
         );
         return Ok(());
     }
@@ -370,8 +483,13 @@ async fn process_item_created_event(
             owner: to_checksum(&item.owner, None),
             manufacturer: item.manufacturer,
             metadata: item.metadata,
-            created_at: Utc::now().to_rfc3339(),
-            tnx_hash: txn_hash.unwrap(),
+            created_at: meta.block_timestamp.clone(),
+            tnx_hash: meta.tnx_hash.clone(),
+            block_number: meta.block_number,
+            block_hash: meta.block_hash.clone(),
+            log_index: meta.log_index,
+            deployment_id,
+            chain_id: meta.chain_id,
         })
         .execute(conn)
         .map_err(|e| {
@@ -384,40 +502,23 @@ async fn process_item_created_event(
 
 pub fn process_ownership_transferred_event(
     event: &OwnershipTransferredFilter,
+    deployment_id: i32,
     conn: &mut PgConnection,
-    txn_hash: Option<String>,
+    meta: &EventMeta,
 ) -> Result<()> {
     let item_id = event.item_id.clone();
     let new_owner = to_checksum(&event.new_onwer, None);
     let old_owner = to_checksum(&event.old_onwer, None);
-    let txn_hash = txn_hash.ok_or_else(|| eyre::eyre!("Transaction hash is required"))?;
+    let txn_hash = meta.tnx_hash.clone();
 
-    // Check if ownership transfer exists (e.g., by txn_hash)
-    let exists: bool = ownership_claims::table
-        .filter(ownership_claims::tnx_hash.eq(&txn_hash))
-        .select(diesel::dsl::count_star())
-        .first::<i64>(conn)
-        .map(|count| count > 0)
-        .map_err(|e| {
-            eprintln!(
-                "Failed to check existing ownership transfer for item {}: {:?}",
-                item_id, e
-            );
-            eyre::eyre!("Failed to check existing ownership transfer: {}", e)
-        })?;
-
-    if exists {
-        eprintln!(
-            "Skipping duplicate ownership transfer for {} (tx: {})",
-            item_id, txn_hash
-        );
-        return Ok(());
-    }
+    // Duplicate deliveries are filtered by chain_events before this runs, so several
+    // transfers from one transaction are each recorded
 
     // Start a transaction to ensure atomicity
     conn.transaction::<_, eyre::Error, _>(|conn| {
         // Check the current owner in the items table
         let current_owner: Option<String> = items::table
+            .filter(items::deployment_id.eq(deployment_id))
             .filter(items::item_id.eq(&item_id))
             .select(items::owner)
             .first::<String>(conn)
@@ -439,8 +540,12 @@ pub fn process_ownership_transferred_event(
                 );
             } else {
                 // Update the owner in the items table
-                diesel::update(items::table.filter(items::item_id.eq(&item_id)))
-                    .set(items::owner.eq(&new_owner))
+                diesel::update(
+                    items::table
+                        .filter(items::deployment_id.eq(deployment_id))
+                        .filter(items::item_id.eq(&item_id)),
+                )
+                .set(items::owner.eq(&new_owner))
                     .execute(conn)
                     .map_err(|e| {
                         eprintln!(
@@ -459,16 +564,20 @@ pub fn process_ownership_transferred_event(
         }
 
         // Fetch and delete associated ownership codes for the item_id
-        let deleted_rows =
-            diesel::delete(ownership_codes::table.filter(ownership_codes::item_id.eq(&item_id)))
-                .execute(conn)
-                .map_err(|e| {
-                    eprintln!(
-                        "Failed to delete ownership codes for item {}: {:?}",
-                        item_id, e
-                    );
-                    eyre::eyre!("Failed to delete ownership codes: {}", e)
-                })?;
+        let deleted_rows = diesel::delete(
+            ownership_codes::table
+                .filter(ownership_codes::chain_id.eq(meta.chain_id))
+                .filter(ownership_codes::deployment_id.eq(deployment_id))
+                .filter(ownership_codes::item_id.eq(&item_id)),
+        )
+        .execute(conn)
+        .map_err(|e| {
+            eprintln!(
+                "Failed to delete ownership codes for item {}: {:?}",
+                item_id, e
+            );
+            eyre::eyre!("Failed to delete ownership codes: {}", e)
+        })?;
 
         if deleted_rows > 0 {
             eprintln!(
@@ -477,6 +586,9 @@ pub fn process_ownership_transferred_event(
             );
         }
 
+        // Claims sent through this backend carry the intent signed for them
+        let intent_id = intent_for_transaction(conn, &txn_hash)?;
+
         // Insert the ownership transfer
         diesel::insert_into(ownership_claims::table)
             .values(NewOwnershipClaim {
@@ -484,7 +596,13 @@ pub fn process_ownership_transferred_event(
                 new_owner,
                 old_owner,
                 tnx_hash: txn_hash,
-                created_at: Utc::now().to_rfc3339(),
+                created_at: meta.block_timestamp.clone(),
+                block_number: meta.block_number,
+                block_hash: meta.block_hash.clone(),
+                log_index: meta.log_index,
+                deployment_id,
+                chain_id: meta.chain_id,
+                intent_id,
             })
             .execute(conn)
             .map_err(|e| {
@@ -501,13 +619,15 @@ pub fn process_ownership_transferred_event(
 
 fn process_authenticity_set_event(
     event: &AuthenticitySetFilter,
+    deployment_id: i32,
     conn: &mut PgConnection,
-    txn_hash: Option<String>,
+    meta: &EventMeta,
 ) -> Result<()> {
     let authenticity_address = to_checksum(&event.authenticity_address, None);
 
     // Check if authenticity setting exists
     let exists: bool = authenticity_settings::table
+        .filter(authenticity_settings::deployment_id.eq(deployment_id))
         .filter(authenticity_settings::authenticity_address.eq(&authenticity_address))
         .select(diesel::dsl::count_star())
         .first::<i64>(conn)
@@ -522,8 +642,8 @@ fn process_authenticity_set_event(
 
     if exists {
         eprintln!(
-            "Skipping duplicate authenticity setting for {} (tx: {:?})",
-            authenticity_address, txn_hash
+            "Skipping duplicate authenticity setting for {} (tx: {})",
+            authenticity_address, meta.tnx_hash
         );
         return Ok(());
     }
@@ -532,8 +652,13 @@ fn process_authenticity_set_event(
     diesel::insert_into(authenticity_settings::table)
         .values(NewAuthenticitySetting {
             authenticity_address,
-            tnx_hash: txn_hash.unwrap(),
-            created_at: Utc::now().to_rfc3339(),
+            tnx_hash: meta.tnx_hash.clone(),
+            created_at: meta.block_timestamp.clone(),
+            block_number: meta.block_number,
+            block_hash: meta.block_hash.clone(),
+            log_index: meta.log_index,
+            deployment_id,
+            chain_id: meta.chain_id,
         })
         .execute(conn)
         .map_err(|e| {
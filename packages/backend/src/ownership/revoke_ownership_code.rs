@@ -1,4 +1,7 @@
+use crate::auth::session::AuthenticatedUser;
 use crate::config::app_state::AppState;
+use crate::models::ownership_intent_model::{IntentSignature, ACTION_REVOKE};
+use crate::ownership::intent::{intent_for_chain, verify_and_record_intent};
 use crate::schema::ownership_codes;
 use axum::{
     extract::{Query, State},
@@ -8,6 +11,7 @@ use axum::{
 };
 use diesel::prelude::*;
 use diesel::RunQueryDsl;
+use ethers::types::Address;
 use eyre::Result;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
@@ -19,8 +23,6 @@ use utoipa::OpenApi;
 pub struct OwnershipQuery {
     #[schema(example = "0xabcdef1234567890abcdef1234567890abcdef1234567890abcdef1234567890")]
     pub ownership_code: String,
-    #[schema(example = "0x1234567890abcdef1234567890abcdef12345678")]
-    pub caller: String,
 }
 
 // Define the response struct for successful ownership retrieval
@@ -40,7 +42,9 @@ struct ErrorResponse {
     path = "/api/revoke_ownership_code",
     params(
         ("ownership_code" = String, Query, description = "Ownership code to verify", example = "0xabcdef1234567890abcdef1234567890abcdef1234567890abcdef1234567890"),
-        ("caller" = String, Query, description = "Address of the caller", example = "0x1234567890abcdef1234567890abcdef12345678")
+        ("nonce" = String, Query, description = "Intent nonce, a decimal uint256 not used before by the caller on this chain", example = "1"),
+        ("deadline" = u64, Query, description = "Unix time after which the intent signature is rejected", example = 1792195200),
+        ("signature" = String, Query, description = "EIP-712 signature of the OwnershipIntent by the session's wallet", example = "0xad71ff20241e4a798f4416f71411b4343ed8f939c45375d4f78125a84fdfd1fd0ca5f8d0b037cddb02bf2989b2c1b0a9e0845260867160172e07cb0cc452148b1c")
     ),
     responses(
         (status = 200, description = "Ownership verified and deleted successfully", body = OwnershipResponse, example = json!({
@@ -50,16 +54,20 @@ struct ErrorResponse {
             "created_at": "2025-08-26T15:54:00+00:00"
         })),
         (status = 400, description = "Invalid input (e.g., caller is not the item owner)", body = ErrorResponse, example = json!({"error": "Caller is not the item owner"})),
+        (status = 401, description = "Missing or invalid session token, or an intent signature that is expired, reused or not from the caller", body = ErrorResponse, example = json!({"error": "Intent signature does not match the caller"})),
         (status = 404, description = "Ownership code not found", body = ErrorResponse, example = json!({"error": "Ownership code not found"})),
         (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({"error": "Internal server error: Database error"}))
     ),
+    security(("session_token" = [])),
     tag = "Ownership"
 )]
 pub async fn revoke_ownership_code(
     Query(query): Query<OwnershipQuery>,
+    Query(intent): Query<IntentSignature>,
     State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
 ) -> impl IntoResponse {
-    match verify_and_delete_ownership_internal(&state, &query).await {
+    match verify_and_delete_ownership_internal(&state, &query, &intent, &user).await {
         Ok(response) => (StatusCode::OK, Json(response)).into_response(),
         Err(e) => {
             eprintln!(
@@ -69,6 +77,8 @@ pub async fn revoke_ownership_code(
             let (status, message) = match e.to_string().as_str() {
                 "Caller is not the item owner" => (StatusCode::BAD_REQUEST, e.to_string()),
                 "Ownership code not found" => (StatusCode::NOT_FOUND, e.to_string()),
+                s if s.starts_with("Invalid intent") => (StatusCode::BAD_REQUEST, e.to_string()),
+                s if s.starts_with("Intent") => (StatusCode::UNAUTHORIZED, e.to_string()),
                 _ => (
                     StatusCode::INTERNAL_SERVER_ERROR,
                     format!("Internal server error: {}", e),
@@ -83,50 +93,70 @@ pub async fn revoke_ownership_code(
 async fn verify_and_delete_ownership_internal(
     state: &Arc<AppState>,
     query: &OwnershipQuery,
+    signed: &IntentSignature,
+    user: &AuthenticatedUser,
 ) -> Result<OwnershipResponse> {
+    let caller = user.address.as_str();
+    let chain = state.chain(Some(user.chain_id))?;
+    let intent = intent_for_chain(
+        chain,
+        ACTION_REVOKE,
+        "",
+        &query.ownership_code,
+        Address::zero(),
+        &signed.nonce,
+        signed.deadline,
+    )?;
+
     let conn = &mut state.db_pool.get().map_err(|e| {
         eprintln!("Failed to get DB connection: {:?}", e);
         eyre::eyre!("Failed to get DB connection: {}", e)
     })?;
-    eprintln!("Caller: {:?}", query.caller);
+    eprintln!("Caller: {:?}", caller);
 
-    // Delete the record from ownership_codes where ownership_code and item_owner match
-    let deleted_rows = diesel::delete(
-        ownership_codes::table
-            .filter(ownership_codes::ownership_code.eq(&query.ownership_code))
-            .filter(ownership_codes::item_owner.eq(&query.caller))
-    )
-        .execute(conn)
-        .map_err(|e| {
-            eprintln!(
-                "Failed to delete ownership code {}: {:?}",
-                query.ownership_code, e
-            );
-            eyre::eyre!("Failed to delete ownership code: {}", e)
-        })?;
+    // The intent is only kept if the code is actually revoked
+    conn.transaction::<_, eyre::Error, _>(|conn| {
+        verify_and_record_intent(conn, user.chain_id, &intent, signed, caller)?;
 
-    // Check if any rows were deleted
-    if deleted_rows == 0 {
-        // Determine if the ownership_code exists but the caller is not the item_owner
-        let exists: bool = ownership_codes::table
-            .filter(ownership_codes::ownership_code.eq(&query.ownership_code))
-            .select(diesel::dsl::count_star())
-            .first::<i64>(conn)
-            .map(|count| count > 0)
+        // Delete the record from ownership_codes where ownership_code and item_owner match
+        let deleted_rows = diesel::delete(
+            ownership_codes::table
+                .filter(ownership_codes::ownership_code.eq(&query.ownership_code))
+                .filter(ownership_codes::item_owner.eq(caller))
+        )
+            .execute(conn)
             .map_err(|e| {
                 eprintln!(
-                    "Failed to check existence of ownership code {}: {:?}",
+                    "Failed to delete ownership code {}: {:?}",
                     query.ownership_code, e
                 );
-                eyre::eyre!("Database query error: {}", e)
+                eyre::eyre!("Failed to delete ownership code: {}", e)
             })?;
 
-        return if exists {
-            Err(eyre::eyre!("Caller is not the item owner"))
-        } else {
-            Err(eyre::eyre!("Ownership code not found"))
+        // Check if any rows were deleted
+        if deleted_rows == 0 {
+            // Determine if the ownership_code exists but the caller is not the item_owner
+            let exists: bool = ownership_codes::table
+                .filter(ownership_codes::ownership_code.eq(&query.ownership_code))
+                .select(diesel::dsl::count_star())
+                .first::<i64>(conn)
+                .map(|count| count > 0)
+                .map_err(|e| {
+                    eprintln!(
+                        "Failed to check existence of ownership code {}: {:?}",
+                        query.ownership_code, e
+                    );
+                    eyre::eyre!("Database query error: {}", e)
+                })?;
+
+            return if exists {
+                Err(eyre::eyre!("Caller is not the item owner"))
+            } else {
+                Err(eyre::eyre!("Ownership code not found"))
+            };
         }
-    }
+        Ok(())
+    })?;
 
     // Return the ownership details
     Ok(OwnershipResponse {
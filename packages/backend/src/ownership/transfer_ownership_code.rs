@@ -1,5 +1,9 @@
+use crate::auth::session::AuthenticatedUser;
 use crate::config::app_state::AppState;
 use crate::contract_models::OwnershipCode;
+use crate::deployments::active_deployment_id;
+use crate::models::ownership_intent_model::{IntentSignature, ACTION_GENERATE};
+use crate::ownership::intent::{intent_for_chain, verify_and_record_intent};
 use crate::schema::{items, ownership_codes, users_info};
 use axum::{
     extract::{Query, State},
@@ -9,6 +13,7 @@ use axum::{
 };
 use chrono::Utc;
 use diesel::prelude::*;
+use ethers::types::Address;
 use eyre::Result;
 use serde::{Deserialize, Serialize};
 use sha3::{Digest, Keccak256};
@@ -23,7 +28,6 @@ pub struct OwnershipCodeResponse {
 #[derive(Deserialize, ToSchema)]
 pub struct GenerateOwnershipCodeQuery {
     item_id: String,
-    caller: String,
     temp_owner: String,
 }
 
@@ -32,24 +36,30 @@ pub struct GenerateOwnershipCodeQuery {
     path = "/api/transfer_ownership",
     params(
         ("item_id" = String, Query, description = "ID of the item", example = "item_001"),
-        ("caller" = String, Query, description = "Address of the caller", example = "0x1234567890abcdef1234567890abcdef12345678"),
-        ("temp_owner" = String, Query, description = "Address of the temporary owner", example = "0xabcdef1234567890abcdef1234567890abcdef12")
+        ("temp_owner" = String, Query, description = "Address of the temporary owner", example = "0xabcdef1234567890abcdef1234567890abcdef12"),
+        ("nonce" = String, Query, description = "Intent nonce, a decimal uint256 not used before by the caller on this chain", example = "1"),
+        ("deadline" = u64, Query, description = "Unix time after which the intent signature is rejected", example = 1792195200),
+        ("signature" = String, Query, description = "EIP-712 signature of the OwnershipIntent by the session's wallet", example = "0xad71ff20241e4a798f4416f71411b4343ed8f939c45375d4f78125a84fdfd1fd0ca5f8d0b037cddb02bf2989b2c1b0a9e0845260867160172e07cb0cc452148b1c")
     ),
     responses(
         (status = 200, description = "Ownership code generated successfully", body = OwnershipCodeResponse, example = json!({
             "ownership_code": "0xabcdef1234567890abcdef1234567890abcdef1234567890abcdef1234567890"
         })),
         (status = 400, description = "Invalid input (e.g., caller is temp_owner or caller not registered)"),
+        (status = 401, description = "Missing or invalid session token, or an intent signature that is expired, reused or not from the caller"),
         (status = 404, description = "Item not found"),
         (status = 500, description = "Internal server error")
     ),
+    security(("session_token" = [])),
     tag = "Ownership"
 )]
 pub async fn transfer_ownership_code(
     Query(query): Query<GenerateOwnershipCodeQuery>,
+    Query(intent): Query<IntentSignature>,
     State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
 ) -> impl IntoResponse {
-    match generate_ownership_code_internal(&state, &query).await {
+    match generate_ownership_code_internal(&state, &query, &intent, &user).await {
         Ok(ownership_code) => (
             StatusCode::OK,
             Json(OwnershipCodeResponse { ownership_code }),
@@ -63,6 +73,10 @@ pub async fn transfer_ownership_code(
             let (status, message) = match e.to_string().as_str() {
                 "Caller cannot be the temporary owner" => (StatusCode::BAD_REQUEST, e.to_string()),
                 "Caller is not registered" => (StatusCode::BAD_REQUEST, e.to_string()),
+                s if s.starts_with("Invalid temp_owner") || s.starts_with("Invalid intent") => {
+                    (StatusCode::BAD_REQUEST, e.to_string())
+                }
+                s if s.starts_with("Intent") => (StatusCode::UNAUTHORIZED, e.to_string()),
                 "Item not found" => (StatusCode::NOT_FOUND, e.to_string()),
                 _ => (
                     StatusCode::INTERNAL_SERVER_ERROR,
@@ -77,10 +91,27 @@ pub async fn transfer_ownership_code(
 async fn generate_ownership_code_internal(
     state: &Arc<AppState>,
     query: &GenerateOwnershipCodeQuery,
+    signed: &IntentSignature,
+    user: &AuthenticatedUser,
 ) -> Result<String> {
-    if query.caller == query.temp_owner {
+    let caller = user.address.as_str();
+    if caller.eq_ignore_ascii_case(&query.temp_owner) {
         return Err(eyre::eyre!("Caller cannot be the temporary owner"));
     }
+    let temp_owner: Address = query
+        .temp_owner
+        .parse()
+        .map_err(|_| eyre::eyre!("Invalid temp_owner address"))?;
+    let chain = state.chain(Some(user.chain_id))?;
+    let intent = intent_for_chain(
+        chain,
+        ACTION_GENERATE,
+        &query.item_id,
+        "",
+        temp_owner,
+        &signed.nonce,
+        signed.deadline,
+    )?;
 
     let conn = &mut state.db_pool.get().map_err(|e| {
         eprintln!("Failed to get DB connection: {:?}", e);
@@ -88,7 +119,7 @@ async fn generate_ownership_code_internal(
     })?;
 
     if !users_info::table
-        .filter(users_info::user_address.eq(query.caller.clone()))
+        .filter(users_info::user_address.eq(caller))
         .filter(users_info::is_registered.eq(true))
         .select(diesel::dsl::count_star())
         .first::<i64>(conn)
@@ -96,10 +127,14 @@ async fn generate_ownership_code_internal(
     {
         return Err(eyre::eyre!("Caller is not registered"));
     }
+    // Codes are tied to the item on the chain's active deployment
+    let deployment_id = active_deployment_id(conn, chain)?;
+
     // Check if item exists and caller is the owner
     let item_exists_and_owned = items::table
+        .filter(items::deployment_id.eq(deployment_id))
         .filter(items::item_id.eq(&query.item_id))
-        .filter(items::owner.eq(query.caller.clone()))
+        .filter(items::owner.eq(caller))
         .select(diesel::dsl::count_star())
         .first::<i64>(conn)
         .map(|count| count > 0)
@@ -118,30 +153,37 @@ async fn generate_ownership_code_internal(
     // Generate keccak256 hash of caller, temp_owner, item_id, and current timestamp
     let hash_input = format!(
         "{}{}{}{}",
-        query.caller,
+        caller,
         query.temp_owner,
         query.item_id,
         Utc::now().to_rfc3339()
     );
     let hash = Keccak256::digest(hash_input.as_bytes());
     let ownership_code = format!("0x{}", hex::encode(hash));
-    // Save to ownership_codes table
-    diesel::insert_into(ownership_codes::table)
-        .values(OwnershipCode {
-            ownership_code: ownership_code.clone(),
-            item_id: query.item_id.clone(),
-            item_owner: query.caller.clone(),
-            temp_owner: query.temp_owner.clone(),
-            created_at: Utc::now().to_rfc3339(),
-        })
-        .execute(conn)
-        .map_err(|e| {
-            eprintln!(
-                "Error inserting ownership code for item {}: {:?}",
-                query.item_id, e
-            );
-            eyre::eyre!("Failed to insert ownership code: {}", e)
-        })?;
+    // Save the signed intent and the code together, so a failed insert leaves the nonce unused
+    conn.transaction::<_, eyre::Error, _>(|conn| {
+        let intent_id = verify_and_record_intent(conn, user.chain_id, &intent, signed, caller)?;
+        diesel::insert_into(ownership_codes::table)
+            .values(OwnershipCode {
+                ownership_code: ownership_code.clone(),
+                item_id: query.item_id.clone(),
+                item_owner: caller.to_string(),
+                temp_owner: query.temp_owner.clone(),
+                created_at: Utc::now().to_rfc3339(),
+                intent_id: Some(intent_id),
+                chain_id: Some(user.chain_id as i64),
+                deployment_id: Some(deployment_id),
+            })
+            .execute(conn)
+            .map_err(|e| {
+                eprintln!(
+                    "Error inserting ownership code for item {}: {:?}",
+                    query.item_id, e
+                );
+                eyre::eyre!("Failed to insert ownership code: {}", e)
+            })?;
+        Ok(())
+    })?;
 
     Ok(ownership_code)
 }
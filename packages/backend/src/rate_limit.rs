@@ -0,0 +1,420 @@
+use crate::auth::api_key::API_KEY_HEADER;
+use crate::auth::session::{find_session, hash_token};
+use crate::config::app_state::AppState;
+use axum::extract::{ConnectInfo, Request, State};
+use axum::http::header::{AUTHORIZATION, RETRY_AFTER};
+use axum::http::{HeaderValue, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use eyre::Result;
+use serde_json::json;
+use std::collections::HashMap;
+use std::env;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+// Buckets are pruned once this many are tracked; full buckets carry no state worth keeping
+const MAX_TRACKED_BUCKETS: usize = 100_000;
+
+// Routes that make the server wallet pay gas have their own, much smaller budget. Every
+// API route also draws from the read budget.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RouteClass {
+    Gas,
+    Read,
+}
+
+impl RouteClass {
+    fn as_str(self) -> &'static str {
+        match self {
+            RouteClass::Gas => "gas",
+            RouteClass::Read => "read",
+        }
+    }
+}
+
+// Requests allowed per minute, and how many can be made at once after a quiet period
+#[derive(Clone, Copy, Debug)]
+pub struct Budget {
+    pub per_minute: u32,
+    pub burst: u32,
+}
+
+impl Budget {
+    // A budget of zero requests per minute turns that limit off
+    fn from_env(prefix: &str, per_minute: u32, burst: u32) -> Result<Option<Self>> {
+        let read = |name: String, default: u32| {
+            env::var(&name)
+                .unwrap_or_else(|_| default.to_string())
+                .parse::<u32>()
+                .map_err(|e| eyre::eyre!("Invalid {}: {}", name, e))
+        };
+        let per_minute = read(format!("{}_PER_MINUTE", prefix), per_minute)?;
+        let burst = read(format!("{}_BURST", prefix), burst)?;
+        Ok((per_minute > 0).then_some(Budget {
+            per_minute,
+            burst: burst.max(1),
+        }))
+    }
+
+    fn refill_per_second(&self) -> f64 {
+        f64::from(self.per_minute) / 60.0
+    }
+}
+
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+// Token buckets per client IP, authenticated address and API key, kept in memory. Each
+// replica enforces its own limits.
+#[derive(Clone)]
+pub struct RateLimiter {
+    gas: Option<Budget>,
+    read: Option<Budget>,
+    // Number of proxies in front of the server that append to X-Forwarded-For. The client
+    // IP is the entry this many hops from the right; anything further left is written by
+    // the client and can't be trusted. Zero ignores the header.
+    trusted_proxies: usize,
+    buckets: Arc<Mutex<HashMap<String, Bucket>>>,
+}
+
+impl RateLimiter {
+    pub fn from_env() -> Result<Self> {
+        Ok(RateLimiter {
+            gas: Budget::from_env("RATE_LIMIT_GAS", 5, 3)?,
+            read: Budget::from_env("RATE_LIMIT_READ", 300, 60)?,
+            trusted_proxies: env::var("RATE_LIMIT_TRUSTED_PROXIES")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse::<usize>()
+                .map_err(|e| eyre::eyre!("Invalid RATE_LIMIT_TRUSTED_PROXIES: {}", e))?,
+            buckets: Arc::default(),
+        })
+    }
+
+    fn budget(&self, class: RouteClass) -> Option<Budget> {
+        match class {
+            RouteClass::Gas => self.gas,
+            RouteClass::Read => self.read,
+        }
+    }
+
+    // Takes a token from every subject's bucket, or none of them. On refusal, returns the
+    // number of seconds until the emptiest bucket has a token again.
+    fn acquire(
+        &self,
+        class: RouteClass,
+        budget: Budget,
+        subjects: &[String],
+    ) -> std::result::Result<(), u64> {
+        let mut buckets = self.buckets.lock().unwrap();
+        let now = Instant::now();
+        if buckets.len() > MAX_TRACKED_BUCKETS {
+            for class in [RouteClass::Gas, RouteClass::Read] {
+                if let Some(budget) = self.budget(class) {
+                    prune(&mut buckets, class, budget, now);
+                }
+            }
+        }
+        acquire_at(&mut buckets, class, budget, subjects, now)
+    }
+
+    fn client_ip(&self, request: &Request) -> Option<String> {
+        let forwarded = request
+            .headers()
+            .get("x-forwarded-for")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| forwarded_client_ip(value, self.trusted_proxies));
+        if forwarded.is_some() {
+            return forwarded;
+        }
+        request
+            .extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip().to_string())
+    }
+}
+
+fn acquire_at(
+    buckets: &mut HashMap<String, Bucket>,
+    class: RouteClass,
+    budget: Budget,
+    subjects: &[String],
+    now: Instant,
+) -> std::result::Result<(), u64> {
+    let capacity = f64::from(budget.burst);
+    let refill = budget.refill_per_second();
+
+    let keys: Vec<String> = subjects
+        .iter()
+        .map(|subject| format!("{}:{}", class.as_str(), subject))
+        .collect();
+    let mut wait: f64 = 0.0;
+    for key in &keys {
+        let bucket = buckets.entry(key.clone()).or_insert(Bucket {
+            tokens: capacity,
+            updated_at: now,
+        });
+        let elapsed = now.duration_since(bucket.updated_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * refill).min(capacity);
+        bucket.updated_at = now;
+        if bucket.tokens < 1.0 {
+            wait = wait.max((1.0 - bucket.tokens) / refill);
+        }
+    }
+    if wait > 0.0 {
+        return Err(wait.ceil() as u64);
+    }
+
+    for key in &keys {
+        if let Some(bucket) = buckets.get_mut(key) {
+            bucket.tokens -= 1.0;
+        }
+    }
+    Ok(())
+}
+
+// Drops `class` buckets that would be full by now. Each class refills at its own rate, so
+// the others are left for their own budget to judge.
+fn prune(buckets: &mut HashMap<String, Bucket>, class: RouteClass, budget: Budget, now: Instant) {
+    let prefix = format!("{}:", class.as_str());
+    let capacity = f64::from(budget.burst);
+    let refill = budget.refill_per_second();
+    buckets.retain(|key, bucket| {
+        !key.starts_with(&prefix)
+            || bucket.tokens + now.duration_since(bucket.updated_at).as_secs_f64() * refill
+                < capacity
+    });
+}
+
+// The X-Forwarded-For entry `trusted_proxies` hops from the right. Returns None when the
+// header is ignored or has fewer entries than there are proxies.
+fn forwarded_client_ip(header: &str, trusted_proxies: usize) -> Option<String> {
+    if trusted_proxies == 0 {
+        return None;
+    }
+    let hops: Vec<&str> = header.split(',').map(str::trim).collect();
+    let index = hops.len().checked_sub(trusted_proxies)?;
+    Some(hops[index].to_string()).filter(|ip| !ip.is_empty())
+}
+
+fn bearer_token(request: &Request) -> Option<&str> {
+    request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+}
+
+// The address behind a session token and the API key a request carries. A token that does
+// not resolve to a live session is left to the handler to reject.
+fn credential_subjects(state: &AppState, request: &Request) -> Vec<String> {
+    let mut subjects = Vec::new();
+    if let Some(token) = bearer_token(request) {
+        let session = state
+            .db_pool
+            .get()
+            .map_err(|e| eyre::eyre!("Failed to get DB connection: {}", e))
+            .and_then(|mut conn| find_session(&mut conn, token));
+        match session {
+            Ok(Some(session)) => subjects.push(format!("address:{}", session.address)),
+            Ok(None) => {}
+            Err(e) => eprintln!("Error resolving session for rate limiting: {:?}", e),
+        }
+    }
+
+    if let Some(key) = request
+        .headers()
+        .get(API_KEY_HEADER)
+        .and_then(|value| value.to_str().ok())
+    {
+        subjects.push(format!("api_key:{}", hash_token(key)));
+    }
+
+    subjects
+}
+
+fn too_many_requests(retry_after: u64) -> Response {
+    let mut response = (
+        StatusCode::TOO_MANY_REQUESTS,
+        Json(json!({"error": "Too many requests"})),
+    )
+        .into_response();
+    response
+        .headers_mut()
+        .insert(RETRY_AFTER, HeaderValue::from(retry_after));
+    response
+}
+
+// Middleware answering 429 with Retry-After once any of the request's subjects has used
+// up its budget for `class`. The client IP is charged before the session is looked up, so
+// a flood of made-up bearer tokens is refused without touching the database.
+pub async fn rate_limit(
+    State(state): State<Arc<AppState>>,
+    class: RouteClass,
+    request: Request,
+    next: Next,
+) -> Response {
+    let limiter = &state.rate_limiter;
+    let Some(budget) = limiter.budget(class) else {
+        return next.run(request).await;
+    };
+
+    if let Some(ip) = limiter.client_ip(&request)
+        && let Err(retry_after) = limiter.acquire(class, budget, &[format!("ip:{}", ip)])
+    {
+        return too_many_requests(retry_after);
+    }
+
+    let subjects = credential_subjects(&state, &request);
+    if subjects.is_empty() {
+        return next.run(request).await;
+    }
+    match limiter.acquire(class, budget, &subjects) {
+        Ok(()) => next.run(request).await,
+        Err(retry_after) => too_many_requests(retry_after),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    const BUDGET: Budget = Budget {
+        per_minute: 60,
+        burst: 2,
+    };
+
+    fn subjects(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn acquire_spends_burst_then_refuses() {
+        let mut buckets = HashMap::new();
+        let now = Instant::now();
+        let ip = subjects(&["ip:1.2.3.4"]);
+
+        assert_eq!(acquire_at(&mut buckets, RouteClass::Read, BUDGET, &ip, now), Ok(()));
+        assert_eq!(acquire_at(&mut buckets, RouteClass::Read, BUDGET, &ip, now), Ok(()));
+        assert_eq!(acquire_at(&mut buckets, RouteClass::Read, BUDGET, &ip, now), Err(1));
+    }
+
+    #[test]
+    fn acquire_refills_over_time_up_to_burst() {
+        let mut buckets = HashMap::new();
+        let start = Instant::now();
+        let ip = subjects(&["ip:1.2.3.4"]);
+        for _ in 0..2 {
+            acquire_at(&mut buckets, RouteClass::Read, BUDGET, &ip, start).unwrap();
+        }
+
+        let later = start + Duration::from_secs(1);
+        assert_eq!(acquire_at(&mut buckets, RouteClass::Read, BUDGET, &ip, later), Ok(()));
+        assert!(acquire_at(&mut buckets, RouteClass::Read, BUDGET, &ip, later).is_err());
+
+        // A long quiet period refills no further than the burst
+        let much_later = later + Duration::from_secs(600);
+        for _ in 0..2 {
+            acquire_at(&mut buckets, RouteClass::Read, BUDGET, &ip, much_later).unwrap();
+        }
+        assert!(acquire_at(&mut buckets, RouteClass::Read, BUDGET, &ip, much_later).is_err());
+    }
+
+    #[test]
+    fn retry_after_rounds_up_to_the_emptiest_bucket() {
+        let slow = Budget {
+            per_minute: 6,
+            burst: 1,
+        };
+        let mut buckets = HashMap::new();
+        let now = Instant::now();
+        let ip = subjects(&["ip:1.2.3.4"]);
+        acquire_at(&mut buckets, RouteClass::Gas, slow, &ip, now).unwrap();
+
+        let later = now + Duration::from_millis(2500);
+        assert_eq!(acquire_at(&mut buckets, RouteClass::Gas, slow, &ip, later), Err(8));
+    }
+
+    #[test]
+    fn acquire_takes_from_all_subjects_or_none() {
+        let mut buckets = HashMap::new();
+        let now = Instant::now();
+        let key = subjects(&["api_key:k"]);
+        for _ in 0..2 {
+            acquire_at(&mut buckets, RouteClass::Read, BUDGET, &key, now).unwrap();
+        }
+
+        let both = subjects(&["ip:1.2.3.4", "api_key:k"]);
+        assert!(acquire_at(&mut buckets, RouteClass::Read, BUDGET, &both, now).is_err());
+        assert_eq!(buckets["read:ip:1.2.3.4"].tokens, 2.0);
+    }
+
+    #[test]
+    fn route_classes_have_separate_buckets() {
+        let mut buckets = HashMap::new();
+        let now = Instant::now();
+        let ip = subjects(&["ip:1.2.3.4"]);
+        for _ in 0..2 {
+            acquire_at(&mut buckets, RouteClass::Gas, BUDGET, &ip, now).unwrap();
+        }
+        assert_eq!(acquire_at(&mut buckets, RouteClass::Read, BUDGET, &ip, now), Ok(()));
+    }
+
+    #[test]
+    fn prune_keeps_only_buckets_still_refilling() {
+        let mut buckets = HashMap::new();
+        let start = Instant::now();
+        acquire_at(&mut buckets, RouteClass::Read, BUDGET, &subjects(&["ip:a"]), start).unwrap();
+        let later = start + Duration::from_millis(500);
+        acquire_at(&mut buckets, RouteClass::Read, BUDGET, &subjects(&["ip:b"]), later).unwrap();
+
+        prune(&mut buckets, RouteClass::Read, BUDGET, start + Duration::from_millis(1200));
+        assert!(!buckets.contains_key("read:ip:a"));
+        assert!(buckets.contains_key("read:ip:b"));
+    }
+
+    #[test]
+    fn prune_judges_each_class_by_its_own_budget() {
+        let gas = Budget {
+            per_minute: 5,
+            burst: 1,
+        };
+        let read = Budget {
+            per_minute: 300,
+            burst: 60,
+        };
+        let mut buckets = HashMap::new();
+        let start = Instant::now();
+        let ip = subjects(&["ip:1.2.3.4"]);
+        acquire_at(&mut buckets, RouteClass::Gas, gas, &ip, start).unwrap();
+        acquire_at(&mut buckets, RouteClass::Read, read, &ip, start).unwrap();
+
+        // The read bucket is full again well before the gas bucket has its token back
+        let later = start + Duration::from_secs(1);
+        prune(&mut buckets, RouteClass::Read, read, later);
+        assert!(!buckets.contains_key("read:ip:1.2.3.4"));
+        assert!(buckets.contains_key("gas:ip:1.2.3.4"));
+        assert!(acquire_at(&mut buckets, RouteClass::Gas, gas, &ip, later).is_err());
+
+        prune(&mut buckets, RouteClass::Gas, gas, later);
+        assert!(buckets.contains_key("gas:ip:1.2.3.4"));
+        prune(&mut buckets, RouteClass::Gas, gas, start + Duration::from_secs(13));
+        assert!(!buckets.contains_key("gas:ip:1.2.3.4"));
+    }
+
+    #[test]
+    fn forwarded_client_ip_counts_trusted_hops_from_the_right() {
+        let header = "6.6.6.6, 1.2.3.4, 10.0.0.1";
+        assert_eq!(forwarded_client_ip(header, 0), None);
+        assert_eq!(forwarded_client_ip(header, 1).as_deref(), Some("10.0.0.1"));
+        assert_eq!(forwarded_client_ip(header, 2).as_deref(), Some("1.2.3.4"));
+        assert_eq!(forwarded_client_ip(header, 3).as_deref(), Some("6.6.6.6"));
+        assert_eq!(forwarded_client_ip(header, 4), None);
+        assert_eq!(forwarded_client_ip("1.2.3.4,", 1), None);
+    }
+}
@@ -0,0 +1,3 @@
+pub mod nonce;
+pub mod queue;
+pub mod worker;
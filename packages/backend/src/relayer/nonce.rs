@@ -0,0 +1,147 @@
+use crate::relayer::queue::{STATUS_CONFIRMED, STATUS_MINED, STATUS_SUBMITTED};
+use crate::schema::relayed_transactions;
+use diesel::prelude::*;
+use diesel::{PgConnection, RunQueryDsl};
+use ethers::prelude::{BlockNumber, Middleware};
+use ethers::types::Address;
+use eyre::Result;
+
+// Hands out the server wallet's nonces for one chain. Only the relayer worker sends from
+// the wallet, so nonces are tracked locally instead of asking the node before every send.
+pub struct NonceManager {
+    next: u64,
+}
+
+impl NonceManager {
+    // Starts past both the node's pending count and every nonce the relayer has already
+    // signed, including transactions stored before a restart but never broadcast
+    pub async fn recover<M: Middleware>(
+        conn: &mut PgConnection,
+        client: &M,
+        chain_id: u64,
+        from: Address,
+        from_address: &str,
+    ) -> Result<Self> {
+        let pending = client
+            .get_transaction_count(from, Some(BlockNumber::Pending.into()))
+            .await
+            .map_err(|e| {
+                eprintln!("Failed to get pending nonce on chain {}: {:?}", chain_id, e);
+                eyre::eyre!("Failed to get pending nonce: {}", e)
+            })?
+            .as_u64();
+
+        let highest_signed = relayed_transactions::table
+            .filter(relayed_transactions::chain_id.eq(chain_id as i64))
+            .filter(relayed_transactions::from_address.eq(from_address))
+            .filter(relayed_transactions::status.eq_any([STATUS_SUBMITTED, STATUS_MINED, STATUS_CONFIRMED]))
+            .select(diesel::dsl::max(relayed_transactions::nonce))
+            .first::<Option<i64>>(conn)
+            .map_err(|e| eyre::eyre!("Failed to load relayed nonces: {}", e))?;
+
+        let next = highest_signed
+            .map(|nonce| (nonce as u64 + 1).max(pending))
+            .unwrap_or(pending);
+        eprintln!("Relayer on chain {} starts at nonce {}", chain_id, next);
+        Ok(NonceManager { next })
+    }
+
+    pub fn peek(&self) -> u64 {
+        self.next
+    }
+
+    // Called once a transaction with the peeked nonce has been stored
+    pub fn advance(&mut self) {
+        self.next += 1;
+    }
+
+    // Gives the last nonce back when the node rejected its transaction for a reason other
+    // than the nonce, so later transactions do not wait behind a gap
+    pub fn release(&mut self, nonce: u64) {
+        if nonce + 1 == self.next {
+            self.next = nonce;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::relayer::queue::{STATUS_FAILED, STATUS_QUEUED};
+    use crate::test_db::test_connection;
+    use ethers::providers::{MockProvider, Provider};
+    use ethers::types::U256;
+
+    const CHAIN_ID: u64 = 31_337;
+    const FROM: &str = "0x1111111111111111111111111111111111111111";
+
+    fn node_with_pending_count(pending: u64) -> Provider<MockProvider> {
+        let (provider, mock) = Provider::mocked();
+        mock.push(U256::from(pending)).unwrap();
+        provider
+    }
+
+    fn insert_relayed(conn: &mut PgConnection, status: &str, nonce: Option<i64>) {
+        diesel::insert_into(relayed_transactions::table)
+            .values((
+                relayed_transactions::chain_id.eq(CHAIN_ID as i64),
+                relayed_transactions::kind.eq("create_item"),
+                relayed_transactions::from_address.eq(FROM),
+                relayed_transactions::to_address.eq(FROM),
+                relayed_transactions::calldata.eq("0x"),
+                relayed_transactions::status.eq(status),
+                relayed_transactions::nonce.eq(nonce),
+                relayed_transactions::created_at.eq("2026-01-01T00:00:00Z"),
+                relayed_transactions::updated_at.eq("2026-01-01T00:00:00Z"),
+                relayed_transactions::job_id.eq(format!("job-{:?}-{}", nonce, status)),
+            ))
+            .execute(conn)
+            .unwrap();
+    }
+
+    async fn recover(conn: &mut PgConnection, pending: u64) -> NonceManager {
+        let client = node_with_pending_count(pending);
+        let from: Address = FROM.parse().unwrap();
+        NonceManager::recover(conn, &client, CHAIN_ID, from, FROM)
+            .await
+            .unwrap()
+    }
+
+    #[test]
+    fn advance_and_release_hand_out_nonces_in_order() {
+        let mut nonces = NonceManager { next: 7 };
+        assert_eq!(nonces.peek(), 7);
+        nonces.advance();
+        assert_eq!(nonces.peek(), 8);
+
+        // Only the most recent nonce can be given back
+        nonces.advance();
+        nonces.release(7);
+        assert_eq!(nonces.peek(), 9);
+        nonces.release(8);
+        assert_eq!(nonces.peek(), 8);
+    }
+
+    #[tokio::test]
+    async fn recover_starts_at_the_pending_count_without_signed_transactions() {
+        let Some(mut conn) = test_connection() else {
+            return;
+        };
+        assert_eq!(recover(&mut conn, 5).await.peek(), 5);
+    }
+
+    #[tokio::test]
+    async fn recover_skips_nonces_signed_before_a_restart() {
+        let Some(mut conn) = test_connection() else {
+            return;
+        };
+        // Signed and stored, but the node has not seen it
+        insert_relayed(&mut conn, STATUS_SUBMITTED, Some(9));
+        // Neither keeps its nonce taken
+        insert_relayed(&mut conn, STATUS_FAILED, Some(12));
+        insert_relayed(&mut conn, STATUS_QUEUED, None);
+
+        assert_eq!(recover(&mut conn, 5).await.peek(), 10);
+        assert_eq!(recover(&mut conn, 11).await.peek(), 11);
+    }
+}
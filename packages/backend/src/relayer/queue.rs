@@ -0,0 +1,131 @@
+use crate::config::app_state::AppState;
+use crate::config::chains::ChainConfig;
+use crate::contract_models::{NewRelayedTransaction, RelayedTransaction};
+use crate::schema::relayed_transactions;
+use crate::utility::timestamp_after;
+use diesel::prelude::*;
+use diesel::{PgConnection, RunQueryDsl};
+use ethers::abi::Detokenize;
+use ethers::contract::ContractCall;
+use ethers::core::utils::to_checksum;
+use ethers::prelude::Middleware;
+use eyre::Result;
+use std::time::{Duration, Instant};
+
+// Statuses of a relayed transaction
+pub const STATUS_QUEUED: &str = "queued";
+pub const STATUS_SUBMITTED: &str = "submitted";
+pub const STATUS_MINED: &str = "mined";
+pub const STATUS_CONFIRMED: &str = "confirmed";
+pub const STATUS_FAILED: &str = "failed";
+
+// How often a waiting request checks on its transaction, and when it gives up. The
+// transaction is still relayed after the request times out.
+const WAIT_INTERVAL: Duration = Duration::from_secs(1);
+const WAIT_TIMEOUT: Duration = Duration::from_secs(300);
+
+// Queues a contract call to be sent from the chain's server wallet by the relayer worker.
+// `intent_id` links the transaction to the signed intent that authorised it.
+pub fn enqueue_call<M: Middleware, D: Detokenize>(
+    conn: &mut PgConnection,
+    chain: &ChainConfig,
+    kind: &str,
+    call: &ContractCall<M, D>,
+    intent_id: Option<i64>,
+) -> Result<RelayedTransaction> {
+    let to = call
+        .tx
+        .to_addr()
+        .ok_or_else(|| eyre::eyre!("Contract call has no target address"))?;
+    let calldata = call
+        .calldata()
+        .ok_or_else(|| eyre::eyre!("Contract call has no calldata"))?;
+    let now = timestamp_after(Duration::ZERO);
+
+    diesel::insert_into(relayed_transactions::table)
+        .values(NewRelayedTransaction {
+            chain_id: chain.chain_id as i64,
+            kind: kind.to_string(),
+            from_address: to_checksum(&chain.ownership_contract.client().address(), None),
+            to_address: to_checksum(to, None),
+            calldata: format!("0x{}", hex::encode(calldata)),
+            status: STATUS_QUEUED.to_string(),
+            intent_id,
+            created_at: now.clone(),
+            updated_at: now,
+            job_id: format!("job_{}", hex::encode(rand::random::<[u8; 16]>())),
+        })
+        .returning(RelayedTransaction::as_returning())
+        .get_result(conn)
+        .map_err(|e| {
+            eprintln!("Failed to queue {} transaction: {:?}", kind, e);
+            eyre::eyre!("Failed to queue transaction: {}", e)
+        })
+}
+
+// Estimates a call before it is queued, so one that would revert is refused with its revert
+// reason instead of becoming a failed job
+pub async fn check_call<M: Middleware, D: Detokenize>(call: &ContractCall<M, D>) -> Result<()> {
+    call.estimate_gas().await.map_err(|e| {
+        let revert_reason = e.decode_revert::<String>().unwrap_or_else(|| e.to_string());
+        eyre::eyre!("Gas estimation failed: {}", revert_reason)
+    })?;
+    Ok(())
+}
+
+pub fn find_job(conn: &mut PgConnection, job_id: &str) -> Result<Option<RelayedTransaction>> {
+    relayed_transactions::table
+        .filter(relayed_transactions::job_id.eq(job_id))
+        .select(RelayedTransaction::as_select())
+        .first(conn)
+        .optional()
+        .map_err(|e| eyre::eyre!("Failed to load job: {}", e))
+}
+
+pub fn find_relayed_transaction(conn: &mut PgConnection, id: i64) -> Result<Option<RelayedTransaction>> {
+    relayed_transactions::table
+        .find(id)
+        .select(RelayedTransaction::as_select())
+        .first(conn)
+        .optional()
+        .map_err(|e| eyre::eyre!("Failed to load relayed transaction: {}", e))
+}
+
+// Waits until the worker has the transaction mined or gives up on it, and returns the hash
+// that was mined. A failed transaction is returned as its error.
+pub async fn wait_for_transaction(state: &AppState, id: i64) -> Result<String> {
+    let started = Instant::now();
+    loop {
+        let relayed = {
+            let conn = &mut state
+                .db_pool
+                .get()
+                .map_err(|e| eyre::eyre!("Failed to get database connection: {}", e))?;
+            find_relayed_transaction(conn, id)?
+                .ok_or_else(|| eyre::eyre!("Relayed transaction {} not found", id))?
+        };
+
+        match relayed.status.as_str() {
+            STATUS_CONFIRMED => {
+                return relayed
+                    .tnx_hash
+                    .ok_or_else(|| eyre::eyre!("Transaction receipt not found"));
+            }
+            STATUS_FAILED => {
+                return Err(eyre::eyre!(
+                    "{}",
+                    relayed.error.unwrap_or_else(|| "Transaction failed".to_string())
+                ));
+            }
+            _ => {}
+        }
+
+        if started.elapsed() > WAIT_TIMEOUT {
+            return Err(eyre::eyre!(
+                "Timed out waiting for relayed transaction {}",
+                id
+            ));
+        }
+        tokio::time::sleep(WAIT_INTERVAL).await;
+    }
+}
@@ -0,0 +1,600 @@
+use crate::config::app_state::AppState;
+use crate::contract_models::RelayedTransaction;
+use crate::ownership::intent::set_intent_transaction;
+use crate::relayer::nonce::NonceManager;
+use crate::relayer::queue::{
+    STATUS_CONFIRMED, STATUS_FAILED, STATUS_MINED, STATUS_QUEUED, STATUS_SUBMITTED,
+};
+use crate::schema::relayed_transactions;
+use crate::utility::timestamp_after;
+use chrono::{SecondsFormat, Utc};
+use diesel::prelude::*;
+use diesel::{PgConnection, RunQueryDsl};
+use ethers::contract::ContractError;
+use ethers::core::utils::{keccak256, to_checksum};
+use ethers::middleware::SignerMiddleware;
+use ethers::prelude::{BlockNumber, Middleware, MiddlewareError};
+use ethers::signers::Signer;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::{
+    Address, BlockId, Bytes, TransactionReceipt, TransactionRequest, H256, U256, U64,
+};
+use eyre::Result;
+use std::env;
+use std::sync::Arc;
+use std::time::Duration;
+
+// How often the worker checks its queue and in-flight transactions
+const RELAYER_INTERVAL: Duration = Duration::from_secs(2);
+
+// Nodes only accept a replacement that pays at least 10% more
+const MIN_FEE_BUMP_PERCENT: u64 = 10;
+
+// Send errors meaning the nonce is not free: a mined or pending transaction already holds it,
+// or the local count has run ahead of the node
+const NONCE_ERRORS: [&str; 5] = [
+    "nonce too low",
+    "nonce too high",
+    "replacement transaction underpriced",
+    "replacement underpriced",
+    "nonce has already been used",
+];
+
+#[derive(Clone, Copy, Debug)]
+pub struct RelayerConfig {
+    // A transaction still unmined after this long is replaced with a higher gas price
+    pub stuck_after: Duration,
+    pub fee_bump_percent: u64,
+    // Gas price replacements never go above
+    pub max_gas_price: Option<U256>,
+}
+
+impl RelayerConfig {
+    pub fn from_env() -> Result<Self> {
+        let stuck_after = env::var("RELAYER_STUCK_AFTER_SECS")
+            .unwrap_or_else(|_| "90".to_string())
+            .parse::<u64>()
+            .map_err(|e| eyre::eyre!("Invalid RELAYER_STUCK_AFTER_SECS: {}", e))?;
+        let fee_bump_percent = env::var("RELAYER_FEE_BUMP_PERCENT")
+            .unwrap_or_else(|_| "20".to_string())
+            .parse::<u64>()
+            .map_err(|e| eyre::eyre!("Invalid RELAYER_FEE_BUMP_PERCENT: {}", e))?;
+        let max_gas_price = match env::var("RELAYER_MAX_GAS_PRICE_GWEI") {
+            Ok(gwei) => Some(
+                U256::from(
+                    gwei.parse::<u64>()
+                        .map_err(|e| eyre::eyre!("Invalid RELAYER_MAX_GAS_PRICE_GWEI: {}", e))?,
+                ) * U256::exp10(9),
+            ),
+            Err(_) => None,
+        };
+
+        Ok(RelayerConfig {
+            stuck_after: Duration::from_secs(stuck_after),
+            fee_bump_percent: fee_bump_percent.max(MIN_FEE_BUMP_PERCENT),
+            max_gas_price,
+        })
+    }
+}
+
+// Background job sending queued calls from the server wallet on one chain, one nonce after
+// the other, and following them until they are confirmed. Transactions left in flight by a
+// previous run are picked up where they were.
+pub async fn run_relayer(state: &Arc<AppState>, chain_id: u64) -> Result<()> {
+    let client = state.chain(Some(chain_id))?.ownership_contract.client();
+    let from = client.address();
+    let from_address = to_checksum(&from, None);
+    let mut nonces = {
+        let conn = &mut connection(state)?;
+        NonceManager::recover(conn, client.as_ref(), chain_id, from, &from_address).await?
+    };
+
+    loop {
+        {
+            let conn = &mut connection(state)?;
+
+            let mined = load_transactions(conn, chain_id, &from_address, STATUS_MINED)?;
+            confirm_mined(state, conn, client.as_ref(), &mined).await?;
+
+            // Read before the receipts, so a transaction mined in between is not taken for
+            // one whose nonce went to another sender
+            let mined_nonce = client
+                .get_transaction_count(from, Some(BlockNumber::Latest.into()))
+                .await
+                .map_err(|e| eyre::eyre!("Failed to get mined nonce: {}", e))?
+                .as_u64();
+            for relayed in load_transactions(conn, chain_id, &from_address, STATUS_SUBMITTED)? {
+                track_submitted(state, conn, client.as_ref(), &relayed, mined_nonce).await?;
+            }
+
+            for relayed in load_transactions(conn, chain_id, &from_address, STATUS_QUEUED)? {
+                submit_queued(conn, client.as_ref(), chain_id, &relayed, &mut nonces).await?;
+            }
+        }
+
+        tokio::time::sleep(RELAYER_INTERVAL).await;
+    }
+}
+
+fn connection(
+    state: &AppState,
+) -> Result<diesel::r2d2::PooledConnection<diesel::r2d2::ConnectionManager<PgConnection>>> {
+    state.db_pool.get().map_err(|e| {
+        eprintln!("Failed to get DB connection: {:?}", e);
+        eyre::eyre!("Failed to get DB connection: {}", e)
+    })
+}
+
+fn load_transactions(
+    conn: &mut PgConnection,
+    chain_id: u64,
+    from_address: &str,
+    status: &str,
+) -> Result<Vec<RelayedTransaction>> {
+    relayed_transactions::table
+        .filter(relayed_transactions::chain_id.eq(chain_id as i64))
+        .filter(relayed_transactions::from_address.eq(from_address))
+        .filter(relayed_transactions::status.eq(status))
+        .order(relayed_transactions::id.asc())
+        .select(RelayedTransaction::as_select())
+        .load(conn)
+        .map_err(|e| eyre::eyre!("Failed to load {} relayed transactions: {}", status, e))
+}
+
+async fn submit_queued<M: Middleware, S: Signer>(
+    conn: &mut PgConnection,
+    client: &SignerMiddleware<M, S>,
+    chain_id: u64,
+    relayed: &RelayedTransaction,
+    nonces: &mut NonceManager,
+) -> Result<()> {
+    let mut tx = transaction_request(client, chain_id, relayed)?;
+
+    // Reverts show up here, before a nonce is spent on the call
+    let gas_estimate = match client.estimate_gas(&tx, None).await {
+        Ok(gas_estimate) => gas_estimate,
+        Err(e) => {
+            let error = ContractError::<SignerMiddleware<M, S>>::from_middleware_error(e);
+            let revert_reason = error.decode_revert::<String>();
+            let message = format!(
+                "Gas estimation failed: {}",
+                revert_reason.clone().unwrap_or_else(|| error.to_string())
+            );
+            return mark_failed(conn, relayed, &message, revert_reason.as_deref());
+        }
+    };
+    let gas_limit = gas_estimate * 120 / 100;
+
+    // Get current gas price with a fallback
+    let gas_price = client
+        .get_gas_price()
+        .await
+        .unwrap_or(U256::from(2_000_000_000u64));
+
+    let balance = client
+        .get_balance(client.address(), None)
+        .await
+        .map_err(|e| eyre::eyre!("Failed to check wallet balance: {}", e))?;
+    let required_funds = gas_limit * gas_price;
+    if balance < required_funds {
+        return mark_failed(
+            conn,
+            relayed,
+            &format!(
+                "Insufficient funds: have {} wei, need {} wei",
+                balance, required_funds
+            ),
+            None,
+        );
+    }
+
+    let nonce = nonces.peek();
+    tx.set_nonce(nonce);
+    tx.set_gas(gas_limit);
+    tx.set_gas_price(gas_price);
+    let (tnx_hash, raw) = sign(client, &tx).await?;
+
+    // Stored before broadcasting, so a restart in between sends the same transaction again
+    // instead of signing a second one
+    record_submission(conn, relayed, Some(nonce), gas_limit, gas_price, &tnx_hash, &raw)?;
+    nonces.advance();
+
+    if let Err(e) = client.send_raw_transaction(raw).await {
+        if e.as_error_response().is_some() && !e.to_string().contains("already known") {
+            eprintln!("Relayed transaction {} was rejected: {:?}", relayed.id, e);
+            mark_failed(conn, relayed, &format!("Failed to send transaction: {}", e), None)?;
+            if is_nonce_error(&e.to_string()) {
+                // Handing the nonce out again would fail every later job the same way, so
+                // start over from what the node and the stored transactions say
+                let from = client.address();
+                *nonces =
+                    NonceManager::recover(conn, client, chain_id, from, &to_checksum(&from, None))
+                        .await?;
+            } else {
+                // The node refused it for another reason, so the nonce is still free
+                nonces.release(nonce);
+            }
+            return Ok(());
+        }
+        // Otherwise it may have reached the node; it is rebroadcast if the node has not seen it
+        eprintln!("Failed to broadcast relayed transaction {}: {:?}", relayed.id, e);
+    }
+    Ok(())
+}
+
+async fn track_submitted<M: Middleware, S: Signer>(
+    state: &AppState,
+    conn: &mut PgConnection,
+    client: &SignerMiddleware<M, S>,
+    relayed: &RelayedTransaction,
+    mined_nonce: u64,
+) -> Result<()> {
+    // Any of the fee-bumped versions may be the one that was mined
+    for tnx_hash in relayed.tnx_hashes.iter().flatten().rev() {
+        let hash: H256 = tnx_hash
+            .parse()
+            .map_err(|e| eyre::eyre!("Invalid stored transaction hash {}: {}", tnx_hash, e))?;
+        let receipt = client
+            .get_transaction_receipt(hash)
+            .await
+            .map_err(|e| eyre::eyre!("Failed to get transaction receipt: {}", e))?;
+        if let Some(receipt) = receipt {
+            return record_receipt(conn, client, relayed, tnx_hash, &receipt).await;
+        }
+    }
+
+    let nonce = relayed
+        .nonce
+        .ok_or_else(|| eyre::eyre!("Submitted transaction {} has no nonce", relayed.id))?
+        as u64;
+    if nonce < mined_nonce {
+        return mark_failed(
+            conn,
+            relayed,
+            &format!("Nonce {} was used by another transaction", nonce),
+            None,
+        );
+    }
+
+    let stuck_before = (Utc::now() - state.relayer.stuck_after)
+        .to_rfc3339_opts(SecondsFormat::Secs, true);
+    if relayed.submitted_at.as_deref().is_some_and(|submitted_at| submitted_at < stuck_before.as_str()) {
+        return bump_fee(conn, client, state.relayer, relayed).await;
+    }
+
+    // A transaction the node does not know was lost before a restart or dropped from the
+    // mempool; send the stored copy again
+    let (Some(tnx_hash), Some(raw)) = (&relayed.tnx_hash, &relayed.raw_transaction) else {
+        return Ok(());
+    };
+    let hash: H256 = tnx_hash
+        .parse()
+        .map_err(|e| eyre::eyre!("Invalid stored transaction hash {}: {}", tnx_hash, e))?;
+    let known = client
+        .get_transaction(hash)
+        .await
+        .map_err(|e| eyre::eyre!("Failed to look up transaction: {}", e))?
+        .is_some();
+    if !known {
+        let raw: Bytes = raw
+            .parse()
+            .map_err(|e| eyre::eyre!("Invalid stored raw transaction: {}", e))?;
+        if let Err(e) = client.send_raw_transaction(raw).await {
+            eprintln!("Failed to rebroadcast relayed transaction {}: {:?}", relayed.id, e);
+        }
+    }
+    Ok(())
+}
+
+// Replaces a stuck transaction with the same call and nonce at a higher gas price
+async fn bump_fee<M: Middleware, S: Signer>(
+    conn: &mut PgConnection,
+    client: &SignerMiddleware<M, S>,
+    config: RelayerConfig,
+    relayed: &RelayedTransaction,
+) -> Result<()> {
+    let parse = |value: &Option<String>, field: &str| {
+        value
+            .as_deref()
+            .and_then(|value| U256::from_dec_str(value).ok())
+            .ok_or_else(|| eyre::eyre!("Submitted transaction {} has no {}", relayed.id, field))
+    };
+    let gas_limit = parse(&relayed.gas_limit, "gas limit")?;
+    let gas_price = parse(&relayed.gas_price, "gas price")?;
+
+    let network_price = client.get_gas_price().await.unwrap_or_default();
+    let Some(bumped_price) = bumped_gas_price(config, gas_price, network_price) else {
+        // Already at the cap; keep waiting for the current one
+        return Ok(());
+    };
+
+    let mut tx = transaction_request(client, relayed.chain_id as u64, relayed)?;
+    tx.set_nonce(relayed.nonce.unwrap_or_default() as u64);
+    tx.set_gas(gas_limit);
+    tx.set_gas_price(bumped_price);
+    let (tnx_hash, raw) = sign(client, &tx).await?;
+    eprintln!(
+        "Replacing stuck relayed transaction {} at {} wei: {}",
+        relayed.id, bumped_price, tnx_hash
+    );
+
+    record_submission(conn, relayed, None, gas_limit, bumped_price, &tnx_hash, &raw)?;
+    if let Err(e) = client.send_raw_transaction(raw).await {
+        eprintln!("Failed to broadcast replacement for {}: {:?}", relayed.id, e);
+    }
+    Ok(())
+}
+
+// Price for a replacement: the configured bump over the current price, or the network price
+// if that is higher, never above the cap. None when the cap leaves no room for a bump.
+fn bumped_gas_price(config: RelayerConfig, gas_price: U256, network_price: U256) -> Option<U256> {
+    let mut bumped_price = (gas_price * (100 + config.fee_bump_percent)).div_mod(U256::from(100)).0 + 1;
+    bumped_price = bumped_price.max(network_price);
+    if let Some(max_gas_price) = config.max_gas_price {
+        bumped_price = bumped_price.min(max_gas_price);
+    }
+    (bumped_price > gas_price).then_some(bumped_price)
+}
+
+fn is_nonce_error(message: &str) -> bool {
+    let message = message.to_lowercase();
+    NONCE_ERRORS.iter().any(|fragment| message.contains(fragment))
+}
+
+fn transaction_request<M: Middleware, S: Signer>(
+    client: &SignerMiddleware<M, S>,
+    chain_id: u64,
+    relayed: &RelayedTransaction,
+) -> Result<TypedTransaction> {
+    let to: Address = relayed
+        .to_address
+        .parse()
+        .map_err(|e| eyre::eyre!("Invalid relayed target address: {}", e))?;
+    let calldata: Bytes = relayed
+        .calldata
+        .parse()
+        .map_err(|e| eyre::eyre!("Invalid relayed calldata: {}", e))?;
+
+    Ok(TransactionRequest::new()
+        .from(client.address())
+        .to(to)
+        .data(calldata)
+        .chain_id(chain_id)
+        .into())
+}
+
+async fn sign<M: Middleware, S: Signer>(
+    client: &SignerMiddleware<M, S>,
+    tx: &TypedTransaction,
+) -> Result<(String, Bytes)> {
+    let signature = client
+        .signer()
+        .sign_transaction(tx)
+        .await
+        .map_err(|e| eyre::eyre!("Failed to sign transaction: {}", e))?;
+    let raw = tx.rlp_signed(&signature);
+    Ok((format!("0x{}", hex::encode(keccak256(&raw))), raw))
+}
+
+// Stores a newly signed version of the transaction. `nonce` is set on the first submission
+// only; replacements keep it.
+fn record_submission(
+    conn: &mut PgConnection,
+    relayed: &RelayedTransaction,
+    nonce: Option<u64>,
+    gas_limit: U256,
+    gas_price: U256,
+    tnx_hash: &str,
+    raw: &Bytes,
+) -> Result<()> {
+    let now = timestamp_after(Duration::ZERO);
+    let mut tnx_hashes = relayed.tnx_hashes.clone();
+    tnx_hashes.push(Some(tnx_hash.to_string()));
+
+    diesel::update(relayed_transactions::table.find(relayed.id))
+        .set((
+            relayed_transactions::status.eq(STATUS_SUBMITTED),
+            relayed_transactions::nonce.eq(nonce.map(|nonce| nonce as i64).or(relayed.nonce)),
+            relayed_transactions::gas_limit.eq(gas_limit.to_string()),
+            relayed_transactions::gas_price.eq(gas_price.to_string()),
+            relayed_transactions::tnx_hash.eq(tnx_hash),
+            relayed_transactions::tnx_hashes.eq(tnx_hashes),
+            relayed_transactions::raw_transaction.eq(format!("0x{}", hex::encode(raw))),
+            relayed_transactions::attempts.eq(relayed.attempts + 1),
+            relayed_transactions::submitted_at.eq(&now),
+            relayed_transactions::updated_at.eq(&now),
+        ))
+        .execute(conn)
+        .map_err(|e| {
+            eprintln!("Failed to record relayed transaction {}: {:?}", relayed.id, e);
+            eyre::eyre!("Failed to record relayed transaction: {}", e)
+        })?;
+
+    // The indexer links claims to their intent by the hash that gets mined
+    if let Some(intent_id) = relayed.intent_id {
+        set_intent_transaction(conn, intent_id, tnx_hash)?;
+    }
+    Ok(())
+}
+
+async fn record_receipt<M: Middleware, S: Signer>(
+    conn: &mut PgConnection,
+    client: &SignerMiddleware<M, S>,
+    relayed: &RelayedTransaction,
+    tnx_hash: &str,
+    receipt: &TransactionReceipt,
+) -> Result<()> {
+    let block_number = receipt.block_number.map(|block| block.as_u64() as i64);
+    if receipt.status == Some(1.into()) {
+        diesel::update(relayed_transactions::table.find(relayed.id))
+            .set((
+                relayed_transactions::status.eq(STATUS_MINED),
+                relayed_transactions::tnx_hash.eq(tnx_hash),
+                relayed_transactions::block_number.eq(block_number),
+                relayed_transactions::updated_at.eq(timestamp_after(Duration::ZERO)),
+            ))
+            .execute(conn)
+            .map_err(|e| eyre::eyre!("Failed to record mined transaction: {}", e))?;
+    } else {
+        let revert_reason = replay_revert(client, relayed, receipt.block_number).await;
+        eprintln!("Relayed transaction {} reverted: {:?}", relayed.id, revert_reason);
+        diesel::update(relayed_transactions::table.find(relayed.id))
+            .set((
+                relayed_transactions::status.eq(STATUS_FAILED),
+                relayed_transactions::tnx_hash.eq(tnx_hash),
+                relayed_transactions::block_number.eq(block_number),
+                relayed_transactions::error.eq(match &revert_reason {
+                    Some(revert_reason) => format!("Transaction reverted: {}", revert_reason),
+                    None => "Transaction reverted".to_string(),
+                }),
+                relayed_transactions::revert_reason.eq(&revert_reason),
+                relayed_transactions::updated_at.eq(timestamp_after(Duration::ZERO)),
+            ))
+            .execute(conn)
+            .map_err(|e| eyre::eyre!("Failed to record reverted transaction: {}", e))?;
+    }
+
+    if let Some(intent_id) = relayed.intent_id {
+        set_intent_transaction(conn, intent_id, tnx_hash)?;
+    }
+    Ok(())
+}
+
+// Receipts do not carry the revert reason, so the call is replayed at its block to get it
+async fn replay_revert<M: Middleware, S: Signer>(
+    client: &SignerMiddleware<M, S>,
+    relayed: &RelayedTransaction,
+    block_number: Option<U64>,
+) -> Option<String> {
+    let tx = transaction_request(client, relayed.chain_id as u64, relayed).ok()?;
+    let block = block_number.map(|block| BlockId::Number(BlockNumber::Number(block)));
+    let e = client.call(&tx, block).await.err()?;
+    ContractError::<SignerMiddleware<M, S>>::from_middleware_error(e).decode_revert::<String>()
+}
+
+// Mined transactions are confirmed once they are as deep as the indexer requires. One a
+// reorg dropped goes back to submitted, to be tracked and rebroadcast again.
+async fn confirm_mined<M: Middleware, S: Signer>(
+    state: &AppState,
+    conn: &mut PgConnection,
+    client: &SignerMiddleware<M, S>,
+    mined: &[RelayedTransaction],
+) -> Result<()> {
+    if mined.is_empty() {
+        return Ok(());
+    }
+    let confirmed_head = state.confirmation_policy.confirmed_head(client).await?.as_u64() as i64;
+
+    for relayed in mined {
+        let Some(tnx_hash) = &relayed.tnx_hash else {
+            continue;
+        };
+        let hash: H256 = tnx_hash
+            .parse()
+            .map_err(|e| eyre::eyre!("Invalid stored transaction hash {}: {}", tnx_hash, e))?;
+        let receipt = client
+            .get_transaction_receipt(hash)
+            .await
+            .map_err(|e| eyre::eyre!("Failed to get transaction receipt: {}", e))?;
+
+        let (status, block_number) = match &receipt {
+            None => {
+                eprintln!("Relayed transaction {} was dropped by a reorg", relayed.id);
+                (STATUS_SUBMITTED, None)
+            }
+            Some(receipt) if receipt.status != Some(1.into()) => {
+                record_receipt(conn, client, relayed, tnx_hash, receipt).await?;
+                continue;
+            }
+            Some(receipt) => {
+                let block_number = receipt.block_number.map(|block| block.as_u64() as i64);
+                match block_number {
+                    Some(block) if block <= confirmed_head => (STATUS_CONFIRMED, block_number),
+                    _ if block_number == relayed.block_number => continue,
+                    _ => (STATUS_MINED, block_number),
+                }
+            }
+        };
+
+        diesel::update(relayed_transactions::table.find(relayed.id))
+            .set((
+                relayed_transactions::status.eq(status),
+                relayed_transactions::block_number.eq(block_number),
+                relayed_transactions::updated_at.eq(timestamp_after(Duration::ZERO)),
+            ))
+            .execute(conn)
+            .map_err(|e| eyre::eyre!("Failed to update mined transaction: {}", e))?;
+    }
+    Ok(())
+}
+
+fn mark_failed(
+    conn: &mut PgConnection,
+    relayed: &RelayedTransaction,
+    error: &str,
+    revert_reason: Option<&str>,
+) -> Result<()> {
+    eprintln!("Relayed transaction {} failed: {}", relayed.id, error);
+    diesel::update(relayed_transactions::table.find(relayed.id))
+        .set((
+            relayed_transactions::status.eq(STATUS_FAILED),
+            relayed_transactions::error.eq(error),
+            relayed_transactions::revert_reason.eq(revert_reason),
+            relayed_transactions::updated_at.eq(timestamp_after(Duration::ZERO)),
+        ))
+        .execute(conn)
+        .map_err(|e| eyre::eyre!("Failed to mark relayed transaction failed: {}", e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(fee_bump_percent: u64, max_gas_price: Option<u64>) -> RelayerConfig {
+        RelayerConfig {
+            stuck_after: Duration::from_secs(90),
+            fee_bump_percent,
+            max_gas_price: max_gas_price.map(U256::from),
+        }
+    }
+
+    #[test]
+    fn bump_adds_the_configured_percentage() {
+        let bumped = bumped_gas_price(config(20, None), U256::from(1_000), U256::zero());
+        assert_eq!(bumped, Some(U256::from(1_201)));
+    }
+
+    #[test]
+    fn bump_follows_a_higher_network_price() {
+        let bumped = bumped_gas_price(config(20, None), U256::from(1_000), U256::from(5_000));
+        assert_eq!(bumped, Some(U256::from(5_000)));
+    }
+
+    #[test]
+    fn bump_stops_at_the_cap() {
+        let capped = bumped_gas_price(config(20, Some(1_100)), U256::from(1_000), U256::zero());
+        assert_eq!(capped, Some(U256::from(1_100)));
+        assert_eq!(
+            bumped_gas_price(config(20, Some(1_000)), U256::from(1_000), U256::zero()),
+            None
+        );
+    }
+
+    #[test]
+    fn nonce_errors_are_told_apart_from_other_rejections() {
+        for message in [
+            "(code: -32000, message: nonce too low, data: None)",
+            "(code: -32000, message: replacement transaction underpriced, data: None)",
+            "(code: -32003, message: Nonce too high, data: None)",
+        ] {
+            assert!(is_nonce_error(message), "{}", message);
+        }
+        for message in [
+            "(code: -32000, message: transaction underpriced, data: None)",
+            "(code: -32000, message: insufficient funds for gas * price + value, data: None)",
+        ] {
+            assert!(!is_nonce_error(message), "{}", message);
+        }
+    }
+}
@@ -1,11 +1,54 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    admin_audit_log (id) {
+        id -> Int8,
+        actor -> Text,
+        role -> Text,
+        action -> Text,
+        chain_id -> Nullable<Int8>,
+        target -> Nullable<Text>,
+        details -> Jsonb,
+        succeeded -> Bool,
+        error -> Nullable<Text>,
+        created_at -> Text,
+    }
+}
+
+diesel::table! {
+    admin_roles (address) {
+        address -> Text,
+        role -> Text,
+        granted_by -> Text,
+        granted_at -> Text,
+    }
+}
+
+diesel::table! {
+    api_keys (id) {
+        id -> Int8,
+        manufacturer_address -> Text,
+        name -> Text,
+        key_prefix -> Text,
+        key_hash -> Text,
+        scopes -> Array<Nullable<Text>>,
+        created_at -> Text,
+        last_used_at -> Nullable<Text>,
+        revoked_at -> Nullable<Text>,
+    }
+}
+
 diesel::table! {
     authenticity_settings (id) {
         id -> Int4,
         authenticity_address -> Text,
         tnx_hash -> Text,
         created_at -> Text,
+        block_number -> Nullable<Int8>,
+        block_hash -> Nullable<Text>,
+        log_index -> Nullable<Int8>,
+        deployment_id -> Nullable<Int4>,
+        chain_id -> Nullable<Int8>,
     }
 }
 
@@ -19,6 +62,23 @@ diesel::table! {
         metadata_hash -> Text,
         metadata -> Array<Nullable<Text>>,
         signature -> Text,
+        chain_id -> Nullable<Int8>,
+    }
+}
+
+diesel::table! {
+    chain_events (id) {
+        id -> Int8,
+        contract_address -> Text,
+        event_name -> Text,
+        block_number -> Int8,
+        block_hash -> Text,
+        log_index -> Int8,
+        tnx_hash -> Text,
+        payload -> Jsonb,
+        created_at -> Text,
+        block_timestamp -> Nullable<Text>,
+        chain_id -> Nullable<Int8>,
     }
 }
 
@@ -37,6 +97,73 @@ diesel::table! {
         owner -> Text,
         tnx_hash -> Text,
         created_at -> Text,
+        block_number -> Nullable<Int8>,
+        block_hash -> Nullable<Text>,
+        log_index -> Nullable<Int8>,
+        deployment_id -> Nullable<Int4>,
+        chain_id -> Nullable<Int8>,
+    }
+}
+
+diesel::table! {
+    deployments (id) {
+        id -> Int4,
+        authenticity_address -> Text,
+        ownership_address -> Text,
+        authenticity_deployment_block -> Nullable<Int8>,
+        ownership_deployment_block -> Nullable<Int8>,
+        is_active -> Bool,
+        created_at -> Text,
+        chain_id -> Nullable<Int8>,
+    }
+}
+
+diesel::table! {
+    eip712_domains (id) {
+        id -> Int8,
+        contract_address -> Text,
+        name -> Text,
+        version -> Text,
+        chain_id -> Int8,
+        verifying_contract -> Text,
+        is_active -> Bool,
+        block_number -> Int8,
+        activated_block -> Int8,
+        tnx_hash -> Text,
+        created_at -> Text,
+        activated_at -> Text,
+        deployment_id -> Nullable<Int4>,
+    }
+}
+
+diesel::table! {
+    failed_events (id) {
+        id -> Int8,
+        contract_address -> Text,
+        stream -> Text,
+        event_name -> Text,
+        block_number -> Int8,
+        log_index -> Int8,
+        tnx_hash -> Text,
+        raw_log -> Jsonb,
+        error -> Text,
+        attempts -> Int4,
+        status -> Text,
+        next_retry_at -> Text,
+        created_at -> Text,
+        updated_at -> Text,
+        chain_id -> Nullable<Int8>,
+        ordering_key -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    indexed_blocks (contract_address, stream, block_number) {
+        contract_address -> Text,
+        stream -> Text,
+        block_number -> Int8,
+        block_hash -> Text,
+        chain_id -> Nullable<Int8>,
     }
 }
 
@@ -52,6 +179,11 @@ diesel::table! {
         metadata -> Array<Nullable<Text>>,
         created_at -> Text,
         tnx_hash -> Text,
+        block_number -> Nullable<Int8>,
+        block_hash -> Nullable<Text>,
+        log_index -> Nullable<Int8>,
+        deployment_id -> Nullable<Int4>,
+        chain_id -> Nullable<Int8>,
     }
 }
 
@@ -62,6 +194,11 @@ diesel::table! {
         is_registered -> Bool,
         registered_at -> Text,
         tnx_hash -> Text,
+        block_number -> Nullable<Int8>,
+        block_hash -> Nullable<Text>,
+        log_index -> Nullable<Int8>,
+        deployment_id -> Nullable<Int4>,
+        chain_id -> Nullable<Int8>,
     }
 }
 
@@ -73,6 +210,12 @@ diesel::table! {
         new_owner -> Text,
         tnx_hash -> Text,
         created_at -> Text,
+        block_number -> Nullable<Int8>,
+        block_hash -> Nullable<Text>,
+        log_index -> Nullable<Int8>,
+        deployment_id -> Nullable<Int4>,
+        chain_id -> Nullable<Int8>,
+        intent_id -> Nullable<Int8>,
     }
 }
 
@@ -83,6 +226,99 @@ diesel::table! {
         item_owner -> Text,
         temp_owner -> Text,
         created_at -> Text,
+        intent_id -> Nullable<Int8>,
+        chain_id -> Nullable<Int8>,
+        deployment_id -> Nullable<Int4>,
+    }
+}
+
+diesel::table! {
+    ownership_intents (id) {
+        id -> Int8,
+        chain_id -> Int8,
+        action -> Text,
+        signer -> Text,
+        item_id -> Text,
+        ownership_code -> Text,
+        temp_owner -> Text,
+        nonce -> Text,
+        deadline -> Int8,
+        digest -> Text,
+        signature -> Text,
+        tnx_hash -> Nullable<Text>,
+        created_at -> Text,
+    }
+}
+
+diesel::table! {
+    reconciliation_runs (id) {
+        id -> Int8,
+        chain_id -> Int8,
+        repair -> Bool,
+        items_checked -> Int8,
+        mismatches -> Int8,
+        repaired -> Int8,
+        report -> Jsonb,
+        started_at -> Text,
+        finished_at -> Text,
+    }
+}
+
+diesel::table! {
+    relayed_transactions (id) {
+        id -> Int8,
+        chain_id -> Int8,
+        kind -> Text,
+        from_address -> Text,
+        to_address -> Text,
+        calldata -> Text,
+        status -> Text,
+        nonce -> Nullable<Int8>,
+        gas_limit -> Nullable<Text>,
+        gas_price -> Nullable<Text>,
+        tnx_hash -> Nullable<Text>,
+        tnx_hashes -> Array<Nullable<Text>>,
+        raw_transaction -> Nullable<Text>,
+        attempts -> Int4,
+        error -> Nullable<Text>,
+        intent_id -> Nullable<Int8>,
+        created_at -> Text,
+        submitted_at -> Nullable<Text>,
+        updated_at -> Text,
+        job_id -> Text,
+        block_number -> Nullable<Int8>,
+        revert_reason -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    sessions (id) {
+        id -> Int8,
+        token_hash -> Text,
+        address -> Text,
+        chain_id -> Int8,
+        created_at -> Text,
+        expires_at -> Text,
+        revoked_at -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    siwe_nonces (nonce) {
+        nonce -> Text,
+        created_at -> Text,
+        expires_at -> Text,
+        used_at -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    sync_checkpoints (contract_address, stream) {
+        contract_address -> Text,
+        stream -> Text,
+        last_processed_block -> Int8,
+        updated_at -> Text,
+        chain_id -> Nullable<Int8>,
     }
 }
 
@@ -93,17 +329,36 @@ diesel::table! {
         is_registered -> Bool,
         created_at -> Text,
         tnx_hash -> Text,
+        block_number -> Nullable<Int8>,
+        block_hash -> Nullable<Text>,
+        log_index -> Nullable<Int8>,
+        deployment_id -> Nullable<Int4>,
+        chain_id -> Nullable<Int8>,
     }
 }
 
 diesel::allow_tables_to_appear_in_same_query!(
+    admin_audit_log,
+    admin_roles,
+    api_keys,
     authenticity_settings,
     certificates,
+    chain_events,
     code_revokations,
     contracts,
+    deployments,
+    eip712_domains,
+    failed_events,
+    indexed_blocks,
     items,
     manufacturers,
     ownership_claims,
     ownership_codes,
+    ownership_intents,
+    reconciliation_runs,
+    relayed_transactions,
+    sessions,
+    siwe_nonces,
+    sync_checkpoints,
     users_info,
 );
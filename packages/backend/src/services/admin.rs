@@ -0,0 +1,323 @@
+use axum::{
+    extract::{Extension, Json, Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json as AxumJson,
+};
+use ethers::types::Address;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+use utoipa::{IntoParams, ToSchema};
+use crate::auth::admin::{
+    grant_role, list_audit_log, list_roles, record_admin_action, revoke_role, AdminActor,
+    AdminRole,
+};
+use crate::config::app_state::AppState;
+use crate::contract_models::{AdminAuditEntry, AdminRoleGrant, ChainQuery};
+use crate::relayer::queue::{enqueue_call, wait_for_transaction};
+
+#[derive(Deserialize, ToSchema)]
+pub struct GrantRoleRequest {
+    #[schema(example = "0x1234567890abcdef1234567890abcdef12345678")]
+    pub address: String,
+    // `admin`, `operator` or `support`
+    #[schema(example = "operator")]
+    pub role: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct AdminRolesResponse {
+    pub roles: Vec<AdminRoleGrant>,
+}
+
+#[derive(Deserialize, ToSchema, IntoParams)]
+pub struct AuditLogQuery {
+    #[schema(example = "0x1234567890AbcdEF1234567890aBcdef12345678")]
+    pub actor: Option<String>,
+    #[schema(example = "set_authenticity")]
+    pub action: Option<String>,
+    #[schema(example = 100)]
+    pub limit: Option<i64>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct AuditLogResponse {
+    pub entries: Vec<AdminAuditEntry>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct RegisterManufacturerRequest {
+    #[schema(example = "SAMSUNG")]
+    pub name: String,
+    #[schema(example = "0x1234567890abcdef1234567890abcdef12345678")]
+    pub address: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct RegisterManufacturerResponse {
+    transaction_hash: String,
+}
+
+// Define the error response struct
+#[derive(Serialize, ToSchema)]
+struct ErrorResponse {
+    error: String,
+}
+
+fn error_response(e: eyre::Report) -> axum::response::Response {
+    let (status, message) = match e.to_string().as_str() {
+        s if s.contains("Unknown role")
+            || s.contains("Invalid address")
+            || s.contains("Unsupported chain id")
+            || s.contains("Manufacturer name cannot be empty") =>
+        {
+            (StatusCode::BAD_REQUEST, e.to_string())
+        }
+        s if s.contains("Admin role not found") => (StatusCode::NOT_FOUND, e.to_string()),
+        s if s.contains("ONLY_OWNER") => (
+            StatusCode::FORBIDDEN,
+            "Backend wallet is not the contract owner".to_string(),
+        ),
+        _ => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Internal server error: {}", e),
+        ),
+    };
+    (status, AxumJson(json!({"error": message}))).into_response()
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/roles",
+    responses(
+        (status = 200, description = "Wallets holding an admin role", body = AdminRolesResponse),
+        (status = 401, description = "Missing or invalid session token or admin key", body = ErrorResponse),
+        (status = 403, description = "Caller lacks the admin role", body = ErrorResponse, example = json!({"error": "Requires the admin role"})),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(("session_token" = []), ("admin_key" = [])),
+    tag = "Admin"
+)]
+pub async fn get_admin_roles(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let result = state
+        .db_pool
+        .get()
+        .map_err(|e| eyre::eyre!("Failed to get database connection: {}", e))
+        .and_then(|mut conn| list_roles(&mut conn));
+
+    match result {
+        Ok(roles) => (StatusCode::OK, AxumJson(AdminRolesResponse { roles })).into_response(),
+        Err(e) => {
+            eprintln!("Error listing admin roles: {:?}", e);
+            error_response(e)
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/roles",
+    request_body = GrantRoleRequest,
+    responses(
+        (status = 200, description = "Role granted, replacing any role the wallet held", body = AdminRoleGrant),
+        (status = 400, description = "Invalid address or unknown role", body = ErrorResponse, example = json!({"error": "Unknown role: owner"})),
+        (status = 401, description = "Missing or invalid session token or admin key", body = ErrorResponse),
+        (status = 403, description = "Caller lacks the admin role", body = ErrorResponse, example = json!({"error": "Requires the admin role"})),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(("session_token" = []), ("admin_key" = [])),
+    tag = "Admin"
+)]
+pub async fn grant_admin_role(
+    State(state): State<Arc<AppState>>,
+    Extension(actor): Extension<AdminActor>,
+    Json(request): Json<GrantRoleRequest>,
+) -> impl IntoResponse {
+    let result = AdminRole::parse(&request.role).and_then(|role| {
+        let conn = &mut state
+            .db_pool
+            .get()
+            .map_err(|e| eyre::eyre!("Failed to get database connection: {}", e))?;
+        grant_role(conn, &request.address, role, &actor.actor)
+    });
+    record_admin_action(
+        &state,
+        &actor,
+        "grant_role",
+        None,
+        Some(&request.address),
+        json!({ "role": request.role }),
+        &result,
+    );
+
+    match result {
+        Ok(grant) => (StatusCode::OK, AxumJson(grant)).into_response(),
+        Err(e) => {
+            eprintln!("Error granting {} role to {}: {:?}", request.role, request.address, e);
+            error_response(e)
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/roles/{address}/revoke",
+    params(
+        ("address" = String, Path, description = "Wallet whose role is revoked", example = "0x1234567890abcdef1234567890abcdef12345678")
+    ),
+    responses(
+        (status = 200, description = "Role revoked", body = AdminRoleGrant),
+        (status = 401, description = "Missing or invalid session token or admin key", body = ErrorResponse),
+        (status = 403, description = "Caller lacks the admin role", body = ErrorResponse, example = json!({"error": "Requires the admin role"})),
+        (status = 404, description = "Wallet holds no admin role", body = ErrorResponse, example = json!({"error": "Admin role not found"})),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(("session_token" = []), ("admin_key" = [])),
+    tag = "Admin"
+)]
+pub async fn revoke_admin_role(
+    State(state): State<Arc<AppState>>,
+    Extension(actor): Extension<AdminActor>,
+    Path(address): Path<String>,
+) -> impl IntoResponse {
+    let result = state
+        .db_pool
+        .get()
+        .map_err(|e| eyre::eyre!("Failed to get database connection: {}", e))
+        .and_then(|mut conn| revoke_role(&mut conn, &address));
+    record_admin_action(
+        &state,
+        &actor,
+        "revoke_role",
+        None,
+        Some(&address),
+        json!({ "role": result.as_ref().ok().map(|grant| &grant.role) }),
+        &result,
+    );
+
+    match result {
+        Ok(grant) => (StatusCode::OK, AxumJson(grant)).into_response(),
+        Err(e) => {
+            eprintln!("Error revoking admin role of {}: {:?}", address, e);
+            error_response(e)
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/audit_log",
+    params(AuditLogQuery),
+    responses(
+        (status = 200, description = "Privileged actions, newest first", body = AuditLogResponse),
+        (status = 401, description = "Missing or invalid session token or admin key", body = ErrorResponse),
+        (status = 403, description = "Caller lacks the admin role", body = ErrorResponse, example = json!({"error": "Requires the admin role"})),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(("session_token" = []), ("admin_key" = [])),
+    tag = "Admin"
+)]
+pub async fn get_audit_log(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<AuditLogQuery>,
+) -> impl IntoResponse {
+    let result = state
+        .db_pool
+        .get()
+        .map_err(|e| eyre::eyre!("Failed to get database connection: {}", e))
+        .and_then(|mut conn| {
+            list_audit_log(
+                &mut conn,
+                query.actor.as_deref(),
+                query.action.as_deref(),
+                query.limit.unwrap_or(100).clamp(1, 1000),
+            )
+        });
+
+    match result {
+        Ok(entries) => (StatusCode::OK, AxumJson(AuditLogResponse { entries })).into_response(),
+        Err(e) => {
+            eprintln!("Error listing audit log: {:?}", e);
+            error_response(e)
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/manufacturers",
+    request_body = RegisterManufacturerRequest,
+    params(
+        ("chain_id" = Option<u64>, Query, description = "Chain to send the transaction on; the default chain when omitted", example = 84532)
+    ),
+    responses(
+        (status = 200, description = "Manufacturer registered on chain; the indexer records it", body = RegisterManufacturerResponse, example = json!({
+            "transaction_hash": "0xabcdef1234567890abcdef1234567890abcdef1234567890abcdef1234567890"
+        })),
+        (status = 400, description = "Invalid address, empty name or unsupported chain", body = ErrorResponse, example = json!({"error": "Invalid address"})),
+        (status = 401, description = "Missing or invalid session token or admin key", body = ErrorResponse),
+        (status = 403, description = "Caller lacks the operator role, or the backend wallet is not the contract owner", body = ErrorResponse, example = json!({"error": "Requires the operator role"})),
+        (status = 500, description = "Internal server error (e.g., contract interaction failed)", body = ErrorResponse)
+    ),
+    security(("session_token" = []), ("admin_key" = [])),
+    tag = "Admin"
+)]
+pub async fn register_manufacturer(
+    State(state): State<Arc<AppState>>,
+    Extension(actor): Extension<AdminActor>,
+    Query(query): Query<ChainQuery>,
+    Json(request): Json<RegisterManufacturerRequest>,
+) -> impl IntoResponse {
+    let result = register_manufacturer_internal(&state, query.chain_id, &request).await;
+    record_admin_action(
+        &state,
+        &actor,
+        "register_manufacturer",
+        state.chain(query.chain_id).ok().map(|chain| chain.chain_id),
+        Some(&request.address),
+        json!({
+            "name": request.name,
+            "transaction_hash": result.as_ref().ok().map(|response| &response.transaction_hash),
+        }),
+        &result,
+    );
+
+    match result {
+        Ok(response) => (StatusCode::OK, AxumJson(response)).into_response(),
+        Err(e) => {
+            eprintln!("Error registering manufacturer {}: {:?}", request.address, e);
+            error_response(e)
+        }
+    }
+}
+
+async fn register_manufacturer_internal(
+    state: &Arc<AppState>,
+    chain_id: Option<u64>,
+    request: &RegisterManufacturerRequest,
+) -> eyre::Result<RegisterManufacturerResponse> {
+    if request.name.is_empty() {
+        return Err(eyre::eyre!("Manufacturer name cannot be empty"));
+    }
+    let address: Address = request
+        .address
+        .parse()
+        .map_err(|_| eyre::eyre!("Invalid address"))?;
+
+    // Queue the transaction for the relayer
+    let chain = state.chain(chain_id)?;
+    let call = chain
+        .authenticity_contract
+        .manufacturer_registers(request.name.clone(), address);
+    let relayed = {
+        let connection = &mut state
+            .db_pool
+            .get()
+            .map_err(|e| eyre::eyre!("Failed to get database connection: {}", e))?;
+        enqueue_call(connection, chain, "manufacturer_registers", &call, None)?
+    };
+
+    let transaction_hash = wait_for_transaction(state, relayed.id).await?;
+    Ok(RegisterManufacturerResponse { transaction_hash })
+}
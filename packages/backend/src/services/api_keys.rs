@@ -0,0 +1,183 @@
+use axum::{
+    extract::{Json, Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json as AxumJson,
+};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, PooledConnection};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+use utoipa::ToSchema;
+use crate::auth::api_key::{create_api_key, list_api_keys, revoke_api_key};
+use crate::auth::session::AuthenticatedUser;
+use crate::config::app_state::AppState;
+use crate::contract_models::ApiKey;
+use crate::schema::manufacturers;
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreateApiKeyRequest {
+    #[schema(example = "Line 3 MES")]
+    pub name: String,
+    #[schema(example = json!(["certificates:write", "items:read"]))]
+    pub scopes: Vec<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct CreateApiKeyResponse {
+    // Shown once; send it as the `x-api-key` header
+    #[schema(example = "true_3f9a1c2e5b7d9f1a3c5e7b9d1f3a5c7e9b1d3f5a7c9e1b3d5f7a9c1e3b5d7f9a1c")]
+    pub key: String,
+    pub api_key: ApiKey,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ApiKeysResponse {
+    pub api_keys: Vec<ApiKey>,
+}
+
+// Define the error response struct
+#[derive(Serialize, ToSchema)]
+struct ErrorResponse {
+    error: String,
+}
+
+// API keys are managed by the manufacturer's wallet, signed in with SIWE
+fn manufacturer_connection(
+    state: &AppState,
+    user: &AuthenticatedUser,
+) -> eyre::Result<PooledConnection<ConnectionManager<PgConnection>>> {
+    let mut conn = state
+        .db_pool
+        .get()
+        .map_err(|e| eyre::eyre!("Failed to get database connection: {}", e))?;
+    let registered = manufacturers::table
+        .filter(manufacturers::manufacturer_address.eq(&user.address))
+        .filter(manufacturers::is_registered.eq(true))
+        .select(diesel::dsl::count_star())
+        .first::<i64>(&mut conn)
+        .map_err(|e| eyre::eyre!("Failed to query database: {}", e))?
+        > 0;
+    if !registered {
+        return Err(eyre::eyre!("Caller is not a registered manufacturer"));
+    }
+    Ok(conn)
+}
+
+fn error_response(e: eyre::Report) -> axum::response::Response {
+    let (status, message) = match e.to_string().as_str() {
+        s if s.contains("API key name cannot be empty")
+            || s.contains("At least one scope is required")
+            || s.contains("Unknown scope") =>
+        {
+            (StatusCode::BAD_REQUEST, e.to_string())
+        }
+        s if s.contains("Caller is not a registered manufacturer") => {
+            (StatusCode::FORBIDDEN, e.to_string())
+        }
+        s if s.contains("API key not found") => (StatusCode::NOT_FOUND, e.to_string()),
+        _ => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Internal server error: {}", e),
+        ),
+    };
+    (status, AxumJson(json!({"error": message}))).into_response()
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/manufacturer/api_keys",
+    request_body = CreateApiKeyRequest,
+    responses(
+        (status = 200, description = "API key created; the key itself is only returned here", body = CreateApiKeyResponse),
+        (status = 400, description = "Empty name or unknown scope", body = ErrorResponse, example = json!({"error": "Unknown scope: items:write"})),
+        (status = 401, description = "Missing or invalid session token", body = ErrorResponse),
+        (status = 403, description = "Caller is not a registered manufacturer", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(("session_token" = [])),
+    tag = "Manufacturer"
+)]
+pub async fn create_manufacturer_api_key(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Json(request): Json<CreateApiKeyRequest>,
+) -> impl IntoResponse {
+    let result = manufacturer_connection(&state, &user).and_then(|mut conn| {
+        create_api_key(&mut conn, &user.address, &request.name, &request.scopes)
+    });
+
+    match result {
+        Ok((api_key, key)) => (
+            StatusCode::OK,
+            AxumJson(CreateApiKeyResponse { key, api_key }),
+        )
+            .into_response(),
+        Err(e) => {
+            eprintln!("Error creating API key for {}: {:?}", user.address, e);
+            error_response(e)
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/manufacturer/api_keys",
+    responses(
+        (status = 200, description = "The caller's API keys, newest first, including revoked ones", body = ApiKeysResponse),
+        (status = 401, description = "Missing or invalid session token", body = ErrorResponse),
+        (status = 403, description = "Caller is not a registered manufacturer", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(("session_token" = [])),
+    tag = "Manufacturer"
+)]
+pub async fn get_manufacturer_api_keys(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+) -> impl IntoResponse {
+    let result = manufacturer_connection(&state, &user)
+        .and_then(|mut conn| list_api_keys(&mut conn, &user.address));
+
+    match result {
+        Ok(api_keys) => (StatusCode::OK, AxumJson(ApiKeysResponse { api_keys })).into_response(),
+        Err(e) => {
+            eprintln!("Error listing API keys for {}: {:?}", user.address, e);
+            error_response(e)
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/manufacturer/api_keys/{id}/revoke",
+    params(
+        ("id" = i64, Path, description = "API key id", example = 1)
+    ),
+    responses(
+        (status = 200, description = "API key revoked", body = ApiKey),
+        (status = 401, description = "Missing or invalid session token", body = ErrorResponse),
+        (status = 403, description = "Caller is not a registered manufacturer", body = ErrorResponse),
+        (status = 404, description = "No such key for this manufacturer", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(("session_token" = [])),
+    tag = "Manufacturer"
+)]
+pub async fn revoke_manufacturer_api_key(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i64>,
+    user: AuthenticatedUser,
+) -> impl IntoResponse {
+    let result = manufacturer_connection(&state, &user)
+        .and_then(|mut conn| revoke_api_key(&mut conn, &user.address, id));
+
+    match result {
+        Ok(api_key) => (StatusCode::OK, AxumJson(api_key)).into_response(),
+        Err(e) => {
+            eprintln!("Error revoking API key {} for {}: {:?}", id, user.address, e);
+            error_response(e)
+        }
+    }
+}
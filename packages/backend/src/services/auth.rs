@@ -0,0 +1,190 @@
+use axum::{
+    extract::{Json, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json as AxumJson,
+};
+use chrono::Utc;
+use ethers::core::utils::to_checksum;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+use utoipa::ToSchema;
+use crate::auth::session::{consume_nonce, create_session, issue_nonce, revoke_session, AuthenticatedUser};
+use crate::auth::siwe::SiweMessage;
+use crate::config::app_state::AppState;
+
+#[derive(Serialize, ToSchema)]
+pub struct NonceResponse {
+    #[schema(example = "4f2a9c1e8b7d6a5f4e3d2c1b0a9f8e7d")]
+    pub nonce: String,
+    #[schema(example = "2026-10-17T00:10:00Z")]
+    pub expires_at: String,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct SiweVerifyRequest {
+    // The EIP-4361 message exactly as it was signed
+    #[schema(example = "localhost:8080 wants you to sign in with your Ethereum account:\n0x1234567890AbcdEF1234567890aBcdef12345678\n\nSign in to True\n\nURI: http://localhost:8080\nVersion: 1\nChain ID: 84532\nNonce: 4f2a9c1e8b7d6a5f4e3d2c1b0a9f8e7d\nIssued At: 2026-10-17T00:00:00Z")]
+    pub message: String,
+    #[schema(example = "0xad71ff20241e4a798f4416f71411b4343ed8f939c45375d4f78125a84fdfd1fd0ca5f8d0b037cddb02bf2989b2c1b0a9e0845260867160172e07cb0cc452148b1c")]
+    pub signature: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SessionResponse {
+    // Sent as `Authorization: Bearer <token>` to the write endpoints
+    pub token: String,
+    #[schema(example = "0x1234567890AbcdEF1234567890aBcdef12345678")]
+    pub address: String,
+    #[schema(example = 84532)]
+    pub chain_id: u64,
+    #[schema(example = "2026-10-18T00:00:00Z")]
+    pub expires_at: String,
+}
+
+// Define the error response struct
+#[derive(Serialize, ToSchema)]
+struct ErrorResponse {
+    error: String,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/auth/nonce",
+    responses(
+        (status = 200, description = "Single-use nonce to put in the SIWE message", body = NonceResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Auth"
+)]
+pub async fn get_nonce(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let result = state
+        .db_pool
+        .get()
+        .map_err(|e| eyre::eyre!("Failed to get database connection: {}", e))
+        .and_then(|mut conn| issue_nonce(&mut conn));
+
+    match result {
+        Ok(nonce) => (
+            StatusCode::OK,
+            AxumJson(NonceResponse {
+                nonce: nonce.nonce,
+                expires_at: nonce.expires_at,
+            }),
+        )
+            .into_response(),
+        Err(e) => {
+            eprintln!("Error issuing SIWE nonce: {:?}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                AxumJson(json!({"error": format!("Internal server error: {}", e)})),
+            )
+                .into_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/auth/verify",
+    request_body = SiweVerifyRequest,
+    responses(
+        (status = 200, description = "Signature verified; a session was issued for the signer", body = SessionResponse),
+        (status = 400, description = "Malformed message or unsupported chain", body = ErrorResponse, example = json!({"error": "Invalid SIWE message: missing nonce"})),
+        (status = 401, description = "Wrong domain, bad signature, or an expired or used nonce", body = ErrorResponse, example = json!({"error": "Invalid signature: signer does not match address"})),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Auth"
+)]
+pub async fn verify_siwe(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<SiweVerifyRequest>,
+) -> impl IntoResponse {
+    match verify_siwe_internal(&state, &request) {
+        Ok(response) => (StatusCode::OK, AxumJson(response)).into_response(),
+        Err(e) => {
+            eprintln!("Error verifying SIWE login: {:?}", e);
+            let (status, message) = match e.to_string().as_str() {
+                s if s.contains("Invalid SIWE message") => (StatusCode::BAD_REQUEST, e.to_string()),
+                s if s.contains("Invalid signature format") => (StatusCode::BAD_REQUEST, e.to_string()),
+                s if s.contains("Unsupported chain id") => (StatusCode::BAD_REQUEST, e.to_string()),
+                s if s.contains("SIWE domain mismatch")
+                    || s.contains("SIWE message expired")
+                    || s.contains("SIWE message not yet valid")
+                    || s.contains("Invalid signature")
+                    || s.contains("already used nonce") =>
+                {
+                    (StatusCode::UNAUTHORIZED, e.to_string())
+                }
+                _ => (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("Internal server error: {}", e),
+                ),
+            };
+            (status, AxumJson(json!({"error": message}))).into_response()
+        }
+    }
+}
+
+fn verify_siwe_internal(
+    state: &Arc<AppState>,
+    request: &SiweVerifyRequest,
+) -> eyre::Result<SessionResponse> {
+    let message = SiweMessage::parse(&request.message)?;
+    message.validate(&state.siwe_domain, Utc::now())?;
+    state.chain(Some(message.chain_id))?;
+    message.verify_signature(&request.message, &request.signature)?;
+
+    let conn = &mut state
+        .db_pool
+        .get()
+        .map_err(|e| eyre::eyre!("Failed to get database connection: {}", e))?;
+    // Checked last so a request with a bad signature cannot burn someone else's nonce
+    consume_nonce(conn, &message.nonce)?;
+
+    let address = to_checksum(&message.address, None);
+    let (token, expires_at) =
+        create_session(conn, &address, message.chain_id, state.session_ttl)?;
+
+    Ok(SessionResponse {
+        token,
+        address,
+        chain_id: message.chain_id,
+        expires_at,
+    })
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/auth/logout",
+    responses(
+        (status = 200, description = "Session revoked"),
+        (status = 401, description = "Missing or invalid session token", body = ErrorResponse, example = json!({"error": "Invalid or expired session token"})),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(("session_token" = [])),
+    tag = "Auth"
+)]
+pub async fn logout(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+) -> impl IntoResponse {
+    let result = state
+        .db_pool
+        .get()
+        .map_err(|e| eyre::eyre!("Failed to get database connection: {}", e))
+        .and_then(|mut conn| revoke_session(&mut conn, user.session_id));
+
+    match result {
+        Ok(()) => (StatusCode::OK, AxumJson(json!({"revoked": true}))).into_response(),
+        Err(e) => {
+            eprintln!("Error revoking session for {}: {:?}", user.address, e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                AxumJson(json!({"error": format!("Internal server error: {}", e)})),
+            )
+                .into_response()
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::IntoResponse,
+    Json as AxumJson,
+};
+use serde::Serialize;
+use std::sync::Arc;
+use utoipa::ToSchema;
+use crate::config::app_state::AppState;
+
+#[derive(Serialize, ToSchema)]
+pub struct ChainInfo {
+    #[schema(example = 84532)]
+    pub chain_id: u64,
+    #[schema(example = "base_sepolia")]
+    pub name: String,
+    #[schema(example = "0x1234567890AbcdEF1234567890aBcdef12345678")]
+    pub authenticity_address: String,
+    #[schema(example = "0xabcdef1234567890ABCDEF1234567890abcdef12")]
+    pub ownership_address: String,
+    // Writes without a `chain_id` go to this chain
+    pub is_default: bool,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ChainsResponse {
+    pub chains: Vec<ChainInfo>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/chains",
+    responses(
+        (status = 200, description = "Chains this backend signs for and indexes; requests accept a `chain_id` to pick one", body = ChainsResponse)
+    ),
+    tag = "Deployments"
+)]
+pub async fn get_chains(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let default_chain_id = state.default_chain().chain_id;
+    let chains = state
+        .chains
+        .iter()
+        .map(|chain| ChainInfo {
+            chain_id: chain.chain_id,
+            name: chain.name.clone(),
+            authenticity_address: format!("{:?}", chain.authenticity_contract.address()),
+            ownership_address: format!("{:?}", chain.ownership_contract.address()),
+            is_default: chain.chain_id == default_chain_id,
+        })
+        .collect();
+
+    (StatusCode::OK, AxumJson(ChainsResponse { chains })).into_response()
+}
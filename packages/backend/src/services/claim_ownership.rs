@@ -1,35 +1,35 @@
 use axum::{
-    extract::{Json, State},
+    extract::{Json, Query, State},
     http::StatusCode,
     response::IntoResponse,
     Json as AxumJson,
 };
 use diesel::prelude::*;
-use ethers::{
-    prelude::*,
-    types::{Address, U256},
-};
+use ethers::types::Address;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::sync::Arc;
 use utoipa::ToSchema;
-use crate::ownership::ownership_abi::TrueOwnership;
+use crate::auth::session::AuthenticatedUser;
 use crate::config::app_state::AppState;
+use crate::contract_models::{ChainQuery, RelayedTransaction};
+use crate::deployments::active_deployment_id;
+use crate::models::ownership_intent_model::{IntentSignature, ACTION_CLAIM};
+use crate::ownership::intent::{intent_for_chain, verify_and_record_intent};
+use crate::relayer::queue::{check_call, enqueue_call};
+use crate::services::jobs::{job_accepted, JobAcceptedResponse};
 use crate::schema::ownership_codes;
 
+// Returned when the session's wallet is not the one the code was issued to; mapped to 403
+const NOT_TEMP_OWNER: &str = "Caller does not match temp_owner";
+
 // Define the input struct for the endpoint
 #[derive(Deserialize, ToSchema)]
 pub struct ClaimOwnershipRequest {
     #[schema(example = "item123")]
     pub ownership_code: String,
-    #[schema(example = "0x1234567890abcdef1234567890abcdef12345678")]
-    pub caller: String,
-}
-
-// Define the response struct for successful transaction
-#[derive(Serialize, ToSchema)]
-pub struct ClaimOwnershipResponse {
-    transaction_hash: String,
+    // EIP-712 signature of the claim by the session's wallet
+    pub intent: IntentSignature,
 }
 
 // Define the error response struct
@@ -42,33 +42,42 @@ struct ErrorResponse {
     post,
     path = "/api/ownership/claim",
     request_body = ClaimOwnershipRequest,
+    params(
+        ("chain_id" = Option<u64>, Query, description = "Chain to send the transaction on; the session's chain when omitted", example = 84532)
+    ),
     responses(
-        (status = 200, description = "Ownership claimed successfully", body = ClaimOwnershipResponse, example = json!({
-            "transaction_hash": "0xabcdef1234567890abcdef1234567890abcdef1234567890abcdef1234567890"
+        (status = 202, description = "Claim queued; poll the job at `Location` for its transaction and result", body = JobAcceptedResponse, example = json!({
+            "job_id": "job_8c1f0b6e2d7a4c39a5e0f1d2c3b4a596",
+            "status": "queued"
         })),
-        (status = 400, description = "Invalid input (e.g., empty item ID or invalid caller address)", body = ErrorResponse, example = json!({"error": "Invalid caller address"})),
+        (status = 400, description = "Invalid input (e.g., empty item ID or unsupported chain)", body = ErrorResponse, example = json!({"error": "Item ID cannot be empty"})),
+        (status = 401, description = "Missing or invalid session token, or an intent signature that is expired, reused or not from the caller", body = ErrorResponse, example = json!({"error": "Intent signature does not match the caller"})),
         (status = 403, description = "Unauthorized (e.g., caller does not match temp_owner)", body = ErrorResponse, example = json!({"error": "Caller does not match temp_owner"})),
         (status = 404, description = "Item ID not found in ownership_codes", body = ErrorResponse, example = json!({"error": "Item ID not found"})),
+        (status = 429, description = "Gas budget used up for this IP, address or API key; retry after the `Retry-After` seconds", body = ErrorResponse, example = json!({"error": "Too many requests"})),
         (status = 500, description = "Internal server error (e.g., contract interaction or database failure)", body = ErrorResponse, example = json!({"error": "Internal server error: Failed to send transaction"}))
     ),
+    security(("session_token" = [])),
     tag = "Ownership"
 )]
 pub async fn claim_ownership(
     State(state): State<Arc<AppState>>,
+    Query(query): Query<ChainQuery>,
+    user: AuthenticatedUser,
     Json(request): Json<ClaimOwnershipRequest>,
 ) -> impl IntoResponse {
-    match claim_ownership_internal(&state, &request).await {
-        Ok(response) => (
-            StatusCode::OK,
-            AxumJson(response),
-        ).into_response(),
+    match claim_ownership_internal(&state, query.chain_id.or(Some(user.chain_id)), &user.address, &request).await {
+        Ok(job) => job_accepted(job),
         Err(e) => {
             eprintln!("Error claiming ownership for item {}: {:?}", request.ownership_code, e);
             let (status, message) = match e.to_string().as_str() {
+                s if s.contains("Unsupported chain id") => (StatusCode::BAD_REQUEST, e.to_string()),
                 s if s.contains("Item ID cannot be empty") => (StatusCode::BAD_REQUEST, e.to_string()),
                 s if s.contains("Invalid caller address") => (StatusCode::BAD_REQUEST, e.to_string()),
+                s if s.starts_with("Invalid intent") => (StatusCode::BAD_REQUEST, e.to_string()),
+                s if s.starts_with("Intent") => (StatusCode::UNAUTHORIZED, e.to_string()),
                 s if s.contains("Item ID not found") => (StatusCode::NOT_FOUND, e.to_string()),
-                s if s.contains("Caller does not match temp_owner") => (StatusCode::FORBIDDEN, e.to_string()),
+                s if s.contains(NOT_TEMP_OWNER) => (StatusCode::FORBIDDEN, e.to_string()),
                 s if s.contains("Failed to query database") => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
                 s if s.contains("ADDRESS_ZERO") => (StatusCode::BAD_REQUEST, "Caller address cannot be zero".to_string()),
                 s if s.contains("AUTHENTICITY_NOT_SET") => (StatusCode::INTERNAL_SERVER_ERROR, "Authenticity contract not set".to_string()),
@@ -85,29 +94,32 @@ pub async fn claim_ownership(
 
 async fn claim_ownership_internal(
     state: &Arc<AppState>,
+    chain_id: Option<u64>,
+    session_address: &str,
     request: &ClaimOwnershipRequest,
-) -> eyre::Result<ClaimOwnershipResponse> {
-    // Validate item ID and caller
+) -> eyre::Result<RelayedTransaction> {
+    // Validate item ID
     if request.ownership_code.is_empty() {
         return Err(eyre::eyre!("Item ID cannot be empty"));
     }
-    if request.caller.is_empty() {
-        return Err(eyre::eyre!("Caller address cannot be empty"));
-    }
 
-    // Parse caller address
-    let caller: Address = request
-        .caller
+    // The session address was checksummed when the session was issued
+    let caller: Address = session_address
         .parse()
         .map_err(|_| eyre::eyre!("Invalid caller address"))?;
 
+    let chain = state.chain(chain_id)?;
+
     // Query the ownership_codes table to get temp_owner
     let connection = &mut state
         .db_pool
         .get()
         .map_err(|e| eyre::eyre!("Failed to get database connection: {}", e))?;
 
+    let deployment_id = active_deployment_id(connection, chain)?;
     let result = ownership_codes::table
+        .filter(ownership_codes::chain_id.eq(chain.chain_id as i64))
+        .filter(ownership_codes::deployment_id.eq(deployment_id))
         .filter(ownership_codes::item_id.eq(&request.ownership_code))
         .select(ownership_codes::temp_owner)
         .first::<String>(connection)
@@ -117,77 +129,29 @@ async fn claim_ownership_internal(
     let temp_owner = result.ok_or_else(|| eyre::eyre!("Item ID not found"))?;
 
     // Compare caller with temp_owner (case-insensitive to handle checksummed addresses)
-    if request.caller.to_lowercase() != temp_owner.to_lowercase() {
-        return Err(eyre::eyre!("Caller is not the temp_owner"));
+    if !session_address.eq_ignore_ascii_case(&temp_owner) {
+        return Err(eyre::eyre!(NOT_TEMP_OWNER));
     }
 
-    // Get the contract and wallet details
-    let contract = &state.ownership_contract;
-    let wallet_address = contract.client().address();
-
-    // Log addresses for debugging
-    eprintln!("Caller: {:?}", caller);
-    eprintln!("Wallet (msg.sender): {:?}", wallet_address);
-    eprintln!("Temp Owner (from DB): {}", temp_owner);
-
-    // Check wallet balance
-    let balance = contract
-        .client()
-        .get_balance(wallet_address, None)
-        .await
-        .map_err(|e| eyre::eyre!("Failed to check wallet balance: {}", e))?;
-
-    // Estimate gas with a 20% buffer
-    let gas_estimate = contract
-        .new_owner_claim_ownership(
-            request.ownership_code.clone(),
-            caller,
-        )
-        .estimate_gas()
-        .await
-        .map_err(|e| {
-            let revert_reason = e.decode_revert().unwrap_or_else(|| e.to_string());
-            eyre::eyre!("Gas estimation failed: {}", revert_reason)
-        })?;
-    let gas_limit = gas_estimate * 120 / 100;
-
-    // Get current gas price with a fallback
-    let gas_price = contract
-        .client()
-        .get_gas_price()
-        .await
-        .unwrap_or(U256::from(2_000_000_000u64));
-
-    // Check if sufficient funds are available
-    let required_funds = gas_limit * gas_price;
-    if balance < required_funds {
-        return Err(eyre::eyre!(
-            "Insufficient funds: have {} wei, need {} wei",
-            balance, required_funds
-        ));
-    }
-
-    // Prepare and send the transaction
-    let call = contract
-        .new_owner_claim_ownership(request.ownership_code.clone(), caller)
-        .gas(gas_limit)
-        .gas_price(gas_price);
-
-    let pending_tx = call
-        .send()
-        .await
-        .map_err(|e| {
-            let revert_reason = e.decode_revert().unwrap_or_else(|| e.to_string());
-            eyre::eyre!("Failed to send transaction: {}", revert_reason)
-        })?;
-
-    // Await transaction confirmation
-    let receipt = pending_tx
-        .await
-        .map_err(|e| eyre::eyre!("Failed to confirm transaction: {}", e))?
-        .ok_or_else(|| eyre::eyre!("Transaction receipt not found"))?;
-
-    Ok(ClaimOwnershipResponse {
-        transaction_hash: format!("0x{}", hex::encode(receipt.transaction_hash)),
+    let intent = intent_for_chain(
+        chain,
+        ACTION_CLAIM,
+        "",
+        &request.ownership_code,
+        Address::zero(),
+        &request.intent.nonce,
+        request.intent.deadline,
+    )?;
+    let call = chain
+        .ownership_contract
+        .new_owner_claim_ownership(request.ownership_code.clone(), caller);
+    // Before the intent is recorded, so a claim that would revert leaves its nonce unused
+    check_call(&call).await?;
+
+    // Recorded together, so every claim transaction has a signed intent behind it
+    connection.transaction::<_, eyre::Error, _>(|conn| {
+        let intent_id =
+            verify_and_record_intent(conn, chain.chain_id, &intent, &request.intent, session_address)?;
+        enqueue_call(conn, chain, "claim_ownership", &call, Some(intent_id))
     })
-}
\ No newline at end of file
+}
@@ -1,25 +1,33 @@
+use crate::authenticity::eip712_domain::active_domain;
+use crate::config::app_state::AppState;
+use crate::contract_models::ChainQuery;
 use crate::models::certificate_model::{
     Certificate, CertificateData, CustomEIP712Domain, Eip712Object,
 };
 use crate::utility::to_meta_hash;
-use axum::{Json, http::StatusCode};
-use ethers::types::transaction::eip712::Eip712;
+use axum::{Json, extract::{Query, State}, http::StatusCode};
 use ethers::utils::hex::ToHexExt;
 use ethers::utils::keccak256;
 use ethers::{contract::EthEvent, prelude::*, signers::Signer};
 use std::error::Error;
+use std::sync::Arc;
 
 #[utoipa::path(
     post,
     path = "/create_certificate",
     request_body = CertificateData,
+    params(
+        ("chain_id" = Option<u64>, Query, description = "Chain whose authenticity contract the certificate is signed for; the default chain when omitted", example = 84532)
+    ),
     responses(
         (status = 200, description = "EIP-712 object created successfully", body = Eip712Object),
-        (status = 400, description = "Invalid input"),
+        (status = 400, description = "Invalid input or unsupported chain"),
         (status = 500, description = "Internal server error")
     )
 )]
 pub async fn create_certificate(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ChainQuery>,
     Json(cert): Json<CertificateData>,
 ) -> Result<Json<Eip712Object>, StatusCode> {
     // Validate inputs
@@ -40,11 +48,20 @@ pub async fn create_certificate(
         StatusCode::BAD_REQUEST
     })?;
 
-    // Create EIP-712 domain
-    let domain = certificate.domain().map_err(|e| {
+    // Sign under the domain currently active on the chain's contract
+    let chain = state.chain(query.chain_id).map_err(|e| {
+        eprintln!("{:?}", e);
+        StatusCode::BAD_REQUEST
+    })?;
+    let conn = &mut state.db_pool.get().map_err(|e| {
+        eprintln!("Failed to get DB connection: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    let domain = active_domain(conn, chain).map_err(|e| {
         eprintln!("EIP-712 domain error: {:?}", e);
         StatusCode::INTERNAL_SERVER_ERROR
     })?;
+    let certificate = certificate.with_domain(domain.clone());
 
     // Convert to CustomEIP712Domain
     let custom_domain = CustomEIP712Domain::from(domain);
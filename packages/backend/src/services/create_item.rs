@@ -1,16 +1,17 @@
 use crate::ownership::ownership_abi::TrueOwnership;
+use crate::auth::session::AuthenticatedUser;
 use crate::config::app_state::AppState;
+use crate::contract_models::{ChainQuery, RelayedTransaction};
 use crate::ownership::ownership_abi;
+use crate::relayer::queue::{check_call, enqueue_call};
+use crate::services::jobs::{job_accepted, JobAcceptedResponse};
 use axum::{
     Json as AxumJson,
-    extract::{Json, State},
+    extract::{Json, Query, State},
     http::StatusCode,
     response::IntoResponse,
 };
-use ethers::{
-    prelude::*,
-    types::{Address, U256},
-};
+use ethers::types::{Address, U256};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::sync::Arc;
@@ -21,8 +22,6 @@ use utoipa::ToSchema;
 // Define the input struct for the endpoint
 #[derive(Deserialize, ToSchema)]
 pub struct CreateItemRequest {
-    #[schema(example = "0x1234567890abcdef1234567890abcdef12345678")]
-    pub caller: String,
     #[schema(example = "Widget")]
     pub name: String,
     #[schema(example = "item123")]
@@ -39,12 +38,6 @@ pub struct CreateItemRequest {
     pub manufacturer_name: String,
 }
 
-// Define the response struct for successful transaction
-#[derive(Serialize, ToSchema)]
-pub struct CreateItemResponse {
-    transaction_hash: String,
-}
-
 // Define the error response struct
 #[derive(Serialize, ToSchema)]
 struct ErrorResponse {
@@ -55,28 +48,38 @@ struct ErrorResponse {
     post,
     path = "/api/item/create",
     request_body = CreateItemRequest,
+    params(
+        ("chain_id" = Option<u64>, Query, description = "Chain to send the transaction on; the session's chain when omitted", example = 84532)
+    ),
     responses(
-        (status = 200, description = "Item created successfully", body = CreateItemResponse, example = json!({
-            "transaction_hash": "0xabcdef1234567890abcdef1234567890abcdef1234567890abcdef1234567890"
+        (status = 202, description = "Item creation queued; poll the job at `Location` for its transaction and result", body = JobAcceptedResponse, example = json!({
+            "job_id": "job_8c1f0b6e2d7a4c39a5e0f1d2c3b4a596",
+            "status": "queued"
         })),
         (status = 400, description = "Invalid input (e.g., empty fields or invalid addresses)", body = ErrorResponse, example = json!({"error": "Caller address is invalid"})),
+        (status = 401, description = "Missing or invalid session token", body = ErrorResponse, example = json!({"error": "Missing session token"})),
         (status = 403, description = "Unauthorized (e.g., caller not allowed to create item)", body = ErrorResponse, example = json!({"error": "Caller not authorized to create item"})),
+        (status = 429, description = "Gas budget used up for this IP, address or API key; retry after the `Retry-After` seconds", body = ErrorResponse, example = json!({"error": "Too many requests"})),
         (status = 500, description = "Internal server error (e.g., contract interaction failed)", body = ErrorResponse, example = json!({"error": "Internal server error: Failed to send transaction"}))
     ),
+    security(("session_token" = [])),
     tag = "Items"
 )]
 pub async fn create_item(
     State(state): State<Arc<AppState>>,
+    Query(query): Query<ChainQuery>,
+    user: AuthenticatedUser,
     Json(request): Json<CreateItemRequest>,
 ) -> impl IntoResponse {
-    match create_item_internal(&state, &request).await {
-        Ok(response) => (StatusCode::OK, AxumJson(response)).into_response(),
+    match create_item_internal(&state, query.chain_id.or(Some(user.chain_id)), &user.address, &request).await {
+        Ok(job) => job_accepted(job),
         Err(e) => {
             eprintln!(
                 "Error creating item with unique_id {}: {:?}",
                 request.unique_id, e
             );
             let (status, message) = match e.to_string().as_str() {
+                s if s.contains("Unsupported chain id") => (StatusCode::BAD_REQUEST, e.to_string()),
                 s if s.contains("Caller address is invalid") => {
                     (StatusCode::BAD_REQUEST, e.to_string())
                 }
@@ -114,12 +117,11 @@ pub async fn create_item(
 
 async fn create_item_internal(
     state: &Arc<AppState>,
+    chain_id: Option<u64>,
+    session_address: &str,
     request: &CreateItemRequest,
-) -> eyre::Result<CreateItemResponse> {
+) -> eyre::Result<RelayedTransaction> {
     // Validate inputs
-    if request.caller.is_empty() {
-        return Err(eyre::eyre!("Caller address cannot be empty"));
-    }
     if request.name.is_empty() {
         return Err(eyre::eyre!("Certificate name cannot be empty"));
     }
@@ -143,8 +145,7 @@ async fn create_item_internal(
     }
 
     // Parse addresses and metadata hash
-    let caller: Address = request
-        .caller
+    let caller: Address = session_address
         .parse()
         .map_err(|_| eyre::eyre!("Caller address is invalid"))?;
     let owner: Address = "0xF2E7E2f51D7C9eEa9B0313C2eCa12f8e43bd1855"
@@ -156,16 +157,7 @@ async fn create_item_internal(
             .try_into()
             .map_err(|_| eyre::eyre!("Metadata hash must be 32 bytes"))?;
 
-    // Get the contract and wallet details
-    let contract = &state.ownership_contract;
-    let wallet_address = contract.client().address();
-
-    // Check wallet balance
-    let balance = contract
-        .client()
-        .get_balance(wallet_address, None)
-        .await
-        .map_err(|e| eyre::eyre!("Failed to check wallet balance: {}", e))?;
+    let chain = state.chain(chain_id)?;
 
     // Create the certificate struct for the contract call
     let certificate = ownership_abi::Certificate {
@@ -178,57 +170,15 @@ async fn create_item_internal(
         metadata: request.metadata.clone(),
     };
 
-    // Estimate gas with a 20% buffer
-    // let gas_estimate = contract
-    //     .create_item(
-    //         caller,
-    //         certificate.clone(),
-    //         request.manufacturer_name.clone(),
-    //     )
-    //     .estimate_gas()
-    //     .await
-    //     .map_err(|e| {
-    //         let revert_reason = e.decode_revert().unwrap_or_else(|| e.to_string());
-    //         eyre::eyre!("Gas estimation failed: {}", revert_reason)
-    //     })?;
-    // let gas_limit = gas_estimate * 120 / 100;
-
-    // Get current gas price with a fallback
-    let gas_price = contract
-        .client()
-        .get_gas_price()
-        .await
-        .unwrap_or(U256::from(2_000_000_000u64));
-
-    // Check if sufficient funds are available
-    // let required_funds = gas_limit * gas_price;
-    // if balance < required_funds {
-    //     return Err(eyre::eyre!(
-    //         "Insufficient funds: have {} wei, need {} wei",
-    //         balance,
-    //         required_funds
-    //     ));
-    // }
-
-    // Prepare and send the transaction
-    let call = contract
-        .create_item(caller, certificate, request.manufacturer_name.clone())
-        // .gas(gas_limit)
-        .gas_price(gas_price);
-
-    let pending_tx = call.send().await.map_err(|e| {
-        // Attempt to parse revert reason
-        let revert_reason = e.decode_revert().unwrap_or_else(|| e.to_string());
-        eyre::eyre!("Failed to send transaction: {}", revert_reason)
-    })?;
-
-    // Await transaction confirmation
-    let receipt = pending_tx
-        .await
-        .map_err(|e| eyre::eyre!("Failed to confirm transaction: {}", e))?
-        .ok_or_else(|| eyre::eyre!("Transaction receipt not found"))?;
-
-    Ok(CreateItemResponse {
-        transaction_hash: format!("0x{}", hex::encode(receipt.transaction_hash)),
-    })
+    // Queue the transaction for the relayer
+    let call = chain
+        .ownership_contract
+        .create_item(caller, certificate, request.manufacturer_name.clone());
+    check_call(&call).await?;
+
+    let connection = &mut state
+        .db_pool
+        .get()
+        .map_err(|e| eyre::eyre!("Failed to get database connection: {}", e))?;
+    enqueue_call(connection, chain, "create_item", &call, None)
 }
@@ -0,0 +1,64 @@
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::IntoResponse,
+    Json as AxumJson,
+};
+use serde::Serialize;
+use serde_json::json;
+use std::sync::Arc;
+use utoipa::ToSchema;
+use crate::config::app_state::AppState;
+use crate::contract_models::Deployment;
+use crate::deployments::list_deployments;
+
+#[derive(Serialize, ToSchema)]
+pub struct DeploymentsResponse {
+    pub deployments: Vec<Deployment>,
+}
+
+// Define the error response struct
+#[derive(Serialize, ToSchema)]
+struct ErrorResponse {
+    error: String,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/deployments",
+    responses(
+        (status = 200, description = "Contract pairs being indexed; reads accept a `deployment_id` to scope to one", body = DeploymentsResponse, example = json!({
+            "deployments": [{
+                "id": 1,
+                "chain_id": 84532,
+                "authenticity_address": "0x1234567890AbcdEF1234567890aBcdef12345678",
+                "ownership_address": "0xabcdef1234567890ABCDEF1234567890abcdef12",
+                "authenticity_deployment_block": 1000,
+                "ownership_deployment_block": 1001,
+                "is_active": true,
+                "created_at": "2026-10-17T00:00:00Z"
+            }]
+        })),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Deployments"
+)]
+pub async fn get_deployments(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let result = state
+        .db_pool
+        .get()
+        .map_err(|e| eyre::eyre!("Failed to get database connection: {}", e))
+        .and_then(|mut conn| list_deployments(&mut conn));
+
+    match result {
+        Ok(deployments) => (StatusCode::OK, AxumJson(DeploymentsResponse { deployments })).into_response(),
+        Err(e) => {
+            eprintln!("Error listing deployments: {:?}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                AxumJson(json!({"error": format!("Internal server error: {}", e)})),
+            )
+                .into_response()
+        }
+    }
+}
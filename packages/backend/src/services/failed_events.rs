@@ -0,0 +1,151 @@
+use axum::{
+    extract::{Extension, Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json as AxumJson,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+use utoipa::{IntoParams, ToSchema};
+use crate::auth::admin::{record_admin_action, AdminActor};
+use crate::config::app_state::AppState;
+use crate::contract_models::FailedEvent;
+use crate::deployments::all_indexers;
+use crate::indexer::dead_letter::{find_failed_event, list_failed_events, replay_failed_event};
+
+#[derive(Deserialize, ToSchema, IntoParams)]
+pub struct FailedEventsQuery {
+    #[schema(example = 84532)]
+    pub chain_id: Option<i64>,
+    #[schema(example = "pending")]
+    pub status: Option<String>,
+    #[schema(example = 100)]
+    pub limit: Option<i64>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct FailedEventsResponse {
+    pub failed_events: Vec<FailedEvent>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ReplayFailedEventResponse {
+    pub id: i64,
+    pub resolved: bool,
+}
+
+// Define the error response struct
+#[derive(Serialize, ToSchema)]
+struct ErrorResponse {
+    error: String,
+}
+
+pub(crate) fn error_response(e: eyre::Report) -> axum::response::Response {
+    let (status, message) = match e.to_string().as_str() {
+        s if s.contains("Failed event not found") => (StatusCode::NOT_FOUND, e.to_string()),
+        _ => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Internal server error: {}", e),
+        ),
+    };
+    (status, AxumJson(json!({"error": message}))).into_response()
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/failed_events",
+    params(FailedEventsQuery),
+    responses(
+        (status = 200, description = "Events that failed to project", body = FailedEventsResponse),
+        (status = 401, description = "Missing or invalid session token or admin key", body = ErrorResponse, example = json!({"error": "Invalid admin key"})),
+        (status = 403, description = "Caller lacks the support role", body = ErrorResponse, example = json!({"error": "Requires the support role"})),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(("session_token" = []), ("admin_key" = [])),
+    tag = "Admin"
+)]
+pub async fn get_failed_events(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<FailedEventsQuery>,
+) -> impl IntoResponse {
+    let result = state
+        .db_pool
+        .get()
+        .map_err(|e| eyre::eyre!("Failed to get database connection: {}", e))
+        .and_then(|mut conn| {
+            list_failed_events(
+                &mut conn,
+                query.chain_id,
+                query.status.as_deref(),
+                query.limit.unwrap_or(100).clamp(1, 1000),
+            )
+        });
+
+    match result {
+        Ok(failed_events) => (
+            StatusCode::OK,
+            AxumJson(FailedEventsResponse { failed_events }),
+        )
+            .into_response(),
+        Err(e) => {
+            eprintln!("Error listing failed events: {:?}", e);
+            error_response(e)
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/failed_events/{id}/replay",
+    params(
+        ("id" = i64, Path, description = "ID of the failed event", example = 1)
+    ),
+    responses(
+        (status = 200, description = "Replay attempted; `resolved` reports whether it succeeded", body = ReplayFailedEventResponse),
+        (status = 401, description = "Missing or invalid session token or admin key", body = ErrorResponse, example = json!({"error": "Invalid admin key"})),
+        (status = 403, description = "Caller lacks the operator role", body = ErrorResponse, example = json!({"error": "Requires the operator role"})),
+        (status = 404, description = "Failed event not found", body = ErrorResponse, example = json!({"error": "Failed event not found"})),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(("session_token" = []), ("admin_key" = [])),
+    tag = "Admin"
+)]
+pub async fn replay_failed_event_handler(
+    State(state): State<Arc<AppState>>,
+    Extension(actor): Extension<AdminActor>,
+    Path(id): Path<i64>,
+) -> impl IntoResponse {
+    match replay_internal(&state, &actor, id).await {
+        Ok(resolved) => (
+            StatusCode::OK,
+            AxumJson(ReplayFailedEventResponse { id, resolved }),
+        )
+            .into_response(),
+        Err(e) => {
+            eprintln!("Error replaying failed event {}: {:?}", id, e);
+            error_response(e)
+        }
+    }
+}
+
+async fn replay_internal(state: &Arc<AppState>, actor: &AdminActor, id: i64) -> eyre::Result<bool> {
+    let conn = &mut state
+        .db_pool
+        .get()
+        .map_err(|e| eyre::eyre!("Failed to get database connection: {}", e))?;
+    let failed = find_failed_event(conn, id)?.ok_or_else(|| eyre::eyre!("Failed event not found"))?;
+
+    let indexers = all_indexers(state)?;
+    let outcome = replay_failed_event(state, &indexers, conn, &failed).await;
+    record_admin_action(
+        state,
+        actor,
+        "replay_failed_event",
+        failed.chain_id.map(|chain_id| chain_id as u64),
+        Some(&id.to_string()),
+        json!({ "resolved": outcome.as_ref().ok() }),
+        &outcome,
+    );
+    outcome
+}
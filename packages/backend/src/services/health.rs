@@ -0,0 +1,61 @@
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::IntoResponse,
+    Json as AxumJson,
+};
+use serde::Serialize;
+use std::sync::Arc;
+use utoipa::ToSchema;
+use crate::config::app_state::AppState;
+use crate::config::supervisor::TaskStatus;
+
+#[derive(Serialize, ToSchema)]
+pub struct HealthResponse {
+    #[schema(example = "ok")]
+    pub status: String,
+    // Whether this replica holds the indexer lock and runs the listeners
+    pub indexer_leader: bool,
+    // Whether this replica holds the relayer lock and sends queued transactions
+    pub relayer_leader: bool,
+    pub tasks: Vec<TaskStatus>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/health",
+    responses(
+        (status = 200, description = "All background tasks are healthy", body = HealthResponse, example = json!({
+            "status": "ok",
+            "indexer_leader": true,
+            "relayer_leader": true,
+            "tasks": [{
+                "name": "ownership_listener_84532_1",
+                "state": "running",
+                "last_error": null,
+                "restart_count": 0,
+                "consecutive_failures": 0,
+                "started_at": "2026-10-17T00:00:00+00:00",
+                "next_restart_at": null
+            }]
+        })),
+        (status = 503, description = "A background task keeps failing", body = HealthResponse)
+    ),
+    tag = "Health"
+)]
+pub async fn health(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let healthy = state.supervisor.is_healthy();
+    let response = HealthResponse {
+        status: if healthy { "ok" } else { "degraded" }.to_string(),
+        indexer_leader: state.leadership.is_leader(),
+        relayer_leader: state.relayer_leadership.is_leader(),
+        tasks: state.supervisor.statuses(),
+    };
+    let status = if healthy {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    (status, AxumJson(response)).into_response()
+}
@@ -0,0 +1,64 @@
+use axum::{
+    extract::State,
+    http::{header, StatusCode},
+    response::IntoResponse,
+    Json as AxumJson,
+};
+use serde::Serialize;
+use std::sync::Arc;
+use utoipa::ToSchema;
+use crate::config::app_state::AppState;
+use crate::indexer::metrics::StreamStatus;
+
+#[derive(Serialize, ToSchema)]
+pub struct IndexerStatusResponse {
+    pub streams: Vec<StreamStatus>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/indexer/status",
+    responses(
+        (status = 200, description = "Progress of each contract listener", body = IndexerStatusResponse, example = json!({
+            "streams": [{
+                "stream": "ownership",
+                "contract_address": "0x1234567890AbcdEF1234567890aBcdef12345678",
+                "mode": "streaming",
+                "chain_head": 1200,
+                "confirmed_head": 1188,
+                "last_processed_block": 1188,
+                "lag_blocks": 12,
+                "lag_seconds": 24,
+                "events_processed": {"ItemCreated": 42, "OwnershipTransferred": 7},
+                "events_failed": {},
+                "last_error": null,
+                "last_error_at": null,
+                "updated_at": "2026-10-17T00:00:00+00:00"
+            }]
+        }))
+    ),
+    tag = "Health"
+)]
+pub async fn indexer_status(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let response = IndexerStatusResponse {
+        streams: state.indexer_metrics.statuses(),
+    };
+    (StatusCode::OK, AxumJson(response)).into_response()
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/indexer/metrics",
+    responses(
+        (status = 200, description = "Indexer metrics in the Prometheus text format", body = String, content_type = "text/plain")
+    ),
+    tag = "Health"
+)]
+pub async fn indexer_metrics(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        state.indexer_metrics.render_prometheus(),
+    )
+        .into_response()
+}
@@ -0,0 +1,203 @@
+use axum::{
+    extract::{Path, State},
+    http::{header::LOCATION, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Json as AxumJson,
+};
+use diesel::prelude::*;
+use diesel::PgConnection;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use utoipa::ToSchema;
+use crate::config::app_state::AppState;
+use crate::contract_models::{
+    AuthenticitySetting, Item, Manufacturer, OwnershipClaim, RelayedTransaction, UserInfo,
+};
+use crate::relayer::queue::{find_job, STATUS_FAILED};
+use crate::schema::{authenticity_settings, items, manufacturers, ownership_claims, users_info};
+
+// Returned by write endpoints once their contract call is queued
+#[derive(Serialize, ToSchema)]
+pub struct JobAcceptedResponse {
+    #[schema(example = "job_8c1f0b6e2d7a4c39a5e0f1d2c3b4a596")]
+    pub job_id: String,
+    #[schema(example = "queued")]
+    pub status: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct JobResponse {
+    #[schema(example = "job_8c1f0b6e2d7a4c39a5e0f1d2c3b4a596")]
+    pub job_id: String,
+    #[schema(example = 84532)]
+    pub chain_id: i64,
+    // Contract call the job makes
+    #[schema(example = "claim_ownership")]
+    pub kind: String,
+    // `queued`, `submitted`, `mined`, `confirmed` or `failed`
+    #[schema(example = "confirmed")]
+    pub status: String,
+    // Latest hash sent; the mined one once the job is mined
+    pub transaction_hash: Option<String>,
+    pub block_number: Option<i64>,
+    // Times the transaction was signed, counting fee-bumped replacements
+    pub attempts: i32,
+    pub error: Option<String>,
+    pub revert_reason: Option<String>,
+    // What the indexer recorded for the transaction, once it has caught up
+    #[schema(value_type = Option<Object>)]
+    pub result: Option<Value>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+// Define the error response struct
+#[derive(Serialize, ToSchema)]
+struct ErrorResponse {
+    error: String,
+}
+
+// 202 Accepted for a queued job, pointing at where to poll it
+pub fn job_accepted(job: RelayedTransaction) -> Response {
+    let mut response = (
+        StatusCode::ACCEPTED,
+        AxumJson(JobAcceptedResponse {
+            job_id: job.job_id.clone(),
+            status: job.status,
+        }),
+    )
+        .into_response();
+    if let Ok(location) = HeaderValue::from_str(&format!("/api/jobs/{}", job.job_id)) {
+        response.headers_mut().insert(LOCATION, location);
+    }
+    response
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/jobs/{id}",
+    params(
+        ("id" = String, Path, description = "Job id returned by a write endpoint", example = "job_8c1f0b6e2d7a4c39a5e0f1d2c3b4a596")
+    ),
+    responses(
+        (status = 200, description = "Current state of the job", body = JobResponse, example = json!({
+            "job_id": "job_8c1f0b6e2d7a4c39a5e0f1d2c3b4a596",
+            "chain_id": 84532,
+            "kind": "claim_ownership",
+            "status": "confirmed",
+            "transaction_hash": "0xabcdef1234567890abcdef1234567890abcdef1234567890abcdef1234567890",
+            "block_number": 1200,
+            "attempts": 1,
+            "error": null,
+            "revert_reason": null,
+            "result": {
+                "id": 1,
+                "item_id": "item123",
+                "old_owner": "0x0000000000000000000000000000000000000000",
+                "new_owner": "0x1234567890AbcdEF1234567890aBcdef12345678",
+                "tnx_hash": "0xabcdef1234567890abcdef1234567890abcdef1234567890abcdef1234567890",
+                "created_at": "2026-10-17T00:00:00Z"
+            },
+            "created_at": "2026-10-17T00:00:00Z",
+            "updated_at": "2026-10-17T00:00:30Z"
+        })),
+        (status = 404, description = "No job with this id", body = ErrorResponse, example = json!({"error": "Job not found"})),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Jobs"
+)]
+pub async fn get_job(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    match get_job_internal(&state, &id) {
+        Ok(job) => (StatusCode::OK, AxumJson(job)).into_response(),
+        Err(e) => {
+            eprintln!("Error loading job {}: {:?}", id, e);
+            let (status, message) = match e.to_string().as_str() {
+                s if s.contains("Job not found") => (StatusCode::NOT_FOUND, e.to_string()),
+                _ => (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("Internal server error: {}", e),
+                ),
+            };
+            (status, AxumJson(json!({"error": message}))).into_response()
+        }
+    }
+}
+
+fn get_job_internal(state: &AppState, id: &str) -> eyre::Result<JobResponse> {
+    let conn = &mut state
+        .db_pool
+        .get()
+        .map_err(|e| eyre::eyre!("Failed to get database connection: {}", e))?;
+    let job = find_job(conn, id)?.ok_or_else(|| eyre::eyre!("Job not found"))?;
+    let result = projected_result(conn, &job)?;
+
+    Ok(JobResponse {
+        job_id: job.job_id,
+        chain_id: job.chain_id,
+        kind: job.kind,
+        status: job.status,
+        transaction_hash: job.tnx_hash,
+        block_number: job.block_number,
+        attempts: job.attempts,
+        error: job.error,
+        revert_reason: job.revert_reason,
+        result,
+        created_at: job.created_at,
+        updated_at: job.updated_at,
+    })
+}
+
+// The row the indexer projected from the job's transaction, looked up by its hash
+fn projected_result(conn: &mut PgConnection, job: &RelayedTransaction) -> eyre::Result<Option<Value>> {
+    let Some(tnx_hash) = job.tnx_hash.as_deref() else {
+        return Ok(None);
+    };
+    if job.status == STATUS_FAILED {
+        return Ok(None);
+    }
+    let chain_id = Some(job.chain_id);
+
+    let result = match job.kind.as_str() {
+        "claim_ownership" => ownership_claims::table
+            .filter(ownership_claims::tnx_hash.eq(tnx_hash))
+            .filter(ownership_claims::chain_id.eq(chain_id))
+            .select(OwnershipClaim::as_select())
+            .first(conn)
+            .optional()
+            .map(|claim| claim.map(|claim| json!(claim))),
+        "create_item" => items::table
+            .filter(items::tnx_hash.eq(tnx_hash))
+            .filter(items::chain_id.eq(chain_id))
+            .select(Item::as_select())
+            .first(conn)
+            .optional()
+            .map(|item| item.map(|item| json!(item))),
+        "user_registers" => users_info::table
+            .filter(users_info::tnx_hash.eq(tnx_hash))
+            .filter(users_info::chain_id.eq(chain_id))
+            .select(UserInfo::as_select())
+            .first(conn)
+            .optional()
+            .map(|user| user.map(|user| json!(user))),
+        "manufacturer_registers" => manufacturers::table
+            .filter(manufacturers::tnx_hash.eq(tnx_hash))
+            .filter(manufacturers::chain_id.eq(chain_id))
+            .select(Manufacturer::as_select())
+            .first(conn)
+            .optional()
+            .map(|manufacturer| manufacturer.map(|manufacturer| json!(manufacturer))),
+        "set_authenticity" => authenticity_settings::table
+            .filter(authenticity_settings::tnx_hash.eq(tnx_hash))
+            .filter(authenticity_settings::chain_id.eq(chain_id))
+            .select(AuthenticitySetting::as_select())
+            .first(conn)
+            .optional()
+            .map(|setting| setting.map(|setting| json!(setting))),
+        _ => Ok(None),
+    };
+    result.map_err(|e| eyre::eyre!("Failed to query database: {}", e))
+}
@@ -0,0 +1,164 @@
+use axum::{
+    extract::{Extension, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json as AxumJson,
+};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+use utoipa::{IntoParams, ToSchema};
+use crate::auth::api_key::ApiKeyAuth;
+use crate::config::app_state::AppState;
+use crate::contract_models::Item;
+use crate::schema::{certificates, items, ownership_claims};
+
+#[derive(Deserialize, ToSchema, IntoParams)]
+pub struct ManufacturerItemsQuery {
+    #[schema(example = 84532)]
+    pub chain_id: Option<i64>,
+    #[schema(example = 100)]
+    pub limit: Option<i64>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ManufacturerItemsResponse {
+    pub items: Vec<Item>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ManufacturerAnalyticsResponse {
+    #[schema(example = "SAMSUNG")]
+    pub manufacturer_name: String,
+    // Items created on chain under the manufacturer's name
+    #[schema(example = 120)]
+    pub items: i64,
+    // Certificates saved with the manufacturer as owner
+    #[schema(example = 150)]
+    pub certificates: i64,
+    // Ownership transfers of the manufacturer's items
+    #[schema(example = 42)]
+    pub ownership_transfers: i64,
+}
+
+// Define the error response struct
+#[derive(Serialize, ToSchema)]
+struct ErrorResponse {
+    error: String,
+}
+
+fn internal_error(e: eyre::Report) -> axum::response::Response {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        AxumJson(json!({"error": format!("Internal server error: {}", e)})),
+    )
+        .into_response()
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/manufacturer/items",
+    params(ManufacturerItemsQuery),
+    responses(
+        (status = 200, description = "Items created by the API key's manufacturer, newest first", body = ManufacturerItemsResponse),
+        (status = 401, description = "Missing, invalid or revoked API key", body = ErrorResponse, example = json!({"error": "Missing API key"})),
+        (status = 403, description = "API key lacks the items:read scope", body = ErrorResponse, example = json!({"error": "API key is missing the items:read scope"})),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(("api_key" = [])),
+    tag = "Manufacturer"
+)]
+pub async fn get_manufacturer_items(
+    State(state): State<Arc<AppState>>,
+    Extension(api_key): Extension<ApiKeyAuth>,
+    Query(query): Query<ManufacturerItemsQuery>,
+) -> impl IntoResponse {
+    let result = state
+        .db_pool
+        .get()
+        .map_err(|e| eyre::eyre!("Failed to get database connection: {}", e))
+        .and_then(|mut conn| {
+            let mut items_query = items::table
+                .filter(items::manufacturer.eq(&api_key.manufacturer_name))
+                .order(items::id.desc())
+                .limit(query.limit.unwrap_or(100).clamp(1, 1000))
+                .select(Item::as_select())
+                .into_boxed();
+            if let Some(chain_id) = query.chain_id {
+                items_query = items_query.filter(items::chain_id.eq(chain_id));
+            }
+            items_query
+                .load(&mut conn)
+                .map_err(|e| eyre::eyre!("Failed to fetch items: {}", e))
+        });
+
+    match result {
+        Ok(items) => (StatusCode::OK, AxumJson(ManufacturerItemsResponse { items })).into_response(),
+        Err(e) => {
+            eprintln!(
+                "Error loading items for {} (API key {}): {:?}",
+                api_key.manufacturer_name, api_key.key_id, e
+            );
+            internal_error(e)
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/manufacturer/analytics",
+    responses(
+        (status = 200, description = "Totals for the API key's manufacturer", body = ManufacturerAnalyticsResponse),
+        (status = 401, description = "Missing, invalid or revoked API key", body = ErrorResponse, example = json!({"error": "Missing API key"})),
+        (status = 403, description = "API key lacks the analytics:read scope", body = ErrorResponse, example = json!({"error": "API key is missing the analytics:read scope"})),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(("api_key" = [])),
+    tag = "Manufacturer"
+)]
+pub async fn get_manufacturer_analytics(
+    State(state): State<Arc<AppState>>,
+    Extension(api_key): Extension<ApiKeyAuth>,
+) -> impl IntoResponse {
+    let result = state
+        .db_pool
+        .get()
+        .map_err(|e| eyre::eyre!("Failed to get database connection: {}", e))
+        .and_then(|mut conn| {
+            let manufacturer_items = items::table
+                .filter(items::manufacturer.eq(&api_key.manufacturer_name));
+            let item_count = manufacturer_items
+                .count()
+                .get_result::<i64>(&mut conn)
+                .map_err(|e| eyre::eyre!("Failed to count items: {}", e))?;
+            let certificate_count = certificates::table
+                .filter(certificates::owner.eq(&api_key.manufacturer_address))
+                .count()
+                .get_result::<i64>(&mut conn)
+                .map_err(|e| eyre::eyre!("Failed to count certificates: {}", e))?;
+            let transfer_count = ownership_claims::table
+                .filter(ownership_claims::item_id.eq_any(manufacturer_items.select(items::item_id)))
+                .count()
+                .get_result::<i64>(&mut conn)
+                .map_err(|e| eyre::eyre!("Failed to count ownership transfers: {}", e))?;
+
+            Ok(ManufacturerAnalyticsResponse {
+                manufacturer_name: api_key.manufacturer_name.clone(),
+                items: item_count,
+                certificates: certificate_count,
+                ownership_transfers: transfer_count,
+            })
+        });
+
+    match result {
+        Ok(analytics) => (StatusCode::OK, AxumJson(analytics)).into_response(),
+        Err(e) => {
+            eprintln!(
+                "Error loading analytics for {} (API key {}): {:?}",
+                api_key.manufacturer_name, api_key.key_id, e
+            );
+            internal_error(e)
+        }
+    }
+}
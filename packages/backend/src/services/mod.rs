@@ -5,4 +5,16 @@ pub mod qr_code;
 pub mod register_user;
 pub mod set_autheticity;
 pub mod claim_ownership;
-pub mod create_item;
\ No newline at end of file
+pub mod create_item;
+pub mod failed_events;
+pub mod health;
+pub mod indexer_status;
+pub mod deployments;
+pub mod chains;
+pub mod reconciliations;
+pub mod auth;
+pub mod ownership_intent;
+pub mod api_keys;
+pub mod manufacturer_api;
+pub mod admin;
+pub mod jobs;
\ No newline at end of file
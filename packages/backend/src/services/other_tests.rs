@@ -1,80 +1,15 @@
 use crate::authenticity::authenticity_abi::{true_authenticity};
 use crate::config::app_state::AppState;
-use crate::models::certificate_model::RegInput;
 use crate::models::certificate_model::{Certificate, CertificateData};
-use crate::models::emitted_events::ManufacturerRegistered;
 use crate::schema::manufacturers;
 use axum::{Json, extract::Path, extract::State, http::StatusCode};
 use diesel::prelude::*;
-use ethabi::RawLog;
 use ethers::types::transaction::eip712::Eip712;
-use ethers::{contract::EthEvent, prelude::*, signers::Signer, types::Signature};
+use ethers::{prelude::*, signers::Signer, types::Signature};
 use std::error::Error;
 use std::sync::Arc;
 //============== FOR TEST ONLY => WILL BE REMOVED WHEN DONE =======================
 
-#[utoipa::path(
-    post,
-    path = "/manufacturer_registers", //TODO: Registration will be done from the frontend
-    request_body = RegInput,
-    responses(
-        (status = 200, description = "Signature verification result", body = String),
-        (status = 400, description = "Invalid input"),
-        (status = 500, description = "Internal server error")
-    )
-)]
-pub async fn manufacturer_registers(
-    State(state): State<Arc<AppState>>,
-    Json(input): Json<RegInput>,
-) -> Result<Json<String>, StatusCode> {
-    // Fetch the contract's owner
-    let contract = state.authenticity_contract.clone(); // Authenticity::new(state.authenticity_contract, state.eth_client.clone());
-
-    let receipt = contract
-        .manufacturer_registers(input.name, input.address.parse().unwrap())
-        .send()
-        .await
-        .map_err(|e| {
-            eprintln!("Transaction send error: {:?}", e.to_string());
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?
-        .await
-        .map_err(|e| {
-            eprintln!("Transaction confirmation error: {:?}", e);
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?
-        .ok_or(StatusCode::INTERNAL_SERVER_ERROR)?;
-
-    if receipt.status != Some(1.into()) {
-        return Err(StatusCode::BAD_REQUEST);
-    }
-
-    let mut event_res = ManufacturerRegistered::init();
-
-    for log in receipt.logs.iter() {
-        let raw_log = RawLog {
-            topics: log.topics.clone(),
-            data: log.data.clone().to_vec(),
-        };
-
-        if let Ok(event) = <ManufacturerRegistered as EthEvent>::decode_log(&raw_log) {
-            event_res = ManufacturerRegistered::new(
-                event.manufacturer_address,
-                event.manufacturer_name.clone(),
-            );
-
-            println!("📦 Manufacturer Registers:");
-            println!("    Manufacturer Address: {}", event.manufacturer_address);
-            println!("    Manufacturer Name: {:?}", event.manufacturer_name);
-        }
-    }
-
-    Ok(Json(format!(
-        "Manufacturer Address: {:?}, Manufacturer Name: {:?}",
-        event_res.manufacturer_address, event_res.manufacturer_name
-    )))
-}
-
 #[utoipa::path( //TODO: This was just used to check the contract status
     get,
     path = "/get_owner/{address}",
@@ -92,7 +27,7 @@ pub async fn get_owner(
     State(state): State<Arc<AppState>>,
     Path(input): Path<String>,
 ) -> Result<Json<Address>, StatusCode> {
-    let contract = state.authenticity_contract.clone(); //Authenticity::new(state.authenticity_contract, state.eth_client.clone());
+    let contract = state.default_chain().authenticity_contract.clone(); //Authenticity::new(state.authenticity_contract, state.eth_client.clone());
 
     // let owner = input.parse().unwrap();
     let owner = input;
@@ -150,6 +85,7 @@ pub async fn verify_signature(
     // accessing the wallet from SignerMiddleware
     // Sign the certificate
     let signature: Signature = state
+        .default_chain()
         .authenticity_contract
         .client()
         // .eth_client
@@ -169,7 +105,7 @@ pub async fn verify_signature(
 
     // Call create_item
     // let contract = Authenticity::new(state.authenticity_contract, state.eth_client.clone());
-    let contract = state.authenticity_contract.clone();
+    let contract = state.default_chain().authenticity_contract.clone();
 
     eprintln!("Address: {:?}", contract.client().signer().address());
     let bytes_sign = Bytes::from(signature.to_vec());
@@ -211,6 +147,7 @@ pub async fn generate_signature(
         .map_err(|_| StatusCode::BAD_REQUEST)?;
 
     let signature: Signature = state
+        .default_chain()
         .authenticity_contract
         .client()
         .signer()
@@ -0,0 +1,133 @@
+use crate::config::app_state::AppState;
+use crate::contract_models::ChainQuery;
+use crate::models::certificate_model::{CustomEIP712Domain, Eip712Object};
+use crate::models::ownership_intent_model::{
+    OwnershipIntent, ACTION_CLAIM, ACTION_GENERATE, ACTION_REVOKE,
+};
+use crate::ownership::intent::intent_for_chain;
+use axum::{
+    extract::{Json, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json as AxumJson,
+};
+use chrono::Utc;
+use ethers::types::Address;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+// How long a prepared intent can be signed and submitted for
+const INTENT_TTL_SECS: u64 = 600;
+
+#[derive(Deserialize, ToSchema)]
+pub struct OwnershipIntentRequest {
+    // `generate`, `revoke` or `claim`
+    #[schema(example = "generate")]
+    pub action: String,
+    // Item to generate a code for
+    #[schema(example = "item123")]
+    #[serde(default)]
+    pub item_id: String,
+    // Code to revoke or claim
+    #[serde(default)]
+    pub ownership_code: String,
+    // Recipient of a generated code
+    #[schema(example = "0xabcdef1234567890abcdef1234567890abcdef12")]
+    pub temp_owner: Option<String>,
+    // A random nonce is chosen when omitted
+    pub nonce: Option<String>,
+    // Ten minutes from now when omitted
+    pub deadline: Option<u64>,
+}
+
+// Define the error response struct
+#[derive(Serialize, ToSchema)]
+struct ErrorResponse {
+    error: String,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/ownership/intent",
+    request_body = OwnershipIntentRequest,
+    params(
+        ("chain_id" = Option<u64>, Query, description = "Chain whose ownership contract the intent is signed for; the default chain when omitted", example = 84532)
+    ),
+    responses(
+        (status = 200, description = "EIP-712 typed data to sign with eth_signTypedData_v4", body = Eip712Object),
+        (status = 400, description = "Unknown action, missing fields or unsupported chain", body = ErrorResponse, example = json!({"error": "Unknown intent action: transfer"}))
+    ),
+    tag = "Ownership"
+)]
+pub async fn prepare_ownership_intent(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ChainQuery>,
+    Json(request): Json<OwnershipIntentRequest>,
+) -> impl IntoResponse {
+    match prepare_ownership_intent_internal(&state, query.chain_id, &request) {
+        Ok(intent) => (
+            StatusCode::OK,
+            AxumJson(Eip712Object {
+                domain: CustomEIP712Domain::from(intent.domain.clone()),
+                types: OwnershipIntent::types(),
+                value: intent.value(),
+            }),
+        )
+            .into_response(),
+        Err(e) => {
+            eprintln!("Error preparing {} intent: {:?}", request.action, e);
+            (StatusCode::BAD_REQUEST, AxumJson(json!({"error": e.to_string()}))).into_response()
+        }
+    }
+}
+
+fn prepare_ownership_intent_internal(
+    state: &Arc<AppState>,
+    chain_id: Option<u64>,
+    request: &OwnershipIntentRequest,
+) -> eyre::Result<OwnershipIntent> {
+    let chain = state.chain(chain_id)?;
+
+    // Only the fields the action signs are kept; the rest are signed empty
+    let (item_id, ownership_code, temp_owner) = match request.action.as_str() {
+        ACTION_GENERATE => {
+            let temp_owner: Address = request
+                .temp_owner
+                .as_deref()
+                .ok_or_else(|| eyre::eyre!("temp_owner is required to generate a code"))?
+                .parse()
+                .map_err(|_| eyre::eyre!("Invalid temp_owner address"))?;
+            if request.item_id.is_empty() {
+                return Err(eyre::eyre!("item_id is required to generate a code"));
+            }
+            (request.item_id.as_str(), "", temp_owner)
+        }
+        ACTION_REVOKE | ACTION_CLAIM => {
+            if request.ownership_code.is_empty() {
+                return Err(eyre::eyre!("ownership_code is required to {} a code", request.action));
+            }
+            ("", request.ownership_code.as_str(), Address::zero())
+        }
+        action => return Err(eyre::eyre!("Unknown intent action: {}", action)),
+    };
+
+    let nonce = request
+        .nonce
+        .clone()
+        .unwrap_or_else(|| rand::random::<u64>().to_string());
+    let deadline = request
+        .deadline
+        .unwrap_or(Utc::now().timestamp().max(0) as u64 + INTENT_TTL_SECS);
+
+    intent_for_chain(
+        chain,
+        &request.action,
+        item_id,
+        ownership_code,
+        temp_owner,
+        &nonce,
+        deadline,
+    )
+}
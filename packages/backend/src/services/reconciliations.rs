@@ -0,0 +1,66 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json as AxumJson,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use utoipa::{IntoParams, ToSchema};
+use crate::config::app_state::AppState;
+use crate::contract_models::ReconciliationRun;
+use crate::indexer::reconcile::list_reconciliation_runs;
+use crate::services::failed_events::error_response;
+
+#[derive(Deserialize, ToSchema, IntoParams)]
+pub struct ReconciliationRunsQuery {
+    #[schema(example = 84532)]
+    pub chain_id: Option<i64>,
+    #[schema(example = 20)]
+    pub limit: Option<i64>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ReconciliationRunsResponse {
+    pub runs: Vec<ReconciliationRun>,
+}
+
+// Define the error response struct
+#[derive(Serialize, ToSchema)]
+struct ErrorResponse {
+    error: String,
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/reconciliations",
+    params(ReconciliationRunsQuery),
+    responses(
+        (status = 200, description = "Reconciliation reports, newest first", body = ReconciliationRunsResponse),
+        (status = 401, description = "Missing or invalid session token or admin key", body = ErrorResponse, example = json!({"error": "Invalid admin key"})),
+        (status = 403, description = "Caller lacks the support role", body = ErrorResponse, example = json!({"error": "Requires the support role"})),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(("session_token" = []), ("admin_key" = [])),
+    tag = "Admin"
+)]
+pub async fn get_reconciliation_runs(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ReconciliationRunsQuery>,
+) -> impl IntoResponse {
+    let result = state
+        .db_pool
+        .get()
+        .map_err(|e| eyre::eyre!("Failed to get database connection: {}", e))
+        .and_then(|mut conn| {
+            list_reconciliation_runs(&mut conn, query.chain_id, query.limit.unwrap_or(20).clamp(1, 100))
+        });
+
+    match result {
+        Ok(runs) => (StatusCode::OK, AxumJson(ReconciliationRunsResponse { runs })).into_response(),
+        Err(e) => {
+            eprintln!("Error listing reconciliation runs: {:?}", e);
+            error_response(e)
+        }
+    }
+}
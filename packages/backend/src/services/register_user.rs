@@ -1,21 +1,19 @@
 
 use axum::{
-    extract::{ State},
+    extract::{Query, State},
     http::StatusCode,
     response::IntoResponse,
     Json,
 };
-use ethers::{
-    prelude::*,
-    providers::{Http, Provider},
-    types::{Address, U256},
-};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::sync::Arc;
 use utoipa::ToSchema;
 use crate::ownership::ownership_abi::TrueOwnership;
 use crate::config::app_state::AppState;
+use crate::contract_models::{ChainQuery, RelayedTransaction};
+use crate::relayer::queue::{check_call, enqueue_call};
+use crate::services::jobs::{job_accepted, JobAcceptedResponse};
 
 // Define the input struct for the endpoint
 #[derive(Deserialize, ToSchema)]
@@ -24,12 +22,6 @@ pub struct UserRegisterRequest {
     pub username: String,
 }
 
-// Define the response struct for successful registration
-#[derive(Serialize, ToSchema)]
-pub struct UserRegisterResponse {
-    transaction_hash: String,
-}
-
 // Define the error response struct
 #[derive(Serialize, ToSchema)]
 struct ErrorResponse {
@@ -40,27 +32,31 @@ struct ErrorResponse {
     post,
     path = "/api/user/register",
     request_body = UserRegisterRequest,
+    params(
+        ("chain_id" = Option<u64>, Query, description = "Chain to send the transaction on; the default chain when omitted", example = 84532)
+    ),
     responses(
-        (status = 200, description = "User registered successfully", body = UserRegisterResponse, example = json!({
-            "transaction_hash": "0xabcdef1234567890abcdef1234567890abcdef1234567890abcdef1234567890"
+        (status = 202, description = "Registration queued; poll the job at `Location` for its transaction and result", body = JobAcceptedResponse, example = json!({
+            "job_id": "job_8c1f0b6e2d7a4c39a5e0f1d2c3b4a596",
+            "status": "queued"
         })),
         (status = 400, description = "Invalid input (e.g., invalid username)", body = ErrorResponse, example = json!({"error": "Username cannot be empty"})),
+        (status = 429, description = "Gas budget used up for this IP, address or API key; retry after the `Retry-After` seconds", body = ErrorResponse, example = json!({"error": "Too many requests"})),
         (status = 500, description = "Internal server error (e.g., contract interaction failed)", body = ErrorResponse, example = json!({"error": "Internal server error: Failed to send transaction"}))
     ),
     tag = "Users"
 )]
 pub async fn user_register(
     State(state): State<Arc<AppState>>,
+    Query(query): Query<ChainQuery>,
     Json(request): Json<UserRegisterRequest>,
 ) -> impl IntoResponse {
-    match register_user_internal(&state, &request).await {
-        Ok(response) => (
-            StatusCode::OK,
-            Json(response),
-        ).into_response(),
+    match register_user_internal(&state, query.chain_id, &request).await {
+        Ok(job) => job_accepted(job),
         Err(e) => {
             eprintln!("Error registering user with username {}: {:?}", request.username, e);
             let (status, message) = match e.to_string().as_str() {
+                s if s.contains("Unsupported chain id") => (StatusCode::BAD_REQUEST, e.to_string()),
                 s if s.contains("Username cannot be empty") => (StatusCode::BAD_REQUEST, e.to_string()),
                 s if s.contains("Username too long") => (StatusCode::BAD_REQUEST, e.to_string()),
                 s if s.contains("ADDRESS_ZERO") => (StatusCode::BAD_REQUEST, "Caller address cannot be zero".to_string()),
@@ -77,8 +73,9 @@ pub async fn user_register(
 
 async fn register_user_internal(
     state: &Arc<AppState>,
+    chain_id: Option<u64>,
     request: &UserRegisterRequest,
-) -> eyre::Result<UserRegisterResponse> {
+) -> eyre::Result<RelayedTransaction> {
     // Validate username
     if request.username.is_empty() {
         return Err(eyre::eyre!("Username cannot be empty"));
@@ -87,62 +84,14 @@ async fn register_user_internal(
         return Err(eyre::eyre!("Username too long (max 32 characters)"));
     }
 
-    // Get the contract and wallet details
-    let contract = &state.ownership_contract;
-    let wallet_address = contract.client().address();
-
-    // Check wallet balance
-    let balance = contract
-        .client()
-        .get_balance(wallet_address, None)
-        .await
-        .map_err(|e| eyre::eyre!("Failed to check wallet balance: {}", e))?;
-
-    // Estimate gas with a 20% buffer
-    let gas_estimate = contract
-        .user_registers(request.username.clone())
-        .estimate_gas()
-        .await
-        .map_err(|e| eyre::eyre!("Gas estimation failed: {}", e))?;
-    let gas_limit = gas_estimate * 120 / 100;
-
-    // Get current gas price with a fallback
-    let gas_price = contract
-        .client()
-        .get_gas_price()
-        .await
-        .unwrap_or(U256::from(2_000_000_000u64));
-
-    // Check if sufficient funds are available
-    let required_funds = gas_limit * gas_price;
-    if balance < required_funds {
-        return Err(eyre::eyre!(
-            "Insufficient funds: have {} wei, need {} wei",
-            balance, required_funds
-        ));
-    }
-
-    // Prepare and send the transaction
-    let call = contract
-        .user_registers(request.username.clone())
-        .gas(gas_limit)
-        .gas_price(gas_price);
+    // Queue the transaction for the relayer
+    let chain = state.chain(chain_id)?;
+    let call = chain.ownership_contract.user_registers(request.username.clone());
+    check_call(&call).await?;
 
-    let pending_tx = call
-        .send()
-        .await
-        .map_err(|e| {
-            // Attempt to parse revert reason
-            eyre::eyre!("Failed to send transaction: {}", e.to_string())
-        })?;
-
-    // Await transaction confirmation
-    let receipt = pending_tx
-        .await
-        .map_err(|e| eyre::eyre!("Failed to confirm transaction: {}", e))?
-        .ok_or_else(|| eyre::eyre!("Transaction receipt not found"))?;
-
-    Ok(UserRegisterResponse {
-        transaction_hash: format!("0x{}", hex::encode(receipt.transaction_hash)),
-    })
-}
\ No newline at end of file
+    let connection = &mut state
+        .db_pool
+        .get()
+        .map_err(|e| eyre::eyre!("Failed to get database connection: {}", e))?;
+    enqueue_call(connection, chain, "user_registers", &call, None)
+}
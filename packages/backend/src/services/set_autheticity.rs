@@ -1,15 +1,14 @@
+use crate::auth::admin::{record_admin_action, AdminActor};
 use crate::config::app_state::AppState;
+use crate::contract_models::ChainQuery;
+use crate::relayer::queue::{enqueue_call, wait_for_transaction};
 use axum::{
-    extract::{Json, State},
+    extract::{Extension, Json, Query, State},
     http::StatusCode,
     response::IntoResponse,
     Json as AxumJson,
 };
-use ethers::{
-    prelude::*
-    ,
-    types::{Address, U256},
-};
+use ethers::types::Address;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::sync::Arc;
@@ -36,23 +35,41 @@ struct ErrorResponse {
 
 #[utoipa::path(
     post,
-    path = "/api/set_authenticity",
+    path = "/admin/authenticity",
     request_body = SetAuthenticityRequest,
+    params(
+        ("chain_id" = Option<u64>, Query, description = "Chain to send the transaction on; the default chain when omitted", example = 84532)
+    ),
     responses(
         (status = 200, description = "Authenticity address set successfully", body = SetAuthenticityResponse, example = json!({
             "transaction_hash": "0xabcdef1234567890abcdef1234567890abcdef1234567890abcdef1234567890"
         })),
         (status = 400, description = "Invalid input (e.g., invalid authenticity address)", body = ErrorResponse, example = json!({"error": "Invalid authenticity address"})),
-        (status = 403, description = "Caller is not the contract owner", body = ErrorResponse, example = json!({"error": "Caller is not the contract owner"})),
+        (status = 401, description = "Missing or invalid session token or admin key", body = ErrorResponse, example = json!({"error": "Invalid admin key"})),
+        (status = 403, description = "Caller lacks the operator role, or the backend wallet is not the contract owner", body = ErrorResponse, example = json!({"error": "Requires the operator role"})),
         (status = 500, description = "Internal server error (e.g., contract interaction failed)", body = ErrorResponse, example = json!({"error": "Internal server error: Failed to send transaction"}))
     ),
-    tag = "Ownership"
+    security(("session_token" = []), ("admin_key" = [])),
+    tag = "Admin"
 )]
 pub async fn set_authenticity(
     State(state): State<Arc<AppState>>,
+    Extension(actor): Extension<AdminActor>,
+    Query(query): Query<ChainQuery>,
     Json(request): Json<SetAuthenticityRequest>,
 ) -> impl IntoResponse {
-    match set_authenticity_internal(&state, &request).await {
+    let result = set_authenticity_internal(&state, query.chain_id, &request).await;
+    record_admin_action(
+        &state,
+        &actor,
+        "set_authenticity",
+        state.chain(query.chain_id).ok().map(|chain| chain.chain_id),
+        Some(&request.authenticity_address),
+        json!({ "transaction_hash": result.as_ref().ok().map(|response| &response.transaction_hash) }),
+        &result,
+    );
+
+    match result {
         Ok(response) => (
             StatusCode::OK,
             AxumJson(response),
@@ -60,6 +77,7 @@ pub async fn set_authenticity(
         Err(e) => {
             eprintln!("Error setting authenticity address {}: {:?}", request.authenticity_address, e);
             let (status, message) = match e.to_string().as_str() {
+                s if s.contains("Unsupported chain id") => (StatusCode::BAD_REQUEST, e.to_string()),
                 s if s.contains("Invalid authenticity address") => (StatusCode::BAD_REQUEST, e.to_string()),
                 s if s.contains("ONLY_OWNER") => (StatusCode::FORBIDDEN, "Caller is not the contract owner".to_string()),
                 _ => (
@@ -74,6 +92,7 @@ pub async fn set_authenticity(
 
 async fn set_authenticity_internal(
     state: &Arc<AppState>,
+    chain_id: Option<u64>,
     request: &SetAuthenticityRequest,
 ) -> eyre::Result<SetAuthenticityResponse> {
     // Validate authenticity address
@@ -82,63 +101,17 @@ async fn set_authenticity_internal(
         .parse()
         .map_err(|_| eyre::eyre!("Invalid authenticity address"))?;
 
-    // Get the contract and wallet details
-    let contract = &state.ownership_contract;
-    let wallet_address = contract.client().address();
-
-    // Check wallet balance
-    let balance = contract
-        .client()
-        .get_balance(wallet_address, None)
-        .await
-        .map_err(|e| eyre::eyre!("Failed to check wallet balance: {}", e))?;
-
-    // Estimate gas with a 20% buffer
-    let gas_estimate = contract
-        .set_authenticity(authenticity_address)
-        .estimate_gas()
-        .await
-        .map_err(|e| eyre::eyre!("Gas estimation failed: {}", e))?;
-    let gas_limit = gas_estimate * 120 / 100;
-
-    // Get current gas price with a fallback
-    let gas_price = contract
-        .client()
-        .get_gas_price()
-        .await
-        .unwrap_or(U256::from(2_000_000_000u64));
+    // Queue the transaction for the relayer
+    let chain = state.chain(chain_id)?;
+    let call = chain.ownership_contract.set_authenticity(authenticity_address);
+    let relayed = {
+        let connection = &mut state
+            .db_pool
+            .get()
+            .map_err(|e| eyre::eyre!("Failed to get database connection: {}", e))?;
+        enqueue_call(connection, chain, "set_authenticity", &call, None)?
+    };
 
-    // Check if sufficient funds are available
-    let required_funds = gas_limit * gas_price;
-    if balance < required_funds {
-        return Err(eyre::eyre!(
-            "Insufficient funds: have {} wei, need {} wei",
-            balance, required_funds
-        ));
-    }
-
-    // Prepare and send the transaction
-    let call = contract
-        .set_authenticity(authenticity_address)
-        .gas(gas_limit)
-        .gas_price(gas_price);
-
-    let pending_tx = call
-        .send()
-        .await
-        .map_err(|e| {
-            // Attempt to parse revert reason
-            let revert_reason = e.to_string();
-            eyre::eyre!("Failed to send transaction: {}", revert_reason)
-        })?;
-
-    // Await transaction confirmation
-    let receipt = pending_tx
-        .await
-        .map_err(|e| eyre::eyre!("Failed to confirm transaction: {}", e))?
-        .ok_or_else(|| eyre::eyre!("Transaction receipt not found"))?;
-
-    Ok(SetAuthenticityResponse {
-        transaction_hash: format!("0x{}", hex::encode(receipt.transaction_hash)),
-    })
-}
\ No newline at end of file
+    let transaction_hash = wait_for_transaction(state, relayed.id).await?;
+    Ok(SetAuthenticityResponse { transaction_hash })
+}
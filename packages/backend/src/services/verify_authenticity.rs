@@ -1,8 +1,16 @@
 use crate::models::certificate_model::{
     Certificate, SignedCertificate,
 };
+use crate::authenticity::eip712_domain::{load_domains, DOMAIN_ACTIVE, DOMAIN_LEGACY};
 use crate::config::app_state::AppState;
-use axum::{extract::State, http::StatusCode, Json};
+use crate::config::chains::ChainConfig;
+use crate::contract_models::ChainQuery;
+use crate::models::certificate_model::chain_domain;
+use axum::{extract::{Query, State}, http::StatusCode, Json};
+use ethers::core::utils::to_checksum;
+use ethers::types::transaction::eip712::EIP712Domain;
+use serde::Serialize;
+use utoipa::ToSchema;
 use ethers::types::transaction::eip712::Eip712;
 use ethers::{
     contract::EthEvent,
@@ -16,21 +24,73 @@ use validator::Validate;
 use crate::authenticity;
 use crate::authenticity::authenticity_abi::{true_authenticity, TrueAuthenticity};
 
+#[derive(Serialize, ToSchema)]
+pub struct VerificationResult {
+    pub address: String,
+    pub manufacturer_name: String,
+    // `active`, or `legacy_domain` when the certificate was signed under a previous domain
+    #[schema(example = "active")]
+    pub domain_status: String,
+    pub domain_version: Option<String>,
+    #[schema(example = 84532)]
+    pub chain_id: u64,
+}
+
+// Domains a certificate may have been signed under on a chain, the active one first. Falls
+// back to the configured domain until the indexer has recorded one.
+fn candidate_domains(
+    state: &AppState,
+    chain: &ChainConfig,
+) -> Result<Vec<(EIP712Domain, &'static str)>, StatusCode> {
+    let conn = &mut state.db_pool.get().map_err(|e| {
+        eprintln!("Failed to get DB connection: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    let verifying_contract = chain.authenticity_contract.address();
+    let contract_address = to_checksum(&verifying_contract, None);
+    let records = load_domains(conn, chain.chain_id, &contract_address).map_err(|e| {
+        eprintln!("Failed to load EIP-712 domains: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    if records.is_empty() {
+        return Ok(vec![(chain_domain(chain.chain_id, verifying_contract), DOMAIN_ACTIVE)]);
+    }
+    records
+        .iter()
+        .map(|record| {
+            let status = if record.is_active { DOMAIN_ACTIVE } else { DOMAIN_LEGACY };
+            record.to_domain().map(|domain| (domain, status)).map_err(|e| {
+                eprintln!("Invalid stored EIP-712 domain {}: {:?}", record.id, e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })
+        })
+        .collect()
+}
 
 #[utoipa::path(
     post,
     path = "/verify_authenticity",
     request_body = SignedCertificate,
+    params(
+        ("chain_id" = Option<u64>, Query, description = "Chain the certificate was signed for; the default chain when omitted", example = 84532)
+    ),
     responses(
-        (status = 200, description = "Signature verification result", body = String),
-        (status = 400, description = "Invalid input"),
+        (status = 200, description = "Signature verification result", body = VerificationResult),
+        (status = 400, description = "Invalid input or unsupported chain"),
         (status = 500, description = "Internal server error")
     )
 )]
 pub async fn verify_authenticity(
     State(state): State<Arc<AppState>>,
+    Query(query): Query<ChainQuery>,
     Json(cert): Json<SignedCertificate>,
-) -> Result<Json<(String, String)>, StatusCode> {
+) -> Result<Json<VerificationResult>, StatusCode> {
+    let chain = state.chain(query.chain_id).map_err(|e| {
+        eprintln!("{:?}", e);
+        StatusCode::BAD_REQUEST
+    })?;
+
     let certificate: Certificate = cert
         .clone()
         .try_into()
@@ -55,29 +115,42 @@ pub async fn verify_authenticity(
 
     eprintln!("Signature: {:?}", signature);
 
-    // Compute the EIP-712 digest
-    let digest = certificate.encode_eip712().map_err(|e| {
-        eprintln!("EIP-712 encoding error: {:?}", e);
-        StatusCode::INTERNAL_SERVER_ERROR
-    })?;
+    // Find the domain the certificate was signed under: the recovered signer only matches the
+    // owner for the right one
+    let mut matched = None;
+    for (domain, status) in candidate_domains(&state, chain)? {
+        let version = domain.version.clone();
+        let digest = certificate.clone().with_domain(domain).encode_eip712().map_err(|e| {
+            eprintln!("EIP-712 encoding error: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
 
-    //this caused big issue until I removed it
-    // let digest = hash_message(digest); // Prefix with \x19Ethereum Signed Message
+        //this caused big issue until I removed it
+        // let digest = hash_message(digest); // Prefix with \x19Ethereum Signed Message
 
-    eprintln!("Digest: {:?}", digest);
+        eprintln!("Digest ({}): {:?}", status, digest);
 
-    // Recover the signer
-    let signer = signature.recover(digest).map_err(|e| {
-        eprintln!("Signer recovery error: {:?}", e);
-        StatusCode::INTERNAL_SERVER_ERROR
-    })?;
+        // Recover the signer
+        let signer = signature.recover(digest).map_err(|e| {
+            eprintln!("Signer recovery error: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+        // very important: double check to make sure the certificate owner is the signer of the signature
+        if signer == certificate.owner {
+            matched = Some((signer, status, version));
+            break;
+        }
+    }
+    let Some((signer, domain_status, domain_version)) = matched else {
+        eprintln!("Signature does not match the certificate owner under any known domain");
+        return Err(StatusCode::BAD_REQUEST);
+    };
 
     eprintln!("Signer: {:?}", signer);
-    // very important: double check to make sure the certificate owner is the signer of the signature
-    assert_eq!(signer, certificate.owner);
     // Fetch the contract's owner
     // let contract = Authenticity::new(state.authenticity_contract, state.eth_client.clone());
-    let contract = state.authenticity_contract.clone();
+    let contract = chain.authenticity_contract.clone();
 
     let manufacturer: true_authenticity::Manufacturer = contract
         .get_manufacturer(signer)
@@ -91,14 +164,26 @@ pub async fn verify_authenticity(
     eprintln!("Manufacturer Address: {:?}", manufacturer.manufacturer_address);
     // Verify the signer matches the owner
     if signer == manufacturer.manufacturer_address {
-        Ok(Json((manufacturer.manufacturer_address.to_string(), manufacturer.name)))
+        Ok(Json(VerificationResult {
+            address: manufacturer.manufacturer_address.to_string(),
+            manufacturer_name: manufacturer.name,
+            domain_status: domain_status.to_string(),
+            domain_version,
+            chain_id: chain.chain_id,
+        }))
         //     format!(
         //     "Signature is valid! Signed by owner: {:?}",
         //     signer
         // ))
         // )
     } else {
-        Ok(Json( (signer.to_string(), manufacturer.name) ))
+        Ok(Json(VerificationResult {
+            address: signer.to_string(),
+            manufacturer_name: manufacturer.name,
+            domain_status: domain_status.to_string(),
+            domain_version,
+            chain_id: chain.chain_id,
+        }))
         //     format!(
         //     "Signature is invalid. Recovered signer: {:?}, expected owner: {:?}",
         //     signer, manufacturer.manufacturer_address
@@ -12,6 +12,10 @@ use utoipa::{OpenApi, ToSchema};
 #[derive(Deserialize, ToSchema)]
 pub struct SyncPayload {
     address: String,
+    // Only return rows indexed from this deployment
+    deployment_id: Option<i32>,
+    // Only return rows indexed from this chain; every chain when omitted
+    chain_id: Option<i64>,
 }
 #[derive(Serialize, ToSchema, Queryable, Selectable)]
 #[diesel(table_name = manufacturers)]
@@ -57,17 +61,35 @@ pub async fn sync(
         eyre::eyre!("Failed to get DB connection: {}", e)
     }).unwrap();
     // Query user
-    let user: Option<User> = users_info
+    let mut user_query = users_info
         .filter(user_address.eq(&payload.address))
         .select(User::as_select())
+        .into_boxed();
+    if let Some(deployment) = payload.deployment_id {
+        user_query = user_query.filter(crate::schema::users_info::deployment_id.eq(deployment));
+    }
+    if let Some(chain) = payload.chain_id {
+        user_query = user_query.filter(crate::schema::users_info::chain_id.eq(chain));
+    }
+    let user: Option<User> = user_query
         .first(conn)
         .optional()
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
     // Query manufacturer
-    let manufacturer: Option<Manufacturer> = manufacturers
+    let mut manufacturer_query = manufacturers
         .filter(manufacturer_address.eq(&payload.address))
         .select(Manufacturer::as_select())
+        .into_boxed();
+    if let Some(deployment) = payload.deployment_id {
+        manufacturer_query =
+            manufacturer_query.filter(crate::schema::manufacturers::deployment_id.eq(deployment));
+    }
+    if let Some(chain) = payload.chain_id {
+        manufacturer_query =
+            manufacturer_query.filter(crate::schema::manufacturers::chain_id.eq(chain));
+    }
+    let manufacturer: Option<Manufacturer> = manufacturer_query
         .first(conn)
         .optional()
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
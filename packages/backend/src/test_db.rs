@@ -0,0 +1,49 @@
+use crate::config::server::MIGRATIONS;
+use crate::indexer::checkpoint::OWNERSHIP_STREAM;
+use crate::indexer::contract_indexer::ContractIndexer;
+use crate::indexer::log_fetcher::LogFetcher;
+use crate::indexer::log_source::LogSource;
+use diesel::{Connection, PgConnection};
+use diesel_migrations::MigrationHarness;
+use ethers::types::{Address, U64};
+use std::sync::Mutex;
+
+// Tests that need Postgres run against TEST_DATABASE_URL and are skipped when it is not set.
+// The database is migrated once per run and every connection stays inside a test transaction,
+// so nothing a test writes is kept.
+static MIGRATED: Mutex<bool> = Mutex::new(false);
+
+pub fn test_connection() -> Option<PgConnection> {
+    let Ok(url) = std::env::var("TEST_DATABASE_URL") else {
+        eprintln!("TEST_DATABASE_URL is not set, skipping");
+        return None;
+    };
+
+    {
+        let mut migrated = MIGRATED.lock().unwrap_or_else(|e| e.into_inner());
+        if !*migrated {
+            let mut conn = PgConnection::establish(&url).expect("Failed to connect to test database");
+            conn.run_pending_migrations(MIGRATIONS)
+                .expect("Failed to migrate test database");
+            *migrated = true;
+        }
+    }
+
+    let mut conn = PgConnection::establish(&url).expect("Failed to connect to test database");
+    conn.begin_test_transaction()
+        .expect("Failed to begin test transaction");
+    Some(conn)
+}
+
+// An ownership indexer with no handlers and a deployment block of 100, for tests that only
+// need its stream bookkeeping
+pub fn test_indexer(chain_id: u64) -> ContractIndexer {
+    ContractIndexer::new(
+        OWNERSHIP_STREAM,
+        chain_id,
+        Address::repeat_byte(1),
+        Some(U64::from(100)),
+        LogSource::Rpc(LogFetcher::new(10)),
+        |_, _, _, _| Ok(()),
+    )
+}
@@ -1,5 +1,7 @@
 // use ethers::prelude::{Bytes,Signature};
+use chrono::{SecondsFormat, Utc};
 use ethers::utils::keccak256;
+use std::time::Duration;
 // use crate::services::certificate_service::Authenticity.sol;
 
 // Convert Signature to Bytes
@@ -7,6 +9,11 @@ use ethers::utils::keccak256;
 //     Bytes::from(signature.to_vec())
 // }
 
+// Fixed-width UTC timestamps, so they can be compared as text in SQL
+pub(crate) fn timestamp_after(delay: Duration) -> String {
+    (Utc::now() + delay).to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
 pub(crate) fn to_meta_hash(metadata: &Vec<String>) -> [u8; 32] {
     let metadata_bytes = ethers::abi::encode(&[ethers::abi::Token::Array(
         metadata